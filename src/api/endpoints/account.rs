@@ -1,6 +1,81 @@
-use cloudflare::endpoints::account::Settings;
+use cloudflare::endpoints::account;
+use cloudflare::framework::endpoint::{Endpoint, Method};
+use cloudflare::framework::response::ApiResult;
 use chrono::{DateTime, Utc};
 
+use crate::http::Paginated;
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct MemberVec {
+    pub members: Vec<Member>
+}
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct SubscriptionVec {
+    pub subscriptions: Vec<Subscription>
+}
+
+/// Account Details
+/// https://api.cloudflare.com/#accounts-account-details
+pub struct AccountDetails<'a> {
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<Account> for AccountDetails<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}", self.identifier)
+    }
+}
+
+/// List Members
+/// https://api.cloudflare.com/#account-members-list-members
+pub struct ListAccountMembers<'a> {
+    pub account_identifier: &'a str,
+    pub params: ListAccountMembersParams,
+}
+
+impl<'a> Endpoint<MemberVec, ListAccountMembersParams> for ListAccountMembers<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/members", self.account_identifier)
+    }
+    fn query(&self) -> Option<ListAccountMembersParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ListAccountMembersParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+}
+
+/// List Subscriptions
+/// https://api.cloudflare.com/#account-subscriptions-list-subscriptions
+pub struct ListAccountSubscriptions<'a> {
+    pub account_identifier: &'a str,
+}
+
+impl<'a> Endpoint<SubscriptionVec> for ListAccountSubscriptions<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/subscriptions", self.account_identifier)
+    }
+}
+
 /// Cloudflare Accounts
 /// An Account is the root object which owns other resources such as zones, load balancers and billing details.
 /// https://api.cloudflare.com/#accounts-properties
@@ -15,3 +90,88 @@ pub struct Account {
     /// describes when the account was created
     pub created_on: Option<DateTime<Utc>>,
 }
+
+/// Version of `cloudflare::endpoints::account::Settings` with readable fields
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    /// Indicates whether or not membership in this account requires that Two-Factor Authentication is enabled
+    pub enforce_twofactor: bool,
+    /// Whether new zones use the account's custom name servers by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_account_custom_ns_by_default: Option<bool>,
+}
+
+/// A user with access to an account
+/// https://api.cloudflare.com/#account-members-properties
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Member {
+    /// Membership identifier tag
+    pub id: String,
+    pub user: MemberUser,
+    /// A member's status in the account, e.g. `accepted` or `pending`
+    pub status: String,
+    /// Roles assigned to this member
+    pub roles: Vec<Role>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MemberUser {
+    pub id: String,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    #[serde(default)]
+    pub two_factor_authentication_enabled: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Role {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// A billing subscription of the account
+/// https://api.cloudflare.com/#account-subscriptions-properties
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Subscription {
+    /// Subscription identifier tag
+    pub id: String,
+    pub rate_plan: Option<RatePlan>,
+    /// The price of the subscription that will be billed
+    pub price: Option<f64>,
+    /// The monetary unit in which pricing information is displayed
+    pub currency: Option<String>,
+    /// How often the subscription is renewed automatically, e.g. `monthly`
+    pub frequency: Option<String>,
+    /// The state that the subscription is in, e.g. `Paid`
+    pub state: Option<String>,
+    /// The end of the current period and also when the next billing is due
+    pub current_period_end: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RatePlan {
+    pub id: String,
+    pub public_name: Option<String>,
+}
+
+impl ApiResult for Account {}
+impl ApiResult for MemberVec {}
+impl ApiResult for SubscriptionVec {}
+
+impl Paginated for Vec<account::Account> {
+    type Item = account::Account;
+
+    fn into_items(self) -> Vec<account::Account> {
+        self
+    }
+}
+
+impl Paginated for MemberVec {
+    type Item = Member;
+
+    fn into_items(self) -> Vec<Member> {
+        self.members
+    }
+}
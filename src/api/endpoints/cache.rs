@@ -25,12 +25,20 @@ pub struct Cache {
     pub id: String
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, Default)]
 pub struct PurgeCacheParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub purge_everything: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub files: Option<Vec<String>>,
+    /// Cache-Tag header values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// URL prefixes without scheme, e.g. `www.mydomain.com/images`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefixes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosts: Option<Vec<String>>,
 }
 
 impl ApiResult for Cache {}
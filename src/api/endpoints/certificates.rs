@@ -0,0 +1,137 @@
+use cloudflare::framework::endpoint::{Endpoint, Method};
+use cloudflare::framework::response::ApiResult;
+
+use crate::http::Paginated;
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct CertificateVec {
+    pub certificates: Vec<Certificate>
+}
+
+/// List Origin CA Certificates of a zone
+/// https://api.cloudflare.com/#origin-ca-list-certificates
+pub struct ListCertificates {
+    pub params: ListCertificatesParams,
+}
+
+impl Endpoint<CertificateVec, ListCertificatesParams> for ListCertificates {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        "certificates".to_string()
+    }
+    fn query(&self) -> Option<ListCertificatesParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ListCertificatesParams {
+    pub zone_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+}
+
+/// Get an Origin CA Certificate
+/// https://api.cloudflare.com/#origin-ca-get-certificate
+pub struct CertificateDetails<'a> {
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<Certificate> for CertificateDetails<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("certificates/{}", self.identifier)
+    }
+}
+
+/// Create an Origin CA Certificate from a CSR
+/// https://api.cloudflare.com/#origin-ca-create-certificate
+pub struct CreateCertificate {
+    pub params: CreateCertificateParams,
+}
+
+impl Endpoint<Certificate, (), CreateCertificateParams> for CreateCertificate {
+    fn method(&self) -> Method {
+        Method::Post
+    }
+    fn path(&self) -> String {
+        "certificates".to_string()
+    }
+    fn body(&self) -> Option<CreateCertificateParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CreateCertificateParams {
+    /// Hostnames or wildcards covered by the certificate
+    pub hostnames: Vec<String>,
+    /// Validity in days
+    pub requested_validity: u32,
+    /// `origin-rsa` or `origin-ecc`
+    pub request_type: String,
+    /// PEM encoded certificate signing request
+    pub csr: String,
+}
+
+/// Revoke an Origin CA Certificate
+/// https://api.cloudflare.com/#origin-ca-revoke-certificate
+pub struct RevokeCertificate<'a> {
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<RevokedCertificate> for RevokeCertificate<'a> {
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+    fn path(&self) -> String {
+        format!("certificates/{}", self.identifier)
+    }
+}
+
+/// Certificate signed by Cloudflare's Origin CA, only trusted by Cloudflare's edge
+/// https://api.cloudflare.com/#origin-ca-properties
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Certificate {
+    /// Certificate identifier tag
+    pub id: String,
+    /// PEM encoded certificate
+    pub certificate: String,
+    /// Hostnames or wildcards covered by the certificate
+    pub hostnames: Vec<String>,
+    /// When the certificate expires, e.g. `2034-09-13 10:04:00 +0000 UTC`
+    pub expires_on: String,
+    /// `origin-rsa` or `origin-ecc`
+    pub request_type: String,
+    /// Validity in days
+    pub requested_validity: u32,
+    /// PEM encoded certificate signing request
+    #[serde(default)]
+    pub csr: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RevokedCertificate {
+    pub id: String,
+    pub revoked_at: Option<String>,
+}
+
+impl ApiResult for Certificate {}
+impl ApiResult for CertificateVec {}
+impl ApiResult for RevokedCertificate {}
+
+impl Paginated for CertificateVec {
+    type Item = Certificate;
+
+    fn into_items(self) -> Vec<Certificate> {
+        self.certificates
+    }
+}
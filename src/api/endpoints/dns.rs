@@ -1,5 +1,65 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use chrono::DateTime;
+use chrono::offset::Utc;
+use cloudflare::endpoints::dns::ListDnsRecordsOrder;
 use cloudflare::framework::endpoint::{Endpoint, Method};
-use cloudflare::endpoints::dns::DnsRecord;
+use cloudflare::framework::{OrderDirection, SearchMatch};
+use cloudflare::framework::response::ApiResult;
+
+use crate::http::Paginated;
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct DnsRecordVec {
+    pub records: Vec<DnsRecord>
+}
+
+/// List DNS Records
+/// https://api.cloudflare.com/#dns-records-for-a-zone-list-dns-records
+pub struct ListDnsRecords<'a> {
+    pub zone_identifier: &'a str,
+    pub params: ListDnsRecordsParams,
+}
+impl<'a> Endpoint<DnsRecordVec, ListDnsRecordsParams> for ListDnsRecords<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/dns_records", self.zone_identifier)
+    }
+    fn query(&self) -> Option<ListDnsRecordsParams> {
+        Some(self.params.clone())
+    }
+}
+
+/// Version of `cloudflare::endpoints::dns::ListDnsRecordsParams` filtering by type, content and proxy
+/// status. The type is a plain string as the tagged `DnsContent` can't be sent in a query string.
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ListDnsRecordsParams {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub record_type: Option<String>,
+    /// Exact name of the records
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Exact content of the records
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxied: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<ListDnsRecordsOrder>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<OrderDirection>,
+    /// Whether records match all the filters or any of them
+    #[serde(rename = "match", skip_serializing_if = "Option::is_none")]
+    pub search_match: Option<SearchMatch>,
+}
 
 /// DNS Record Details
 /// https://api.cloudflare.com/#dns-records-for-a-zone-dns-record-details
@@ -15,3 +75,305 @@ impl<'a> Endpoint<DnsRecord> for DnsRecordDetails<'a> {
         format!("zones/{}/dns_records/{}", self.zone_identifier, self.identifier)
     }
 }
+
+/// Create DNS Record
+/// https://api.cloudflare.com/#dns-records-for-a-zone-create-dns-record
+pub struct CreateDnsRecord<'a> {
+    pub zone_identifier: &'a str,
+    pub params: DnsRecordParams<'a>,
+}
+impl<'a> Endpoint<DnsRecord, (), DnsRecordParams<'a>> for CreateDnsRecord<'a> {
+    fn method(&self) -> Method {
+        Method::Post
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/dns_records", self.zone_identifier)
+    }
+    fn body(&self) -> Option<DnsRecordParams<'a>> {
+        Some(self.params.clone())
+    }
+}
+
+/// Update DNS Record
+/// https://api.cloudflare.com/#dns-records-for-a-zone-update-dns-record
+pub struct UpdateDnsRecord<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+    pub params: DnsRecordParams<'a>,
+}
+impl<'a> Endpoint<DnsRecord, (), DnsRecordParams<'a>> for UpdateDnsRecord<'a> {
+    fn method(&self) -> Method {
+        Method::Put
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/dns_records/{}", self.zone_identifier, self.identifier)
+    }
+    fn body(&self) -> Option<DnsRecordParams<'a>> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DnsRecordParams<'a> {
+    /// Time to live for DNS record. Value of 1 is 'automatic'
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+    /// Whether the record is receiving the performance and security benefits of Cloudflare
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxied: Option<bool>,
+    /// DNS record name
+    pub name: &'a str,
+    /// Type of the DNS record that also holds the record value
+    #[serde(flatten)]
+    pub content: DnsContent,
+}
+
+/// Type of the DNS record, along with the associated value.
+/// Record types with several fields carry them in `data`, which the API also returns
+/// flattened into a `content` string that is ignored here.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum DnsContent {
+    A { content: Ipv4Addr },
+    AAAA { content: Ipv6Addr },
+    CNAME { content: String },
+    NS { content: String },
+    PTR { content: String },
+    MX { content: String, priority: u16 },
+    TXT { content: String },
+    SPF { content: String },
+    OPENPGPKEY { content: String },
+    SRV { data: SrvData },
+    CAA { data: CaaData },
+    TLSA { data: TlsaData },
+    SMIMEA { data: TlsaData },
+    LOC { data: LocData },
+    HTTPS { data: SvcbData },
+    SVCB { data: SvcbData },
+    DS { data: DsData },
+    DNSKEY { data: DnskeyData },
+    CERT { data: CertData },
+    URI { data: UriData, priority: u16 },
+    NAPTR { data: NaptrData },
+    SSHFP { data: SshfpData },
+}
+
+/// Every record type supported by Cloudflare
+pub const RECORD_TYPES: [&str; 22] = [
+    "A", "AAAA", "CNAME", "NS", "PTR", "MX", "TXT", "SPF", "OPENPGPKEY", "SRV", "CAA", "TLSA",
+    "SMIMEA", "LOC", "HTTPS", "SVCB", "DS", "DNSKEY", "CERT", "URI", "NAPTR", "SSHFP",
+];
+
+impl DnsContent {
+    pub fn record_type(&self) -> &'static str {
+        match self {
+            DnsContent::A { .. } => "A",
+            DnsContent::AAAA { .. } => "AAAA",
+            DnsContent::CNAME { .. } => "CNAME",
+            DnsContent::NS { .. } => "NS",
+            DnsContent::PTR { .. } => "PTR",
+            DnsContent::MX { .. } => "MX",
+            DnsContent::TXT { .. } => "TXT",
+            DnsContent::SPF { .. } => "SPF",
+            DnsContent::OPENPGPKEY { .. } => "OPENPGPKEY",
+            DnsContent::SRV { .. } => "SRV",
+            DnsContent::CAA { .. } => "CAA",
+            DnsContent::TLSA { .. } => "TLSA",
+            DnsContent::SMIMEA { .. } => "SMIMEA",
+            DnsContent::LOC { .. } => "LOC",
+            DnsContent::HTTPS { .. } => "HTTPS",
+            DnsContent::SVCB { .. } => "SVCB",
+            DnsContent::DS { .. } => "DS",
+            DnsContent::DNSKEY { .. } => "DNSKEY",
+            DnsContent::CERT { .. } => "CERT",
+            DnsContent::URI { .. } => "URI",
+            DnsContent::NAPTR { .. } => "NAPTR",
+            DnsContent::SSHFP { .. } => "SSHFP",
+        }
+    }
+
+    /// Priority of the record types that have one
+    pub fn priority(&self) -> Option<u16> {
+        match self {
+            DnsContent::MX { priority, .. } | DnsContent::URI { priority, .. } => Some(*priority),
+            DnsContent::SRV { data } => Some(data.priority),
+            DnsContent::HTTPS { data } | DnsContent::SVCB { data } => Some(data.priority),
+            _ => None,
+        }
+    }
+}
+
+/// Service record. The service and protocol are part of the record name, e.g. `_sip._tcp.example.com`
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct SrvData {
+    pub priority: u16,
+    pub weight: u16,
+    pub port: u16,
+    pub target: String,
+    /// Only returned by older API versions
+    #[serde(default, skip_serializing)]
+    pub service: Option<String>,
+    /// Only returned by older API versions
+    #[serde(default, skip_serializing)]
+    pub proto: Option<String>,
+}
+
+/// Certification Authority Authorization
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct CaaData {
+    pub flags: u8,
+    /// One of `issue`, `issuewild` or `iodef`
+    pub tag: String,
+    pub value: String,
+}
+
+/// TLSA and SMIMEA certificate association
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct TlsaData {
+    pub usage: u8,
+    pub selector: u8,
+    pub matching_type: u8,
+    /// Certificate association data, hex encoded
+    pub certificate: String,
+}
+
+/// Geographical location
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct LocData {
+    pub lat_degrees: u8,
+    pub lat_minutes: u8,
+    pub lat_seconds: f64,
+    /// `N` or `S`
+    pub lat_direction: String,
+    pub long_degrees: u8,
+    pub long_minutes: u8,
+    pub long_seconds: f64,
+    /// `E` or `W`
+    pub long_direction: String,
+    /// Altitude in meters
+    pub altitude: f64,
+    /// Size of the location in meters
+    pub size: f64,
+    /// Horizontal precision in meters
+    pub precision_horz: f64,
+    /// Vertical precision in meters
+    pub precision_vert: f64,
+}
+
+/// HTTPS and SVCB service binding
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct SvcbData {
+    pub priority: u16,
+    pub target: String,
+    /// SvcParams, e.g. `alpn="h3,h2" ipv4hint="192.0.2.1"`
+    pub value: String,
+}
+
+/// Delegation signer
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct DsData {
+    pub key_tag: u16,
+    pub algorithm: u8,
+    pub digest_type: u8,
+    /// Hex encoded digest
+    pub digest: String,
+}
+
+/// DNS public key
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct DnskeyData {
+    pub flags: u16,
+    /// Always 3
+    pub protocol: u8,
+    pub algorithm: u8,
+    /// Base64 encoded public key
+    pub public_key: String,
+}
+
+/// Certificate
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct CertData {
+    #[serde(rename = "type")]
+    pub cert_type: u16,
+    pub key_tag: u16,
+    pub algorithm: u8,
+    /// Base64 encoded certificate
+    pub certificate: String,
+}
+
+/// Uniform Resource Identifier. The priority is a field of the record itself.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct UriData {
+    pub weight: u16,
+    pub target: String,
+}
+
+/// Naming authority pointer
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct NaptrData {
+    pub order: u16,
+    pub preference: u16,
+    pub flags: String,
+    pub service: String,
+    pub regex: String,
+    pub replacement: String,
+}
+
+/// SSH public key fingerprint
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct SshfpData {
+    pub algorithm: u8,
+    #[serde(rename = "type")]
+    pub fingerprint_type: u8,
+    /// Hex encoded fingerprint
+    pub fingerprint: String,
+}
+
+/// Extra Cloudflare-specific information about the record
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Meta {
+    /// Will exist if Cloudflare automatically added this DNS record during initial setup.
+    pub auto_added: bool,
+}
+
+/// Version of `cloudflare::endpoints::dns::DnsRecord` covering every record type
+/// https://api.cloudflare.com/#dns-records-for-a-zone-properties
+#[derive(Deserialize, Serialize, Debug)]
+pub struct DnsRecord {
+    /// DNS record identifier tag
+    pub id: String,
+    /// DNS record name
+    pub name: String,
+    /// Type of the DNS record that also holds the record value
+    #[serde(flatten)]
+    pub content: DnsContent,
+    /// Time to live for DNS record. Value of 1 is 'automatic'
+    pub ttl: u32,
+    /// Whether the record is receiving the performance and security benefits of Cloudflare
+    pub proxied: bool,
+    /// Whether the record can be proxied by Cloudflare or not
+    pub proxiable: bool,
+    /// Whether this record can be modified/deleted (true means it's managed by Cloudflare)
+    pub locked: bool,
+    /// Zone identifier tag
+    pub zone_id: String,
+    /// The domain of the record
+    pub zone_name: String,
+    /// When the record was created
+    pub created_on: DateTime<Utc>,
+    /// When the record was last modified
+    pub modified_on: DateTime<Utc>,
+    /// Extra Cloudflare-specific information about the record
+    pub meta: Meta,
+}
+
+impl ApiResult for DnsRecord {}
+impl ApiResult for DnsRecordVec {}
+
+impl Paginated for DnsRecordVec {
+    type Item = DnsRecord;
+
+    fn into_items(self) -> Vec<DnsRecord> {
+        self.records
+    }
+}
@@ -0,0 +1,472 @@
+use chrono::DateTime;
+use chrono::offset::Utc;
+use cloudflare::framework::endpoint::{Endpoint, Method};
+use cloudflare::framework::response::ApiResult;
+
+use crate::http::Paginated;
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct FilterVec {
+    pub filters: Vec<Filter>
+}
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct FirewallRuleVec {
+    pub rules: Vec<FirewallRule>
+}
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct AccessRuleVec {
+    pub rules: Vec<AccessRule>
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct PageParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+}
+
+/// List Filters
+/// https://api.cloudflare.com/#filters-list-filters
+pub struct ListFilters<'a> {
+    pub zone_identifier: &'a str,
+    pub params: PageParams,
+}
+
+impl<'a> Endpoint<FilterVec, PageParams> for ListFilters<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/filters", self.zone_identifier)
+    }
+    fn query(&self) -> Option<PageParams> {
+        Some(self.params.clone())
+    }
+}
+
+/// Create Filters
+/// https://api.cloudflare.com/#filters-create-filters
+pub struct CreateFilters<'a> {
+    pub zone_identifier: &'a str,
+    pub params: Vec<FilterParams>,
+}
+
+impl<'a> Endpoint<FilterVec, (), Vec<FilterParams>> for CreateFilters<'a> {
+    fn method(&self) -> Method {
+        Method::Post
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/filters", self.zone_identifier)
+    }
+    fn body(&self) -> Option<Vec<FilterParams>> {
+        Some(self.params.clone())
+    }
+}
+
+/// Filter Details
+/// https://api.cloudflare.com/#filters-get-individual-filter
+pub struct FilterDetails<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<Filter> for FilterDetails<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/filters/{}", self.zone_identifier, self.identifier)
+    }
+}
+
+/// Update a Filter
+/// https://api.cloudflare.com/#filters-update-individual-filter
+pub struct UpdateFilter<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+    pub params: FilterParams,
+}
+
+impl<'a> Endpoint<Filter, (), FilterParams> for UpdateFilter<'a> {
+    fn method(&self) -> Method {
+        Method::Put
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/filters/{}", self.zone_identifier, self.identifier)
+    }
+    fn body(&self) -> Option<FilterParams> {
+        Some(self.params.clone())
+    }
+}
+
+/// Delete a Filter
+/// https://api.cloudflare.com/#filters-delete-individual-filter
+pub struct DeleteFilter<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<Identifier> for DeleteFilter<'a> {
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/filters/{}", self.zone_identifier, self.identifier)
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct FilterParams {
+    /// Filter identifier tag, only sent when updating
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Rules language expression, e.g. `ip.src eq 93.184.216.34`
+    pub expression: String,
+    pub paused: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// List Firewall Rules
+/// https://api.cloudflare.com/#firewall-rules-list-of-firewall-rules
+pub struct ListFirewallRules<'a> {
+    pub zone_identifier: &'a str,
+    pub params: PageParams,
+}
+
+impl<'a> Endpoint<FirewallRuleVec, PageParams> for ListFirewallRules<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/firewall/rules", self.zone_identifier)
+    }
+    fn query(&self) -> Option<PageParams> {
+        Some(self.params.clone())
+    }
+}
+
+/// Firewall Rule Details
+/// https://api.cloudflare.com/#firewall-rules-get-individual-firewall-rule
+pub struct FirewallRuleDetails<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<FirewallRule> for FirewallRuleDetails<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/firewall/rules/{}", self.zone_identifier, self.identifier)
+    }
+}
+
+/// Create Firewall Rules
+/// Filters given with an expression instead of an identifier are created along with the rules
+/// https://api.cloudflare.com/#firewall-rules-create-firewall-rules
+pub struct CreateFirewallRules<'a> {
+    pub zone_identifier: &'a str,
+    pub params: Vec<FirewallRuleParams>,
+}
+
+impl<'a> Endpoint<FirewallRuleVec, (), Vec<FirewallRuleParams>> for CreateFirewallRules<'a> {
+    fn method(&self) -> Method {
+        Method::Post
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/firewall/rules", self.zone_identifier)
+    }
+    fn body(&self) -> Option<Vec<FirewallRuleParams>> {
+        Some(self.params.clone())
+    }
+}
+
+/// Update a Firewall Rule
+/// https://api.cloudflare.com/#firewall-rules-update-individual-firewall-rule
+pub struct UpdateFirewallRule<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+    pub params: FirewallRuleParams,
+}
+
+impl<'a> Endpoint<FirewallRule, (), FirewallRuleParams> for UpdateFirewallRule<'a> {
+    fn method(&self) -> Method {
+        Method::Put
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/firewall/rules/{}", self.zone_identifier, self.identifier)
+    }
+    fn body(&self) -> Option<FirewallRuleParams> {
+        Some(self.params.clone())
+    }
+}
+
+/// Delete a Firewall Rule
+/// https://api.cloudflare.com/#firewall-rules-delete-individual-firewall-rule
+pub struct DeleteFirewallRule<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+    pub params: DeleteFirewallRuleParams,
+}
+
+impl<'a> Endpoint<Identifier, DeleteFirewallRuleParams> for DeleteFirewallRule<'a> {
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/firewall/rules/{}", self.zone_identifier, self.identifier)
+    }
+    fn query(&self) -> Option<DeleteFirewallRuleParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct DeleteFirewallRuleParams {
+    /// Also delete the rule's filter when no other rule uses it
+    pub delete_filter_if_unused: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FirewallRuleParams {
+    /// Rule identifier tag, only sent when updating
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub filter: FilterParams,
+    pub action: String,
+    pub paused: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+}
+
+/// Scope of IP Access Rules, a zone or every zone of an account
+#[derive(Debug, Clone, Copy)]
+pub enum AccessRuleScope<'a> {
+    Zone(&'a str),
+    Account(&'a str),
+}
+
+impl<'a> AccessRuleScope<'a> {
+    fn path(&self) -> String {
+        match self {
+            AccessRuleScope::Zone(id) => format!("zones/{}/firewall/access_rules/rules", id),
+            AccessRuleScope::Account(id) => format!("accounts/{}/firewall/access_rules/rules", id),
+        }
+    }
+}
+
+/// List IP Access Rules
+/// https://api.cloudflare.com/#ip-access-rules-for-a-zone-list-ip-access-rules
+/// https://api.cloudflare.com/#ip-access-rules-for-an-account-list-ip-access-rules
+pub struct ListAccessRules<'a> {
+    pub scope: AccessRuleScope<'a>,
+    pub params: ListAccessRulesParams,
+}
+
+impl<'a> Endpoint<AccessRuleVec, ListAccessRulesParams> for ListAccessRules<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        self.scope.path()
+    }
+    fn query(&self) -> Option<ListAccessRulesParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ListAccessRulesParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+}
+
+/// Create an IP Access Rule
+/// https://api.cloudflare.com/#ip-access-rules-for-a-zone-create-an-ip-access-rule
+pub struct CreateAccessRule<'a> {
+    pub scope: AccessRuleScope<'a>,
+    pub params: CreateAccessRuleParams,
+}
+
+impl<'a> Endpoint<AccessRule, (), CreateAccessRuleParams> for CreateAccessRule<'a> {
+    fn method(&self) -> Method {
+        Method::Post
+    }
+    fn path(&self) -> String {
+        self.scope.path()
+    }
+    fn body(&self) -> Option<CreateAccessRuleParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CreateAccessRuleParams {
+    pub mode: String,
+    pub configuration: AccessRuleConfiguration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+/// Update an IP Access Rule
+/// https://api.cloudflare.com/#ip-access-rules-for-a-zone-update-an-ip-access-rule
+pub struct EditAccessRule<'a> {
+    pub scope: AccessRuleScope<'a>,
+    pub identifier: &'a str,
+    pub params: EditAccessRuleParams,
+}
+
+impl<'a> Endpoint<AccessRule, (), EditAccessRuleParams> for EditAccessRule<'a> {
+    fn method(&self) -> Method {
+        Method::Patch
+    }
+    fn path(&self) -> String {
+        format!("{}/{}", self.scope.path(), self.identifier)
+    }
+    fn body(&self) -> Option<EditAccessRuleParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct EditAccessRuleParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+/// Delete an IP Access Rule
+/// https://api.cloudflare.com/#ip-access-rules-for-a-zone-delete-an-ip-access-rule
+pub struct DeleteAccessRule<'a> {
+    pub scope: AccessRuleScope<'a>,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<Identifier> for DeleteAccessRule<'a> {
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+    fn path(&self) -> String {
+        format!("{}/{}", self.scope.path(), self.identifier)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Identifier {
+    pub id: String,
+}
+
+/// Matches requests with a Rules language expression
+/// https://api.cloudflare.com/#filters-properties
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Filter {
+    /// Filter identifier tag
+    pub id: String,
+    pub expression: String,
+    #[serde(default)]
+    pub paused: bool,
+    pub description: Option<String>,
+}
+
+/// Applies an action to the requests matched by a filter
+/// https://api.cloudflare.com/#firewall-rules-properties
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FirewallRule {
+    /// Rule identifier tag
+    pub id: String,
+    pub filter: Filter,
+    /// `block`, `challenge`, `js_challenge`, `managed_challenge`, `allow`, `log` or `bypass`
+    pub action: String,
+    #[serde(default)]
+    pub paused: bool,
+    pub description: Option<String>,
+    /// Rules with a lower priority are evaluated first, rules without one are evaluated last
+    pub priority: Option<u32>,
+    /// Products skipped by `bypass` rules, e.g. `waf`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub products: Option<Vec<String>>,
+    pub created_on: Option<DateTime<Utc>>,
+    pub modified_on: Option<DateTime<Utc>>,
+}
+
+/// Blocks, challenges or allows requests by IP, IP range, ASN or country
+/// https://api.cloudflare.com/#ip-access-rules-for-a-zone-properties
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AccessRule {
+    /// Rule identifier tag
+    pub id: String,
+    /// `block`, `challenge`, `whitelist`, `js_challenge` or `managed_challenge`
+    pub mode: String,
+    pub configuration: AccessRuleConfiguration,
+    #[serde(default)]
+    pub notes: String,
+    pub scope: Option<AccessRuleScopeDetails>,
+    pub created_on: Option<DateTime<Utc>>,
+    pub modified_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AccessRuleConfiguration {
+    /// `ip`, `ip6`, `ip_range`, `asn` or `country`
+    pub target: String,
+    /// e.g. `198.51.100.4`, `198.51.100.0/24`, `AS12345` or `US`
+    pub value: String,
+}
+
+/// Where a rule was created, rules of an account apply to all of its zones
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AccessRuleScopeDetails {
+    pub id: String,
+    /// `user`, `organization` or `zone`
+    #[serde(rename = "type")]
+    pub scope_type: String,
+}
+
+impl ApiResult for Identifier {}
+impl ApiResult for Filter {}
+impl ApiResult for FilterVec {}
+impl ApiResult for FirewallRule {}
+impl ApiResult for FirewallRuleVec {}
+impl ApiResult for AccessRule {}
+impl ApiResult for AccessRuleVec {}
+
+impl Paginated for FilterVec {
+    type Item = Filter;
+
+    fn into_items(self) -> Vec<Filter> {
+        self.filters
+    }
+}
+
+impl Paginated for FirewallRuleVec {
+    type Item = FirewallRule;
+
+    fn into_items(self) -> Vec<FirewallRule> {
+        self.rules
+    }
+}
+
+impl Paginated for AccessRuleVec {
+    type Item = AccessRule;
+
+    fn into_items(self) -> Vec<AccessRule> {
+        self.rules
+    }
+}
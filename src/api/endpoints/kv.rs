@@ -0,0 +1,167 @@
+use cloudflare::endpoints::workerskv::WorkersKvNamespace;
+use cloudflare::framework::endpoint::{Endpoint, Method};
+use cloudflare::framework::response::ApiResult;
+use serde_json::Value;
+
+use crate::http::Paginated;
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct KeyVec {
+    pub keys: Vec<Key>
+}
+
+/// List a Namespace's Keys
+/// Version of `cloudflare::endpoints::workerskv::list_namespace_keys` returning key metadata.
+/// The cursor of the next page is in `result_info`.
+/// https://api.cloudflare.com/#workers-kv-namespace-list-a-namespace-s-keys
+pub struct ListKeys<'a> {
+    pub account_identifier: &'a str,
+    pub namespace_identifier: &'a str,
+    pub params: ListKeysParams,
+}
+
+impl<'a> Endpoint<KeyVec, ListKeysParams> for ListKeys<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/storage/kv/namespaces/{}/keys", self.account_identifier, self.namespace_identifier)
+    }
+    fn query(&self) -> Option<ListKeysParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ListKeysParams {
+    /// Between 10 and 1000
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+}
+
+/// Read key-value pair
+/// The response is the raw value, fetched with `http::Client::download`
+/// https://api.cloudflare.com/#workers-kv-namespace-read-key-value-pair
+pub struct ReadValue<'a> {
+    pub account_identifier: &'a str,
+    pub namespace_identifier: &'a str,
+    pub key: &'a str,
+}
+
+impl<'a> Endpoint for ReadValue<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        value_path(self.account_identifier, self.namespace_identifier, self.key)
+    }
+}
+
+/// Write key-value pair with metadata
+/// The body is the raw value, or a multipart form with the value and its metadata, sent with
+/// `http::Client`
+/// https://api.cloudflare.com/#workers-kv-namespace-write-key-value-pair-with-metadata
+pub struct WriteValue<'a> {
+    pub account_identifier: &'a str,
+    pub namespace_identifier: &'a str,
+    pub key: &'a str,
+    pub params: WriteValueParams,
+}
+
+impl<'a> Endpoint<(), WriteValueParams> for WriteValue<'a> {
+    fn method(&self) -> Method {
+        Method::Put
+    }
+    fn path(&self) -> String {
+        value_path(self.account_identifier, self.namespace_identifier, self.key)
+    }
+    fn query(&self) -> Option<WriteValueParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct WriteValueParams {
+    /// Time the key expires, in seconds since the UNIX epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<i64>,
+    /// Seconds from now the key expires, at least 60
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_ttl: Option<i64>,
+}
+
+/// Write multiple key-value pairs
+/// Version of `cloudflare::endpoints::workerskv::write_bulk` supporting metadata.
+/// Up to 10,000 pairs can be written at once.
+/// https://api.cloudflare.com/#workers-kv-namespace-write-multiple-key-value-pairs
+pub struct WriteBulk<'a> {
+    pub account_identifier: &'a str,
+    pub namespace_identifier: &'a str,
+    pub pairs: Vec<KeyValuePair>,
+}
+
+impl<'a> Endpoint<(), (), Vec<KeyValuePair>> for WriteBulk<'a> {
+    fn method(&self) -> Method {
+        Method::Put
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/storage/kv/namespaces/{}/bulk", self.account_identifier, self.namespace_identifier)
+    }
+    fn body(&self) -> Option<Vec<KeyValuePair>> {
+        Some(self.pairs.clone())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KeyValuePair {
+    pub key: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_ttl: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    /// The value is base64 encoded binary data
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub base64: bool,
+}
+
+/// A key of a namespace
+/// https://api.cloudflare.com/#workers-kv-namespace-list-a-namespace-s-keys
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Key {
+    pub name: String,
+    /// Time the key expires, in seconds since the UNIX epoch
+    pub expiration: Option<i64>,
+    /// Arbitrary JSON stored with the key
+    pub metadata: Option<Value>,
+}
+
+// Keys may contain any character, including slashes
+fn value_path(account_identifier: &str, namespace_identifier: &str, key: &str) -> String {
+    let key: String = key.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            b => format!("%{:02X}", b),
+        })
+        .collect();
+
+    format!("accounts/{}/storage/kv/namespaces/{}/values/{}", account_identifier, namespace_identifier, key)
+}
+
+impl ApiResult for KeyVec {}
+
+impl Paginated for Vec<WorkersKvNamespace> {
+    type Item = WorkersKvNamespace;
+
+    fn into_items(self) -> Vec<WorkersKvNamespace> {
+        self
+    }
+}
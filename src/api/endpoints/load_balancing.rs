@@ -0,0 +1,433 @@
+use std::collections::BTreeMap;
+
+use chrono::DateTime;
+use chrono::offset::Utc;
+use cloudflare::framework::endpoint::{Endpoint, Method};
+use cloudflare::framework::response::ApiResult;
+use serde_json::{Map, Value};
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct LoadBalancerVec {
+    pub load_balancers: Vec<LoadBalancer>
+}
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct PoolVec {
+    pub pools: Vec<Pool>
+}
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct MonitorVec {
+    pub monitors: Vec<Monitor>
+}
+
+/// List Load Balancers
+/// https://api.cloudflare.com/#load-balancers-list-load-balancers
+pub struct ListLoadBalancers<'a> {
+    pub zone_identifier: &'a str,
+}
+
+impl<'a> Endpoint<LoadBalancerVec> for ListLoadBalancers<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/load_balancers", self.zone_identifier)
+    }
+}
+
+/// Load Balancer Details
+/// https://api.cloudflare.com/#load-balancers-load-balancer-details
+pub struct LoadBalancerDetails<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<LoadBalancer> for LoadBalancerDetails<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/load_balancers/{}", self.zone_identifier, self.identifier)
+    }
+}
+
+/// Create Load Balancer
+/// The body is the load balancer as read from a spec file
+/// https://api.cloudflare.com/#load-balancers-create-load-balancer
+pub struct CreateLoadBalancer<'a> {
+    pub zone_identifier: &'a str,
+    pub params: Value,
+}
+
+impl<'a> Endpoint<LoadBalancer, (), Value> for CreateLoadBalancer<'a> {
+    fn method(&self) -> Method {
+        Method::Post
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/load_balancers", self.zone_identifier)
+    }
+    fn body(&self) -> Option<Value> {
+        Some(self.params.clone())
+    }
+}
+
+/// Patch Load Balancer
+/// Only the given fields are changed
+/// https://api.cloudflare.com/#load-balancers-patch-load-balancer
+pub struct EditLoadBalancer<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+    pub params: Value,
+}
+
+impl<'a> Endpoint<LoadBalancer, (), Value> for EditLoadBalancer<'a> {
+    fn method(&self) -> Method {
+        Method::Patch
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/load_balancers/{}", self.zone_identifier, self.identifier)
+    }
+    fn body(&self) -> Option<Value> {
+        Some(self.params.clone())
+    }
+}
+
+/// Delete Load Balancer
+/// https://api.cloudflare.com/#load-balancers-delete-load-balancer
+pub struct DeleteLoadBalancer<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<Identifier> for DeleteLoadBalancer<'a> {
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/load_balancers/{}", self.zone_identifier, self.identifier)
+    }
+}
+
+/// List Pools
+/// https://api.cloudflare.com/#account-load-balancer-pools-list-pools
+pub struct ListPools<'a> {
+    pub account_identifier: &'a str,
+}
+
+impl<'a> Endpoint<PoolVec> for ListPools<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/load_balancers/pools", self.account_identifier)
+    }
+}
+
+/// Pool Details
+/// https://api.cloudflare.com/#account-load-balancer-pools-pool-details
+pub struct PoolDetails<'a> {
+    pub account_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<Pool> for PoolDetails<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/load_balancers/pools/{}", self.account_identifier, self.identifier)
+    }
+}
+
+/// Create Pool
+/// The body is the pool as read from a spec file
+/// https://api.cloudflare.com/#account-load-balancer-pools-create-pool
+pub struct CreatePool<'a> {
+    pub account_identifier: &'a str,
+    pub params: Value,
+}
+
+impl<'a> Endpoint<Pool, (), Value> for CreatePool<'a> {
+    fn method(&self) -> Method {
+        Method::Post
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/load_balancers/pools", self.account_identifier)
+    }
+    fn body(&self) -> Option<Value> {
+        Some(self.params.clone())
+    }
+}
+
+/// Patch Pool
+/// Only the given fields are changed
+/// https://api.cloudflare.com/#account-load-balancer-pools-patch-pool
+pub struct EditPool<'a> {
+    pub account_identifier: &'a str,
+    pub identifier: &'a str,
+    pub params: Value,
+}
+
+impl<'a> Endpoint<Pool, (), Value> for EditPool<'a> {
+    fn method(&self) -> Method {
+        Method::Patch
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/load_balancers/pools/{}", self.account_identifier, self.identifier)
+    }
+    fn body(&self) -> Option<Value> {
+        Some(self.params.clone())
+    }
+}
+
+/// Delete Pool
+/// https://api.cloudflare.com/#account-load-balancer-pools-delete-pool
+pub struct DeletePool<'a> {
+    pub account_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<Identifier> for DeletePool<'a> {
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/load_balancers/pools/{}", self.account_identifier, self.identifier)
+    }
+}
+
+/// Pool Health Details
+/// https://api.cloudflare.com/#account-load-balancer-pools-pool-health-details
+pub struct PoolHealthDetails<'a> {
+    pub account_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<PoolHealth> for PoolHealthDetails<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/load_balancers/pools/{}/health", self.account_identifier, self.identifier)
+    }
+}
+
+/// List Monitors
+/// https://api.cloudflare.com/#account-load-balancer-monitors-list-monitors
+pub struct ListMonitors<'a> {
+    pub account_identifier: &'a str,
+}
+
+impl<'a> Endpoint<MonitorVec> for ListMonitors<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/load_balancers/monitors", self.account_identifier)
+    }
+}
+
+/// Monitor Details
+/// https://api.cloudflare.com/#account-load-balancer-monitors-monitor-details
+pub struct MonitorDetails<'a> {
+    pub account_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<Monitor> for MonitorDetails<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/load_balancers/monitors/{}", self.account_identifier, self.identifier)
+    }
+}
+
+/// Create Monitor
+/// The body is the monitor as read from a spec file
+/// https://api.cloudflare.com/#account-load-balancer-monitors-create-monitor
+pub struct CreateMonitor<'a> {
+    pub account_identifier: &'a str,
+    pub params: Value,
+}
+
+impl<'a> Endpoint<Monitor, (), Value> for CreateMonitor<'a> {
+    fn method(&self) -> Method {
+        Method::Post
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/load_balancers/monitors", self.account_identifier)
+    }
+    fn body(&self) -> Option<Value> {
+        Some(self.params.clone())
+    }
+}
+
+/// Patch Monitor
+/// Only the given fields are changed
+/// https://api.cloudflare.com/#account-load-balancer-monitors-patch-monitor
+pub struct EditMonitor<'a> {
+    pub account_identifier: &'a str,
+    pub identifier: &'a str,
+    pub params: Value,
+}
+
+impl<'a> Endpoint<Monitor, (), Value> for EditMonitor<'a> {
+    fn method(&self) -> Method {
+        Method::Patch
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/load_balancers/monitors/{}", self.account_identifier, self.identifier)
+    }
+    fn body(&self) -> Option<Value> {
+        Some(self.params.clone())
+    }
+}
+
+/// Delete Monitor
+/// https://api.cloudflare.com/#account-load-balancer-monitors-delete-monitor
+pub struct DeleteMonitor<'a> {
+    pub account_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<Identifier> for DeleteMonitor<'a> {
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/load_balancers/monitors/{}", self.account_identifier, self.identifier)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Identifier {
+    pub id: String,
+}
+
+/// A load balancer, distributing the traffic of a hostname across pools
+/// https://api.cloudflare.com/#load-balancers-properties
+#[derive(Deserialize, Serialize, Debug)]
+pub struct LoadBalancer {
+    pub id: String,
+    /// The hostname of the load balancer
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub proxied: bool,
+    pub ttl: Option<u32>,
+    /// e.g. `off`, `geo`, `random` or `dynamic_latency`
+    pub steering_policy: Option<String>,
+    /// Pool identifiers, in failover order
+    #[serde(default)]
+    pub default_pools: Vec<String>,
+    /// Pool used when every other pool is unhealthy
+    pub fallback_pool: Option<String>,
+    pub created_on: Option<DateTime<Utc>>,
+    pub modified_on: Option<DateTime<Utc>>,
+}
+
+/// A group of origins, checked by a monitor
+/// https://api.cloudflare.com/#account-load-balancer-pools-properties
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Pool {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    /// Fewer healthy origins make the pool unhealthy
+    pub minimum_origins: Option<u32>,
+    /// Monitor identifier
+    pub monitor: Option<String>,
+    #[serde(default)]
+    pub origins: Vec<Origin>,
+    pub notification_email: Option<String>,
+    /// Only reported once a monitor checked the pool
+    pub healthy: Option<bool>,
+    pub created_on: Option<DateTime<Utc>>,
+    pub modified_on: Option<DateTime<Utc>>,
+}
+
+/// A server of a pool
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Origin {
+    pub name: String,
+    /// IP address or hostname
+    pub address: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    pub weight: Option<f64>,
+    /// Fields cflare doesn't use, e.g. `header`, sent back unchanged when the origins are edited
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// A health check of the origins of pools
+/// https://api.cloudflare.com/#account-load-balancer-monitors-properties
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Monitor {
+    pub id: String,
+    /// e.g. `http`, `https` or `tcp`
+    #[serde(rename = "type")]
+    pub monitor_type: Option<String>,
+    pub description: Option<String>,
+    pub method: Option<String>,
+    pub path: Option<String>,
+    pub port: Option<u16>,
+    pub expected_codes: Option<String>,
+    /// Seconds between checks
+    pub interval: Option<u32>,
+    pub timeout: Option<u32>,
+    pub retries: Option<u32>,
+    pub created_on: Option<DateTime<Utc>>,
+    pub modified_on: Option<DateTime<Utc>>,
+}
+
+/// Health of the origins of a pool, as seen from each Cloudflare data center checking them
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PoolHealth {
+    pub pool_id: String,
+    /// Keyed by data center, e.g. `Amsterdam, NL`
+    #[serde(default)]
+    pub pop_health: BTreeMap<String, PopHealth>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PopHealth {
+    pub healthy: Option<bool>,
+    /// Each entry maps an origin address to its health
+    #[serde(default)]
+    pub origins: Vec<BTreeMap<String, OriginHealth>>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct OriginHealth {
+    pub healthy: Option<bool>,
+    /// Round trip time, e.g. `66ms`
+    pub rtt: Option<String>,
+    pub failure_reason: Option<String>,
+    pub response_code: Option<u16>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl ApiResult for Identifier {}
+impl ApiResult for LoadBalancer {}
+impl ApiResult for LoadBalancerVec {}
+impl ApiResult for Pool {}
+impl ApiResult for PoolVec {}
+impl ApiResult for PoolHealth {}
+impl ApiResult for Monitor {}
+impl ApiResult for MonitorVec {}
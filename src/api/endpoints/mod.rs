@@ -1,6 +1,13 @@
 // Workaround the current limitation of cloudflare-rs
 pub mod account;
 pub mod cache;
+pub mod certificates;
 pub mod dns;
+pub mod firewall;
+pub mod kv;
+pub mod load_balancing;
+pub mod pagerules;
 pub mod plan;
+pub mod workers;
+pub mod zone_settings;
 pub mod zones;
@@ -0,0 +1,215 @@
+use chrono::DateTime;
+use chrono::offset::Utc;
+use cloudflare::framework::endpoint::{Endpoint, Method};
+use cloudflare::framework::response::ApiResult;
+use serde_json::Value;
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct PageRuleVec {
+    pub rules: Vec<PageRule>
+}
+
+/// List Page Rules
+/// https://api.cloudflare.com/#page-rules-for-a-zone-list-page-rules
+pub struct ListPageRules<'a> {
+    pub zone_identifier: &'a str,
+    pub params: ListPageRulesParams,
+}
+
+impl<'a> Endpoint<PageRuleVec, ListPageRulesParams> for ListPageRules<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/pagerules", self.zone_identifier)
+    }
+    fn query(&self) -> Option<ListPageRulesParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ListPageRulesParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<String>,
+}
+
+/// Page Rule Details
+/// https://api.cloudflare.com/#page-rules-for-a-zone-page-rule-details
+pub struct PageRuleDetails<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<PageRule> for PageRuleDetails<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/pagerules/{}", self.zone_identifier, self.identifier)
+    }
+}
+
+/// Create Page Rule
+/// https://api.cloudflare.com/#page-rules-for-a-zone-create-page-rule
+pub struct CreatePageRule<'a> {
+    pub zone_identifier: &'a str,
+    pub params: PageRuleParams,
+}
+
+impl<'a> Endpoint<PageRule, (), PageRuleParams> for CreatePageRule<'a> {
+    fn method(&self) -> Method {
+        Method::Post
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/pagerules", self.zone_identifier)
+    }
+    fn body(&self) -> Option<PageRuleParams> {
+        Some(self.params.clone())
+    }
+}
+
+/// Update Page Rule. Replaces every field of the rule.
+/// https://api.cloudflare.com/#page-rules-for-a-zone-update-page-rule
+pub struct UpdatePageRule<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+    pub params: PageRuleParams,
+}
+
+impl<'a> Endpoint<PageRule, (), PageRuleParams> for UpdatePageRule<'a> {
+    fn method(&self) -> Method {
+        Method::Put
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/pagerules/{}", self.zone_identifier, self.identifier)
+    }
+    fn body(&self) -> Option<PageRuleParams> {
+        Some(self.params.clone())
+    }
+}
+
+/// Edit Page Rule. Only changes the given fields, used to reorder rules.
+/// https://api.cloudflare.com/#page-rules-for-a-zone-edit-page-rule
+pub struct EditPageRule<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+    pub params: EditPageRuleParams,
+}
+
+impl<'a> Endpoint<PageRule, (), EditPageRuleParams> for EditPageRule<'a> {
+    fn method(&self) -> Method {
+        Method::Patch
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/pagerules/{}", self.zone_identifier, self.identifier)
+    }
+    fn body(&self) -> Option<EditPageRuleParams> {
+        Some(self.params.clone())
+    }
+}
+
+/// Delete Page Rule
+/// https://api.cloudflare.com/#page-rules-for-a-zone-delete-page-rule
+pub struct DeletePageRule<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<PageRuleId> for DeletePageRule<'a> {
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/pagerules/{}", self.zone_identifier, self.identifier)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Active,
+    Disabled,
+}
+
+/// URL pattern the rule applies to
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Target {
+    /// Always `url`
+    pub target: String,
+    pub constraint: Constraint,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Constraint {
+    /// Always `matches`
+    pub operator: String,
+    /// URL pattern, with `*` wildcards. e.g. `*example.com/images/*`
+    pub value: String,
+}
+
+impl Target {
+    pub fn url(pattern: &str) -> Self {
+        Target {
+            target: "url".to_string(),
+            constraint: Constraint { operator: "matches".to_string(), value: pattern.to_string() },
+        }
+    }
+}
+
+/// Setting applied to matching requests. The value depends on the action, e.g. a string for
+/// `cache_level`, a number for `edge_cache_ttl` or an object for `forwarding_url`.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Action {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct PageRuleParams {
+    pub targets: Vec<Target>,
+    pub actions: Vec<Action>,
+    /// Rules with a higher priority are applied first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EditPageRuleParams {
+    pub priority: u32,
+}
+
+/// A Page Rule triggers actions for requests matching a URL pattern
+/// https://api.cloudflare.com/#page-rules-for-a-zone-properties
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PageRule {
+    /// Page Rule identifier tag
+    pub id: String,
+    pub targets: Vec<Target>,
+    pub actions: Vec<Action>,
+    /// Rules with a higher priority are applied first
+    pub priority: u32,
+    pub status: Status,
+    /// When the rule was created
+    pub created_on: DateTime<Utc>,
+    /// When the rule was last modified
+    pub modified_on: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PageRuleId {
+    pub id: String,
+}
+
+impl ApiResult for PageRule {}
+impl ApiResult for PageRuleVec {}
+impl ApiResult for PageRuleId {}
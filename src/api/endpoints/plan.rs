@@ -1,4 +1,4 @@
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum Frequency {
     #[serde(rename = "")]
@@ -9,7 +9,7 @@ pub enum Frequency {
     Yearly,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Plan {
     /// Plan identifier tag
     pub id: String,
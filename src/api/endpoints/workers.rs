@@ -0,0 +1,129 @@
+use chrono::DateTime;
+use chrono::offset::Utc;
+use cloudflare::framework::endpoint::{Endpoint, Method};
+use cloudflare::framework::response::ApiResult;
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct ScriptVec {
+    pub scripts: Vec<Script>
+}
+
+/// List Workers
+/// https://api.cloudflare.com/#worker-script-list-workers
+pub struct ListScripts<'a> {
+    pub account_identifier: &'a str,
+}
+
+impl<'a> Endpoint<ScriptVec> for ListScripts<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/workers/scripts", self.account_identifier)
+    }
+}
+
+/// Upload Worker
+/// The body is a multipart form with the script and its metadata, sent with `http::Client::upload`
+/// https://api.cloudflare.com/#worker-script-upload-worker
+pub struct UploadScript<'a> {
+    pub account_identifier: &'a str,
+    pub name: &'a str,
+}
+
+impl<'a> Endpoint<Script> for UploadScript<'a> {
+    fn method(&self) -> Method {
+        Method::Put
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/workers/scripts/{}", self.account_identifier, self.name)
+    }
+}
+
+/// Download Worker
+/// The response is the raw script, fetched with `http::Client::download`
+/// https://api.cloudflare.com/#worker-script-download-worker
+pub struct DownloadScript<'a> {
+    pub account_identifier: &'a str,
+    pub name: &'a str,
+}
+
+impl<'a> Endpoint for DownloadScript<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/workers/scripts/{}", self.account_identifier, self.name)
+    }
+}
+
+/// Delete Worker
+/// https://api.cloudflare.com/#worker-script-delete-worker
+pub struct DeleteScript<'a> {
+    pub account_identifier: &'a str,
+    pub name: &'a str,
+}
+
+impl<'a> Endpoint for DeleteScript<'a> {
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+    fn path(&self) -> String {
+        format!("accounts/{}/workers/scripts/{}", self.account_identifier, self.name)
+    }
+}
+
+/// Part of the upload form describing the script
+/// https://developers.cloudflare.com/workers/configuration/multipart-upload-metadata/
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ScriptMetadata {
+    /// Name of the part holding a service worker script
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_part: Option<String>,
+    /// Name of the part holding the entry point of an ES module worker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub main_module: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub bindings: Vec<Binding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compatibility_date: Option<String>,
+}
+
+/// Resources available to the script as global variables, or as `env` properties for modules
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum Binding {
+    KvNamespace { name: String, namespace_id: String },
+    PlainText { name: String, text: String },
+    SecretText { name: String, text: String },
+}
+
+impl Binding {
+    /// Copy safe to print, with the value of secrets hidden
+    pub fn redacted(&self) -> Binding {
+        match self {
+            Binding::SecretText { name, .. } => Binding::SecretText { name: name.clone(), text: "<redacted>".to_string() },
+            other => other.clone(),
+        }
+    }
+}
+
+/// A Worker script
+/// https://api.cloudflare.com/#worker-script-properties
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Script {
+    /// Script name
+    pub id: String,
+    pub etag: Option<String>,
+    /// Events handled by the script, e.g. `fetch` or `scheduled`
+    #[serde(default)]
+    pub handlers: Vec<String>,
+    pub usage_model: Option<String>,
+    pub created_on: Option<DateTime<Utc>>,
+    pub modified_on: Option<DateTime<Utc>>,
+}
+
+impl ApiResult for Script {}
+impl ApiResult for ScriptVec {}
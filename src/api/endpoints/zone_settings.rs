@@ -0,0 +1,120 @@
+use chrono::DateTime;
+use chrono::offset::Utc;
+use cloudflare::framework::endpoint::{Endpoint, Method};
+use cloudflare::framework::response::ApiResult;
+use serde_json::Value;
+
+// Workaround for error E0117
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct ZoneSettingVec {
+    pub settings: Vec<ZoneSetting>
+}
+
+/// Get all Zone settings
+/// https://api.cloudflare.com/#zone-settings-get-all-zone-settings
+pub struct ListZoneSettings<'a> {
+    pub zone_identifier: &'a str,
+}
+
+impl<'a> Endpoint<ZoneSettingVec> for ListZoneSettings<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/settings", self.zone_identifier)
+    }
+}
+
+/// Get a single Zone setting, e.g. `zones/:zone_identifier/settings/ssl`
+/// https://api.cloudflare.com/#zone-settings-properties
+pub struct ZoneSettingDetails<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<ZoneSetting> for ZoneSettingDetails<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/settings/{}", self.zone_identifier, self.identifier)
+    }
+}
+
+/// Change a single Zone setting
+/// https://api.cloudflare.com/#zone-settings-properties
+pub struct EditZoneSetting<'a> {
+    pub zone_identifier: &'a str,
+    pub identifier: &'a str,
+    pub params: EditZoneSettingParams,
+}
+
+impl<'a> Endpoint<ZoneSetting, (), EditZoneSettingParams> for EditZoneSetting<'a> {
+    fn method(&self) -> Method {
+        Method::Patch
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/settings/{}", self.zone_identifier, self.identifier)
+    }
+    fn body(&self) -> Option<EditZoneSettingParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EditZoneSettingParams {
+    pub value: Value,
+}
+
+/// Edit zone settings info
+/// Changes several settings in a single request
+/// https://api.cloudflare.com/#zone-settings-edit-zone-settings-info
+pub struct EditZoneSettings<'a> {
+    pub zone_identifier: &'a str,
+    pub params: EditZoneSettingsParams,
+}
+
+impl<'a> Endpoint<ZoneSettingVec, (), EditZoneSettingsParams> for EditZoneSettings<'a> {
+    fn method(&self) -> Method {
+        Method::Patch
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/settings", self.zone_identifier)
+    }
+    fn body(&self) -> Option<EditZoneSettingsParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EditZoneSettingsParams {
+    pub items: Vec<SettingValue>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SettingValue {
+    /// Setting name, e.g. `always_use_https`
+    pub id: String,
+    pub value: Value,
+}
+
+/// A zone setting. Most values are strings such as `on` or `off`, some are numbers or objects.
+/// https://api.cloudflare.com/#zone-settings-properties
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneSetting {
+    /// Setting name, e.g. `always_use_https`
+    pub id: String,
+    pub value: Value,
+    /// Whether the setting can be changed on the zone's plan
+    #[serde(default)]
+    pub editable: bool,
+    /// Last time this setting was modified
+    pub modified_on: Option<DateTime<Utc>>,
+    /// Seconds until a temporary setting such as `development_mode` is turned off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_remaining: Option<i64>,
+}
+
+impl ApiResult for ZoneSetting {}
+impl ApiResult for ZoneSettingVec {}
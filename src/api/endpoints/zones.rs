@@ -2,11 +2,8 @@ use chrono::DateTime;
 use chrono::offset::Utc;
 use cloudflare::endpoints::{
     zone::{
-        HostingPartner,
         ListZonesParams,
-        Owner,
         Status,
-        Type,
     },
 };
 use cloudflare::framework::{
@@ -15,6 +12,7 @@ use cloudflare::framework::{
 };
 use crate::api::endpoints::plan::Plan;
 use crate::api::endpoints::account::Account;
+use crate::http::Paginated;
 
 // Workaround for error E0117
 #[derive(Deserialize, Debug)]
@@ -42,8 +40,171 @@ impl Endpoint<ZoneVec, ListZonesParams> for ListZones {
     }
 }
 
-/// Extra Cloudflare-specific information about the record
+/// List the zones of an account
+/// https://api.cloudflare.com/#zone-list-zones
+pub struct ListAccountZones {
+    pub params: ListAccountZonesParams,
+}
+
+impl Endpoint<ZoneVec, ListAccountZonesParams> for ListAccountZones {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        "zones".to_string()
+    }
+    fn query(&self) -> Option<ListAccountZonesParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ListAccountZonesParams {
+    #[serde(rename = "account.id")]
+    pub account_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+}
+
+/// Zone Details
+/// https://api.cloudflare.com/#zone-zone-details
+pub struct ZoneDetails<'a> {
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<Zone> for ZoneDetails<'a> {
+    fn method(&self) -> Method {
+        Method::Get
+    }
+    fn path(&self) -> String {
+        format!("zones/{}", self.identifier)
+    }
+}
+
+/// Create Zone
+/// https://api.cloudflare.com/#zone-create-zone
+pub struct CreateZone {
+    pub params: CreateZoneParams,
+}
+
+impl Endpoint<Zone, (), CreateZoneParams> for CreateZone {
+    fn method(&self) -> Method {
+        Method::Post
+    }
+    fn path(&self) -> String {
+        "zones".to_string()
+    }
+    fn body(&self) -> Option<CreateZoneParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CreateZoneParams {
+    /// The domain name
+    pub name: String,
+    pub account: AccountIdentifier,
+    /// Automatically attempt to fetch existing DNS records
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jump_start: Option<bool>,
+    #[serde(rename = "type")]
+    pub zone_type: Type,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AccountIdentifier {
+    pub id: String,
+}
+
+/// Edit Zone
+/// Only one zone property can be changed at a time
+/// https://api.cloudflare.com/#zone-edit-zone
+pub struct EditZone<'a> {
+    pub identifier: &'a str,
+    pub params: EditZoneParams,
+}
+
+impl<'a> Endpoint<Zone, (), EditZoneParams> for EditZone<'a> {
+    fn method(&self) -> Method {
+        Method::Patch
+    }
+    fn path(&self) -> String {
+        format!("zones/{}", self.identifier)
+    }
+    fn body(&self) -> Option<EditZoneParams> {
+        Some(self.params.clone())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct EditZoneParams {
+    /// Only use Cloudflare DNS, without its security or performance benefits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paused: Option<bool>,
+}
+
+/// Delete Zone
+/// https://api.cloudflare.com/#zone-delete-zone
+pub struct DeleteZone<'a> {
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<ZoneIdentifier> for DeleteZone<'a> {
+    fn method(&self) -> Method {
+        Method::Delete
+    }
+    fn path(&self) -> String {
+        format!("zones/{}", self.identifier)
+    }
+}
+
+/// Zone Activation Check
+/// Triggers a new activation check for a pending zone, at most once per hour on free plans
+/// https://api.cloudflare.com/#zone-zone-activation-check
+pub struct ZoneActivationCheck<'a> {
+    pub identifier: &'a str,
+}
+
+impl<'a> Endpoint<ZoneIdentifier> for ZoneActivationCheck<'a> {
+    fn method(&self) -> Method {
+        Method::Put
+    }
+    fn path(&self) -> String {
+        format!("zones/{}/activation_check", self.identifier)
+    }
+}
+
 #[derive(Deserialize, Debug)]
+pub struct ZoneIdentifier {
+    pub id: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "lowercase", tag = "type")]
+pub enum Owner {
+    User { id: String, email: String },
+    Organization { id: String, name: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Type {
+    Full,
+    Partial,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct HostingPartner {
+    /// Host company name
+    pub name: String,
+    /// The host's website URL
+    pub website: String,
+}
+
+/// Extra Cloudflare-specific information about the record
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Meta {
     /// Will exist if Cloudflare automatically added this DNS record during initial setup.
     pub auto_added: Option<bool>,
@@ -51,7 +212,7 @@ pub struct Meta {
 
 /// A Zone is a domain name along with its subdomains and other identities
 /// https://api.cloudflare.com/#zone-properties
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Zone {
     /// Zone identifier tag
     pub id: String,
@@ -69,7 +230,7 @@ pub struct Zone {
     /// The interval (in seconds) from when development mode expires (positive integer) or last
     /// expired (negative integer) for the domain. If development mode has never been enabled, this
     /// value is 0.
-    pub development_mode: u8,
+    pub development_mode: i32,
     /// Hosting partner information, if the zone signed up via a Cloudflare hosting partner
     pub host: Option<HostingPartner>,
     /// Metadata about the domain.
@@ -108,3 +269,12 @@ pub struct Zone {
 
 impl ApiResult for Zone {}
 impl ApiResult for ZoneVec {}
+impl ApiResult for ZoneIdentifier {}
+
+impl Paginated for ZoneVec {
+    type Item = Zone;
+
+    fn into_items(self) -> Vec<Zone> {
+        self.zones
+    }
+}
@@ -5,38 +5,213 @@ use cloudflare::endpoints::account::{
 };
 use cloudflare::framework::{
     apiclient::ApiClient,
-    HttpApiClient,
     OrderDirection,
 };
-use tabular::Row;
+use tabular::{Row, Table};
 
+use crate::api::endpoints::account::{
+    self as endpoints,
+    AccountDetails,
+    ListAccountMembers,
+    ListAccountMembersParams,
+    ListAccountSubscriptions,
+    Member,
+    Subscription,
+};
+use crate::api::endpoints::zones::{ListAccountZones, ListAccountZonesParams};
 use crate::commands::table_from_cols;
+use crate::error::Error;
+use crate::http::{self, Client, PageOptions};
+use crate::output::{self, Format};
+use crate::terminal;
+
+const ALL_RESULTS_PAGE_SIZE: u32 = 50;
+// Smallest page size accepted by the zones endpoint
+const MIN_ZONES_PAGE_SIZE: u32 = 5;
+
+/// Everything `accounts describe` shows about an account
+#[derive(Serialize)]
+pub struct AccountDescription {
+    #[serde(flatten)]
+    pub account: endpoints::Account,
+    pub zone_count: u64,
+    pub members: Vec<Member>,
+    pub subscriptions: Vec<Subscription>,
+}
 
-pub fn list(api: &HttpApiClient, page: u32, limit: u32) {
-    let response = api.request(&ListAccounts {
+fn fetch_all_accounts(api: &Client) -> Result<Vec<Account>, Error> {
+    http::paginate(api, &PageOptions::all(ALL_RESULTS_PAGE_SIZE), |page, per_page| ListAccounts {
         params: Some(ListAccountsParams {
             page: Some(page),
-            per_page: Some(limit),
+            per_page: Some(per_page),
             direction: Some(OrderDirection::Ascending),
         })
-    });
-
-    match response {
-        Ok(success) => {
-            let list: Vec<Account> = success.result;
-            let columns = vec![
-                "ID",
-                "NAME"
-            ];
-            let mut table = table_from_cols(columns);
-
-            for acc in list {
-                table.add_row(Row::new()
-                    .with_cell(acc.id)
-                    .with_cell(acc.name));
-            }
-            print!("{}", table);
+    }).map_err(Error::from)
+}
+
+/// Finds the identifier of an account given its identifier or its name
+pub fn resolve(api: &Client, id_or_name: &str) -> Result<String, Error> {
+    let is_id = id_or_name.len() == 32 && id_or_name.chars().all(|c| c.is_ascii_hexdigit());
+    if is_id {
+        return Ok(id_or_name.to_string());
+    }
+
+    let matches: Vec<Account> = fetch_all_accounts(api)?
+        .into_iter()
+        .filter(|a| a.name.eq_ignore_ascii_case(id_or_name))
+        .collect();
+
+    match matches.len() {
+        0 => Err(Error::NotFound(format!("Account \"{}\" not found", id_or_name))),
+        1 => Ok(matches[0].id.clone()),
+        _ => Err(Error::Validation(format!("Several accounts are named \"{}\", use the account identifier instead", id_or_name))),
+    }
+}
+
+/// Like `resolve`, falling back to the only account available to the credentials
+pub fn resolve_or_default(api: &Client, id_or_name: Option<&str>) -> Result<String, Error> {
+    if let Some(id_or_name) = id_or_name {
+        return resolve(api, id_or_name);
+    }
+
+    let accounts = fetch_all_accounts(api)?;
+    match accounts.len() {
+        0 => Err(Error::NotFound("No account is available with these credentials".to_string())),
+        1 => Ok(accounts[0].id.clone()),
+        _ => Err(Error::Validation("Several accounts are available, choose one with --account".to_string())),
+    }
+}
+
+fn zone_count(api: &Client, account_id: &str) -> Result<u64, Error> {
+    let success = api.request(&ListAccountZones {
+        params: ListAccountZonesParams {
+            account_id: account_id.to_string(),
+            page: Some(1),
+            per_page: Some(MIN_ZONES_PAGE_SIZE),
+        },
+    })?;
+
+    Ok(success.result_info
+        .and_then(|info| info["total_count"].as_u64())
+        .unwrap_or(success.result.zones.len() as u64))
+}
+
+// Members and subscriptions need extra permissions, the rest of the description is still useful without them
+fn optional<T>(what: &str, result: Result<Vec<T>, Error>) -> Vec<T> {
+    result.unwrap_or_else(|e| {
+        terminal::warn(format!("Could not fetch {}: {}", what, e).as_str());
+        vec![]
+    })
+}
+
+pub fn list(api: &Client, pagination: &PageOptions, format: Format) -> Result<(), Error> {
+    let list: Vec<Account> = http::paginate(api, pagination, |page, per_page| ListAccounts {
+        params: Some(ListAccountsParams {
+            page: Some(page),
+            per_page: Some(per_page),
+            direction: Some(OrderDirection::Ascending),
+        })
+    })?;
+
+    output::render(format, &list, |accounts| {
+        let columns = vec![
+            "ID",
+            "NAME"
+        ];
+        let mut table = table_from_cols(columns);
+
+        for acc in accounts {
+            table.add_row(Row::new()
+                .with_cell(&acc.id)
+                .with_cell(&acc.name));
         }
-        Err(e) => println!("{:?}", e)
+        table
+    })
+}
+
+pub fn describe(api: &Client, account_id: &str, format: Format) -> Result<(), Error> {
+    let account = api.request(&AccountDetails { identifier: account_id })?.result;
+    let zone_count = zone_count(api, account_id)?;
+
+    let members = http::paginate(api, &PageOptions::all(ALL_RESULTS_PAGE_SIZE), |page, per_page| ListAccountMembers {
+        account_identifier: account_id,
+        params: ListAccountMembersParams {
+            page: Some(page),
+            per_page: Some(per_page),
+        },
+    }).map_err(Error::from);
+    let subscriptions = api.request(&ListAccountSubscriptions { account_identifier: account_id })
+        .map(|success| success.result.subscriptions)
+        .map_err(Error::from);
+
+    let description = AccountDescription {
+        account,
+        zone_count,
+        members: optional("members", members),
+        subscriptions: optional("subscriptions", subscriptions),
+    };
+
+    if format != Format::Table {
+        return output::print(format, &description);
+    }
+
+    let account = &description.account;
+    let settings = account.settings.as_ref();
+    let mut table = Table::new("{:<}    {:<}");
+    table.add_row(Row::new().with_cell("ID").with_cell(&account.id))
+        .add_row(Row::new().with_cell("NAME").with_cell(&account.name))
+        .add_row(Row::new().with_cell("CREATED").with_cell(account.created_on.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string())))
+        .add_row(Row::new().with_cell("ZONES").with_cell(description.zone_count))
+        .add_row(Row::new().with_cell("ENFORCE 2FA").with_cell(match settings.map(|s| s.enforce_twofactor) {
+            Some(true) => "Yes",
+            Some(false) => "No",
+            None => "-",
+        }))
+        .add_row(Row::new().with_cell("CUSTOM NS BY DEFAULT").with_cell(match settings.and_then(|s| s.use_account_custom_ns_by_default) {
+            Some(true) => "Yes",
+            Some(false) => "No",
+            None => "-",
+        }));
+    print!("{}", table);
+
+    let mut members = table_from_cols(vec!["MEMBER", "EMAIL", "STATUS", "2FA", "ROLES"]);
+    for member in &description.members {
+        let user = &member.user;
+        let name = [user.first_name.as_deref(), user.last_name.as_deref()].iter()
+            .flatten()
+            .copied()
+            .collect::<Vec<&str>>()
+            .join(" ");
+        let roles: Vec<&str> = member.roles.iter().map(|r| r.name.as_str()).collect();
+
+        members.add_row(Row::new()
+            .with_cell(if name.is_empty() { "-" } else { &name })
+            .with_cell(&user.email)
+            .with_cell(&member.status)
+            .with_cell(if user.two_factor_authentication_enabled { "Yes" } else { "No" })
+            .with_cell(roles.join(", ")));
+    }
+    print!("\n{}", members);
+
+    let mut subscriptions = table_from_cols(vec!["SUBSCRIPTION", "PLAN", "PRICE", "FREQUENCY", "STATE", "RENEWS"]);
+    for subscription in &description.subscriptions {
+        let plan = subscription.rate_plan.as_ref()
+            .map(|p| p.public_name.clone().unwrap_or_else(|| p.id.clone()))
+            .unwrap_or_else(|| "-".to_string());
+        let price = match (subscription.price, &subscription.currency) {
+            (Some(price), Some(currency)) => format!("{:.2} {}", price, currency),
+            (Some(price), None) => format!("{:.2}", price),
+            _ => "-".to_string(),
+        };
+
+        subscriptions.add_row(Row::new()
+            .with_cell(&subscription.id)
+            .with_cell(plan)
+            .with_cell(price)
+            .with_cell(subscription.frequency.as_deref().unwrap_or("-"))
+            .with_cell(subscription.state.as_deref().unwrap_or("-"))
+            .with_cell(subscription.current_period_end.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string())));
     }
+    print!("\n{}", subscriptions);
+    Ok(())
 }
@@ -1,41 +1,133 @@
-use cloudflare::framework::{
-    apiclient::ApiClient,
-    HttpApiClient,
-};
+use std::fs;
+use std::io::{self, Read};
+use std::thread;
+use std::time::Duration;
+
+use cloudflare::framework::apiclient::ApiClient;
+
 use crate::api::endpoints::cache::{PurgeCache, PurgeCacheParams};
-use crate::{terminal, http};
-use clap::Values;
-use cloudflare::framework::response::ApiResponse;
+use crate::api::endpoints::zones::ZoneDetails;
+use crate::commands::{confirm, send, submit};
+use crate::error::Error;
+use crate::http::Client;
+use crate::terminal;
+
+/// Most URLs, tags, prefixes or hosts accepted by a single purge request
+pub const MAX_PURGE_ITEMS: usize = 30;
+// Pause between batches, purge requests have their own rate limit
+const BATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// What a selective purge removes from the cache
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PurgeKind {
+    Files,
+    Tags,
+    Prefixes,
+    Hosts,
+}
+
+impl PurgeKind {
+    fn params(self, items: Vec<String>) -> PurgeCacheParams {
+        let items = Some(items);
+        match self {
+            PurgeKind::Files => PurgeCacheParams { files: items, ..Default::default() },
+            PurgeKind::Tags => PurgeCacheParams { tags: items, ..Default::default() },
+            PurgeKind::Prefixes => PurgeCacheParams { prefixes: items, ..Default::default() },
+            PurgeKind::Hosts => PurgeCacheParams { hosts: items, ..Default::default() },
+        }
+    }
 
-pub fn process_response<T>(res: ApiResponse<T>) {
-    match res {
-        Ok(_) => terminal::info("Successfully purged assets. Please allow up to 30 seconds for changes to take effect."),
-        Err(e) => terminal::error(http::format_error(e, None).as_str())
+    fn noun(self) -> &'static str {
+        match self {
+            PurgeKind::Files => "URLs",
+            PurgeKind::Tags => "tags",
+            PurgeKind::Prefixes => "prefixes",
+            PurgeKind::Hosts => "hosts",
+        }
     }
 }
 
-pub fn purge_all(api: &HttpApiClient, zone_id: &str) {
-    let res = api.request(&PurgeCache {
-        zone_identifier: zone_id,
-        params: PurgeCacheParams {
-            purge_everything: Some(true),
-            files: None,
-        },
-    });
+/// Reads URLs from a file, one per line, or from stdin when the path is `-`.
+/// Blank lines and lines starting with `#` are skipped.
+pub fn read_urls(path: &str) -> Result<Vec<String>, Error> {
+    let input = if path == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input).map(|_| input)
+    } else {
+        fs::read_to_string(path)
+    };
+    let input = input.map_err(|e| Error::io(path, e))?;
+
+    Ok(input.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
 
-    process_response(res);
+// Rate limited batches are retried by the client
+fn purge_batch(api: &Client, zone_id: &str, params: PurgeCacheParams) -> Result<(), Error> {
+    send(api, &PurgeCache { zone_identifier: zone_id, params })
 }
 
-pub fn purge_url(api: &HttpApiClient, zone_id: &str, urls: Values) {
-    let files: Vec<String> = urls.map(String::from).collect();
+pub fn purge_all(api: &Client, zone_id: &str, yes: bool) -> Result<(), Error> {
+    confirm(api, yes, || {
+        let zone = api.request(&ZoneDetails { identifier: zone_id })?.result;
+        terminal::warn("Every visitor request will reach the origin until the cache fills again");
+        Ok(format!("Purge everything cached for zone \"{}\"?", zone.name))
+    })?;
 
-    let res = api.request(&PurgeCache {
+    let endpoint = PurgeCache {
         zone_identifier: zone_id,
         params: PurgeCacheParams {
-            purge_everything: None,
-            files: Some(files),
+            purge_everything: Some(true),
+            ..Default::default()
         },
-    });
+    };
+    if submit(api, &endpoint)?.is_some() {
+        terminal::info("Successfully purged assets. Please allow up to 30 seconds for changes to take effect.");
+    }
+    Ok(())
+}
+
+/// Purges URLs, tags, prefixes or hosts, split in batches of `MAX_PURGE_ITEMS`
+pub fn purge(api: &Client, zone_id: &str, kind: PurgeKind, items: Vec<String>) -> Result<(), Error> {
+    if items.is_empty() {
+        return Err(Error::Validation(format!("No {} to purge", kind.noun())));
+    }
+    if items.len() <= MAX_PURGE_ITEMS {
+        purge_batch(api, zone_id, kind.params(items))?;
+        if api.is_dry_run() {
+            return Ok(());
+        }
+        terminal::info("Successfully purged assets. Please allow up to 30 seconds for changes to take effect.");
+        return Ok(());
+    }
+
+    let batches: Vec<&[String]> = items.chunks(MAX_PURGE_ITEMS).collect();
+    let total = batches.len();
+    let mut failed = 0;
+    for (i, batch) in batches.into_iter().enumerate() {
+        if i > 0 && !api.is_dry_run() {
+            thread::sleep(BATCH_INTERVAL);
+        }
+
+        match purge_batch(api, zone_id, kind.params(batch.to_vec())) {
+            Ok(_) if api.is_dry_run() => {}
+            Ok(_) => terminal::info(format!("Batch {}/{}: purged {} {}", i + 1, total, batch.len(), kind.noun()).as_str()),
+            Err(e) => {
+                failed += 1;
+                terminal::error(format!("Batch {}/{} ({} to {}): {}", i + 1, total, batch[0], batch[batch.len() - 1], e).as_str());
+            }
+        }
+    }
 
-    process_response(res);
+    if failed > 0 {
+        return Err(Error::Partial(format!("{} of {} batches could not be purged", failed, total)));
+    }
+    if api.is_dry_run() {
+        return Ok(());
+    }
+    terminal::info(format!("Successfully purged {} {}. Please allow up to 30 seconds for changes to take effect.", items.len(), kind.noun()).as_str());
+    Ok(())
 }
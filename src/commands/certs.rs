@@ -0,0 +1,242 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use cloudflare::framework::apiclient::ApiClient;
+use openssl::ec::{EcGroup, EcKey};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use openssl::rsa::Rsa;
+use openssl::stack::Stack;
+use openssl::x509::{X509Name, X509ReqBuilder};
+use openssl::x509::extension::SubjectAlternativeName;
+use tabular::{Row, Table};
+
+use crate::api::endpoints::certificates::{
+    Certificate,
+    CertificateDetails,
+    CreateCertificate,
+    CreateCertificateParams,
+    ListCertificates,
+    ListCertificatesParams,
+    RevokeCertificate,
+};
+use crate::commands::{confirm_items, submit, table_from_cols};
+use crate::error::Error;
+use crate::http::{self, Client, PageOptions};
+use crate::output::{self, Format};
+use crate::terminal;
+
+/// Validity periods accepted by the Origin CA, in days
+pub const VALIDITY_DAYS: [&str; 7] = ["7", "30", "90", "365", "730", "1095", "5475"];
+const RSA_KEY_BITS: u32 = 2048;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyType {
+    Rsa,
+    Ecc,
+}
+
+impl KeyType {
+    fn request_type(self) -> &'static str {
+        match self {
+            KeyType::Rsa => "origin-rsa",
+            KeyType::Ecc => "origin-ecc",
+        }
+    }
+}
+
+pub struct CreateParams<'a> {
+    pub hostnames: Vec<&'a str>,
+    pub validity: u32,
+    pub key_type: KeyType,
+    pub key_file: Option<&'a str>,
+    pub cert_file: Option<&'a str>,
+    /// Overwrite existing key and certificate files
+    pub force: bool,
+}
+
+fn generate_key(key_type: KeyType) -> Result<PKey<Private>, failure::Error> {
+    let key = match key_type {
+        KeyType::Rsa => PKey::from_rsa(Rsa::generate(RSA_KEY_BITS)?)?,
+        KeyType::Ecc => {
+            let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+            PKey::from_ec_key(EcKey::generate(&group)?)?
+        }
+    };
+    Ok(key)
+}
+
+// The Origin CA takes hostnames from the request, the CSR subject is informational
+fn generate_csr(key: &PKey<Private>, hostnames: &[&str]) -> Result<String, failure::Error> {
+    let mut name = X509Name::builder()?;
+    name.append_entry_by_nid(Nid::COMMONNAME, hostnames[0])?;
+
+    let mut builder = X509ReqBuilder::new()?;
+    builder.set_pubkey(key)?;
+    builder.set_subject_name(&name.build())?;
+
+    let mut san = SubjectAlternativeName::new();
+    for hostname in hostnames {
+        san.dns(hostname);
+    }
+    let mut extensions = Stack::new()?;
+    extensions.push(san.build(&builder.x509v3_context(None))?)?;
+    builder.add_extensions(&extensions)?;
+
+    builder.sign(key, MessageDigest::sha256())?;
+    Ok(String::from_utf8(builder.build().to_pem()?)?)
+}
+
+// Files are named after the first hostname, e.g. `_wildcard.mydomain.com.key`
+fn default_path(hostname: &str, extension: &str) -> PathBuf {
+    PathBuf::from(format!("{}.{}", hostname.replace('*', "_wildcard"), extension))
+}
+
+// Writes a file only readable by the current user, which is never readable by others meanwhile
+fn write_private(path: &Path, contents: &str) -> Result<(), failure::Error> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path)?;
+
+    // The mode only applies to new files, an overwritten one keeps its permissions otherwise
+    #[cfg(unix)]
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+fn print_certificate(format: Format, cert: &Certificate) -> Result<(), Error> {
+    if format != Format::Table {
+        return output::print(format, cert);
+    }
+
+    let mut table = Table::new("{:<}    {:<}");
+    table.add_row(Row::new().with_cell("ID").with_cell(&cert.id))
+        .add_row(Row::new().with_cell("HOSTNAMES").with_cell(cert.hostnames.join(", ")))
+        .add_row(Row::new().with_cell("TYPE").with_cell(&cert.request_type))
+        .add_row(Row::new().with_cell("VALIDITY").with_cell(format!("{} days", cert.requested_validity)))
+        .add_row(Row::new().with_cell("EXPIRES").with_cell(&cert.expires_on));
+    print!("{}\n{}", table, cert.certificate);
+    Ok(())
+}
+
+pub fn create(api: &Client, params: CreateParams, format: Format) -> Result<(), Error> {
+    let key_path = params.key_file.map(PathBuf::from)
+        .unwrap_or_else(|| default_path(params.hostnames[0], "key"));
+    let cert_path = params.cert_file.map(PathBuf::from)
+        .unwrap_or_else(|| default_path(params.hostnames[0], "pem"));
+
+    if !params.force {
+        if let Some(existing) = [&key_path, &cert_path].iter().find(|p| p.exists()) {
+            return Err(Error::Validation(format!("{} already exists. Use --force to overwrite it", existing.display())));
+        }
+    }
+
+    let (key_pem, csr) = generate_key(params.key_type)
+        .and_then(|key| {
+            let csr = generate_csr(&key, &params.hostnames)?;
+            let pem = String::from_utf8(key.private_key_to_pem_pkcs8()?)?;
+            Ok((pem, csr))
+        })
+        .map_err(|e| Error::Io(format!("Could not generate the private key: {}", e)))?;
+
+    let endpoint = CreateCertificate {
+        params: CreateCertificateParams {
+            hostnames: params.hostnames.iter().map(|h| h.to_string()).collect(),
+            requested_validity: params.validity,
+            request_type: params.key_type.request_type().to_string(),
+            csr,
+        },
+    };
+    let Some(cert) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    // The private key never leaves this machine, losing it means revoking the certificate
+    write_private(&key_path, &key_pem)
+        .and_then(|_| write_private(&cert_path, &cert.certificate))
+        .map_err(|e| Error::Io(format!("Certificate {} created but could not be saved: {}", cert.id, e)))?;
+
+    if format == Format::Table {
+        terminal::info(format!("Certificate \"{}\" created, expires on {}", cert.id, cert.expires_on).as_str());
+        terminal::info(format!("Private key: {}\nCertificate: {}", key_path.display(), cert_path.display()).as_str());
+        return Ok(());
+    }
+    print_certificate(format, &cert)
+}
+
+pub fn list(api: &Client, zone_id: &str, pagination: &PageOptions, format: Format) -> Result<(), Error> {
+    let certs = http::paginate(api, pagination, |page, per_page| ListCertificates {
+        params: ListCertificatesParams {
+            zone_id: zone_id.to_string(),
+            page: Some(page),
+            per_page: Some(per_page),
+        },
+    })?;
+
+    output::render(format, &certs, |certs| {
+        let columns = vec![
+            "ID",
+            "HOSTNAMES",
+            "TYPE",
+            "VALIDITY",
+            "EXPIRES",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for cert in certs {
+            table.add_row(Row::new()
+                .with_cell(&cert.id)
+                .with_cell(cert.hostnames.join(", "))
+                .with_cell(&cert.request_type)
+                .with_cell(format!("{} days", cert.requested_validity))
+                .with_cell(&cert.expires_on));
+        }
+        table
+    })
+}
+
+pub fn get(api: &Client, id: &str, cert_file: Option<&str>, format: Format) -> Result<(), Error> {
+    let cert = api.request(&CertificateDetails { identifier: id })?.result;
+
+    match cert_file {
+        Some(path) => {
+            fs::write(path, &cert.certificate).map_err(|e| Error::io(path, e))?;
+            terminal::info(format!("Certificate written to {}", path).as_str());
+            Ok(())
+        }
+        None => print_certificate(format, &cert),
+    }
+}
+
+pub fn revoke(api: &Client, ids: Vec<&str>, yes: bool) -> Result<(), Error> {
+    confirm_items(api, yes, "Revoke", "certificate", "certificates", &ids)?;
+
+    let total = ids.len();
+    let mut failed = 0;
+
+    for id in ids {
+        let endpoint = RevokeCertificate { identifier: id };
+        match submit(api, &endpoint) {
+            Ok(None) => {}
+            Ok(Some(cert)) => terminal::info(format!("Certificate \"{}\" revoked", cert.id).as_str()),
+            Err(e) if total == 1 => return Err(e.into()),
+            Err(e) => {
+                failed += 1;
+                terminal::error(format!("Certificate \"{}\": {}", id, Error::from(e)).as_str());
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Partial(format!("{} of {} certificates could not be revoked", failed, total)));
+    }
+    Ok(())
+}
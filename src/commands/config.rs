@@ -6,39 +6,164 @@ use cloudflare::endpoints::user::{
 use cloudflare::framework::{
     apiclient::ApiClient,
     auth::Credentials,
-    Environment,
-    HttpApiClient,
-    HttpApiClientConfig,
 };
 use failure;
+use tabular::Row;
 
 use crate::{http, terminal};
+use crate::http::{Client, ClientOptions};
+use crate::commands::table_from_cols;
 use crate::config::{Config, Context, GlobalCredential, get_global_config_path};
+use crate::error::Error;
+use crate::output::{self, Format};
 
-pub fn save_credential(cred: &GlobalCredential) -> Result<(), failure::Error> {
-    terminal::info("Validating credentials...");
-    validate_credentials(&cred)?;
+const DEFAULT_CONTEXT: &str = "default";
 
-    let context = Context { name: "default".to_string(), credential: cred.to_owned() };
-    let config = Config {
-        current_context: "default".to_string(),
-        contexts: vec![context],
-    };
+// Reads the config file or starts a new one when it does not exist yet
+fn load_config() -> Result<Config, failure::Error> {
+    let config_path = get_global_config_path()?;
+
+    if config_path.exists() {
+        Config::from_file(config_path)
+    } else {
+        Ok(Config::default())
+    }
+}
 
+fn write_config(config: &Config) -> Result<(), failure::Error> {
     let config_path = get_global_config_path()?;
 
     config.to_file(config_path.as_path())
 }
 
+// Stores the credential in the given context, or in the current one when no name is provided.
+// The API URL of the context is kept unless a new one is given. Other contexts in the config file
+// are left untouched.
+pub fn save_credential(cred: &GlobalCredential, context: Option<&str>, api_url: Option<&str>) -> Result<(), failure::Error> {
+    let mut config = load_config()?;
+    let name = match context {
+        Some(name) => name.to_string(),
+        None if config.current_context.is_empty() => DEFAULT_CONTEXT.to_string(),
+        None => config.current_context.clone(),
+    };
+    let api_url = api_url.map(String::from)
+        .or_else(|| config.context(Some(&name)).ok().and_then(|c| c.api_url.clone()));
+
+    terminal::info("Validating credentials...");
+    validate_credentials(cred, api_url.as_deref())?;
+
+    config.set_context(Context { name: name.clone(), api_url, credential: cred.to_owned() })?;
+    if config.current_context.is_empty() {
+        config.use_context(&name)?;
+    }
+
+    write_config(&config)?;
+    terminal::info(format!("Credentials saved to context \"{}\"", name).as_str());
+    Ok(())
+}
+
+pub fn add_context(name: &str, cred: &GlobalCredential, api_url: Option<&str>) -> Result<(), failure::Error> {
+    let mut config = load_config()?;
+    config.add_context(Context { name: name.to_string(), api_url: api_url.map(String::from), credential: cred.to_owned() })?;
+
+    terminal::info("Validating credentials...");
+    validate_credentials(cred, api_url)?;
+
+    write_config(&config)?;
+    terminal::info(format!("Context \"{}\" added", name).as_str());
+    Ok(())
+}
+
+pub fn use_context(name: &str) -> Result<(), failure::Error> {
+    let mut config = load_config()?;
+    config.use_context(name)?;
+
+    write_config(&config)?;
+    terminal::info(format!("Switched to context \"{}\"", name).as_str());
+    Ok(())
+}
+
+pub fn rename_context(name: &str, new_name: &str) -> Result<(), failure::Error> {
+    let mut config = load_config()?;
+    config.rename_context(name, new_name)?;
+
+    write_config(&config)?;
+    terminal::info(format!("Context \"{}\" renamed to \"{}\"", name, new_name).as_str());
+    Ok(())
+}
+
+pub fn delete_context(name: &str) -> Result<(), failure::Error> {
+    let mut config = load_config()?;
+    config.delete_context(name)?;
+
+    write_config(&config)?;
+    terminal::info(format!("Context \"{}\" deleted", name).as_str());
+    if config.current_context.is_empty() && !config.contexts.is_empty() {
+        terminal::warn("No current context is set. Select one with `cflare config use-context`");
+    }
+    Ok(())
+}
+
+/// What `config get-contexts` shows of a context, leaving out its secrets
+#[derive(Serialize)]
+pub struct ContextSummary {
+    pub name: String,
+    pub current: bool,
+    /// `api_token` or `global_key`
+    pub auth: &'static str,
+    /// Email of a Global API Key
+    pub email: Option<String>,
+    pub api_url: Option<String>,
+}
+
+pub fn get_contexts(format: Format) -> Result<(), Error> {
+    let config = load_config().map_err(Error::config)?;
+    let current_context = config.current_context;
+    let contexts: Vec<ContextSummary> = config.contexts.into_iter().map(|context| {
+        let (auth, email) = match context.credential {
+            GlobalCredential::Token { .. } => ("api_token", None),
+            GlobalCredential::GlobalKey { email, .. } => ("global_key", Some(email)),
+        };
+        ContextSummary {
+            current: context.name == current_context,
+            name: context.name,
+            auth,
+            email,
+            api_url: context.api_url,
+        }
+    }).collect();
+
+    output::render(format, &contexts, |contexts| {
+        let columns = vec![
+            "CURRENT",
+            "NAME",
+            "AUTH",
+            "API URL",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for context in contexts {
+            let auth = match &context.email {
+                Some(email) => format!("Global API Key ({})", email),
+                None => "API Token".to_string(),
+            };
+
+            table.add_row(Row::new()
+                .with_cell(if context.current { "*" } else { "" })
+                .with_cell(&context.name)
+                .with_cell(auth)
+                .with_cell(context.api_url.as_deref().unwrap_or("-")));
+        }
+        table
+    })
+}
+
 // validate_credentials() checks the /user/tokens/verify endpoint (for API token)
 // or /user endpoint (for global API key) to ensure provided credentials actually work.
 // Source: https://github.com/cloudflare/wrangler/
-pub fn validate_credentials(credential: &GlobalCredential) -> Result<(), failure::Error> {
-    let client = HttpApiClient::new(
-        Credentials::from(credential.to_owned()),
-        HttpApiClientConfig::default(),
-        Environment::Production,
-    )?;
+pub fn validate_credentials(credential: &GlobalCredential, api_url: Option<&str>) -> Result<(), failure::Error> {
+    let environment = http::environment(api_url).map_err(failure::err_msg)?;
+    let client = Client::new(Credentials::from(credential.to_owned()), environment, ClientOptions::default())?;
 
     match credential {
         GlobalCredential::Token { .. } => {
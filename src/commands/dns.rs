@@ -1,36 +1,171 @@
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::collections::HashMap;
+use std::fs;
 
 use cloudflare::endpoints::dns::{
-    CreateDnsRecord,
-    CreateDnsRecordParams,
     DeleteDnsRecord,
-    DeleteDnsRecordResponse,
+    ListDnsRecordsOrder,
+};
+use cloudflare::framework::apiclient::ApiClient;
+use cloudflare::framework::response::ApiFailure;
+use cloudflare::framework::{OrderDirection, SearchMatch};
+use regex::Regex;
+use tabular::{Row, Table};
+
+use crate::commands::{confirm, for_each_id, send, submit, table_from_cols};
+use crate::error::Error;
+use crate::api::endpoints::dns::{
+    self as endpoints,
+    CreateDnsRecord,
     DnsContent,
-    DnsRecord,
+    DnsRecordDetails,
+    DnsRecordParams,
     ListDnsRecords,
     ListDnsRecordsParams,
+    RECORD_TYPES,
     UpdateDnsRecord,
-    UpdateDnsRecordParams,
-};
-use cloudflare::framework::{
-    apiclient::ApiClient,
-    HttpApiClient,
 };
-use tabular::Row;
+use crate::output::{self, Format};
+use crate::{http, terminal, zonefile};
+use crate::http::{Client, PageOptions};
+
+const ALL_RECORDS_PAGE_SIZE: u32 = 100;
 
-use crate::commands::table_from_cols;
-use crate::api::endpoints::dns::DnsRecordDetails;
+/// Flags that make up the content of record types with several fields, in RDATA order
+const RECORD_FIELDS: [(&str, &[&str]); 13] = [
+    ("MX", &["priority", "target"]),
+    ("SRV", &["priority", "weight", "port", "target"]),
+    ("CAA", &["caa-flags", "caa-tag", "caa-value"]),
+    ("TLSA", &["usage", "selector", "matching-type", "certificate"]),
+    ("SMIMEA", &["usage", "selector", "matching-type", "certificate"]),
+    ("HTTPS", &["priority", "target", "svc-params"]),
+    ("SVCB", &["priority", "target", "svc-params"]),
+    ("DS", &["key-tag", "algorithm", "digest-type", "digest"]),
+    ("DNSKEY", &["flags", "protocol", "algorithm", "public-key"]),
+    ("CERT", &["cert-type", "key-tag", "algorithm", "certificate"]),
+    ("URI", &["priority", "weight", "target"]),
+    ("NAPTR", &["order", "preference", "naptr-flags", "service", "regex", "replacement"]),
+    ("SSHFP", &["algorithm", "fingerprint-type", "fingerprint"]),
+];
+
+/// Every structured record flag, `--priority` aside
+pub const FIELD_FLAGS: [&str; 29] = [
+    "srv-service", "srv-proto", "weight", "port", "target", "caa-flags", "caa-tag", "caa-value",
+    "usage", "selector", "matching-type", "certificate", "svc-params", "key-tag", "algorithm",
+    "digest-type", "digest", "flags", "protocol", "public-key", "cert-type", "order", "preference",
+    "naptr-flags", "service", "regex", "replacement", "fingerprint-type", "fingerprint",
+];
+
+/// Values of the structured record flags, by flag name
+pub type RecordFields<'a> = HashMap<&'static str, &'a str>;
 
 pub struct ListParams<'a, 'b> {
     pub zone_id: &'a str,
-    pub page: u32,
-    pub limit: u32,
+    pub pagination: PageOptions,
     pub wide: bool,
+    pub format: Format,
     pub filters: ListFilters<'b>,
+    pub sort: Option<ListSort>,
 }
 
+/// Fields records can be listed by
+pub enum SortField {
+    Name,
+    Type,
+    Ttl,
+    Modified,
+}
+
+pub struct ListSort {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+/// Filters of the records listed or deleted. Empty filters match every record.
+#[derive(Default)]
 pub struct ListFilters<'a> {
-    pub all: Option<&'a str>
+    /// Part of the name
+    pub all: Option<&'a str>,
+    /// Exact name
+    pub name: Option<&'a str>,
+    pub record_type: Option<&'a str>,
+    /// Exact content
+    pub content: Option<&'a str>,
+    /// Pattern of the content, where `*` matches any characters and `?` a single one
+    pub content_match: Option<&'a str>,
+    pub proxied: Option<bool>,
+    /// Matched against the name and content, for searches the API can't express
+    pub regex: Option<Regex>,
+    /// Whether records must match every filter sent to the API, or any of them
+    pub search_match: Option<SearchMatch>,
+}
+
+impl<'a> ListFilters<'a> {
+    pub fn is_empty(&self) -> bool {
+        self.all.is_none() && self.name.is_none() && self.record_type.is_none() && self.content.is_none()
+            && self.content_match.is_none() && self.proxied.is_none() && self.regex.is_none()
+    }
+
+    // Filters applied by the API, content patterns and regular expressions are matched here
+    fn params(&self) -> ListDnsRecordsParams {
+        ListDnsRecordsParams {
+            record_type: self.record_type.map(String::from),
+            name: self.name.map(String::from).or_else(|| self.all.map(|n| format!("contains:{}", n))),
+            content: self.content.map(String::from),
+            proxied: self.proxied,
+            search_match: self.search_match.clone(),
+            ..Default::default()
+        }
+    }
+
+    // Whether some records are only filtered out once fetched
+    fn is_local(&self) -> bool {
+        self.content_match.is_some() || self.regex.is_some()
+    }
+
+    fn matches(&self, record: &endpoints::DnsRecord) -> bool {
+        let content = display_content(&record.content);
+
+        // TXT contents may be quoted or not, the pattern is meant for the text itself
+        let content_matches = self.content_match.is_none_or(|pattern| {
+            glob_match(pattern, &content) || glob_match(pattern, content.trim_matches('"'))
+        });
+        let regex_matches = self.regex.as_ref().is_none_or(|regex| {
+            regex.is_match(&record.name) || regex.is_match(&content)
+        });
+
+        content_matches && regex_matches
+    }
+}
+
+// Whether the whole text matches the pattern, where `*` matches any characters and `?` one
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and of the text it was tried at, to backtrack to
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
 }
 
 pub struct CreateParams<'a> {
@@ -38,9 +173,10 @@ pub struct CreateParams<'a> {
     pub name: &'a str,
     pub ttl: u32,
     pub proxied: bool,
-    pub content: &'a str,
+    pub content: Option<&'a str>,
     pub record_type: &'a str,
     pub priority: u16,
+    pub fields: RecordFields<'a>,
 }
 
 pub struct UpdateParams<'a> {
@@ -50,217 +186,527 @@ pub struct UpdateParams<'a> {
     pub ttl: Option<u32>,
     pub proxied: Option<bool>,
     pub content: Option<&'a str>,
+    pub priority: Option<u16>,
+    pub fields: RecordFields<'a>,
+}
+
+pub struct UpsertParams<'a> {
+    pub zone_id: &'a str,
+    pub zone_name: &'a str,
+    pub name: &'a str,
+    pub record_type: &'a str,
+    pub ttl: Option<u32>,
+    pub proxied: Option<bool>,
+    pub content: Option<&'a str>,
+    pub priority: Option<u16>,
+    pub fields: RecordFields<'a>,
 }
 
-fn resolve_content(record_type: &str, content: &str, priority: u16) -> Result<DnsContent, &'static str> {
-    match record_type {
-        "A" => {
-            let ip: Option<Ipv4Addr> = content.parse().ok();
+fn record_fields(record_type: &str) -> Option<&'static [&'static str]> {
+    RECORD_FIELDS.iter().find(|(t, _)| *t == record_type).map(|(_, fields)| *fields)
+}
 
-            match ip {
-                Some(content) => Ok(DnsContent::A { content }),
-                None => Err("Invalid IPv4 address")
-            }
-        }
-        "AAAA" => {
-            let ip: Option<Ipv6Addr> = content.parse().ok();
+fn field_value(flag: &str, priority: Option<u16>, fields: &RecordFields) -> Option<String> {
+    match flag {
+        "priority" => priority.map(|p| p.to_string()),
+        _ => fields.get(flag).map(|v| v.to_string()),
+    }
+}
 
-            match ip {
-                Some(content) => Ok(DnsContent::AAAA { content }),
-                None => Err("Invalid IPv6 address")
-            }
-        }
-        "CNAME" => Ok(DnsContent::CNAME { content: String::from(content) }),
-        "MX" => Ok(DnsContent::MX { content: String::from(content), priority }),
-        "TXT" => Ok(DnsContent::TXT { content: String::from(content) }),
-        "NS" => Ok(DnsContent::CNAME { content: String::from(content) }),
-        _ => Err("Record type not supported")
+/// Builds record content from `--content`, or from the structured flags of the record type
+pub fn record_content(record_type: &str, content: Option<&str>, priority: Option<u16>, fields: &RecordFields) -> Result<DnsContent, String> {
+    if let Some(content) = content {
+        return zonefile::parse_content(record_type, content, priority);
     }
+
+    let flags = record_fields(record_type)
+        .ok_or_else(|| format!("--content is required for {} records", record_type))?;
+    let tokens = flags.iter().map(|flag| {
+        let default = match *flag {
+            "caa-flags" => Some("0"),
+            "protocol" => Some("3"),
+            "svc-params" => Some(""),
+            _ => None,
+        };
+        field_value(flag, priority, fields)
+            .or_else(|| default.map(String::from))
+            .ok_or_else(|| format!("Either --content or --{} is required for {} records", flag, record_type))
+    }).collect::<Result<Vec<String>, String>>()?;
+
+    zonefile::parse_rdata(record_type, &tokens, None)
 }
 
-pub fn list(api: &HttpApiClient, params: ListParams) {
-    let name = match params.filters.all {
-        Some(n) => Some(format!("contains:{}", n)),
-        _ => None
+// Applies the new content and structured flags of an update over the current content
+fn updated_content(current: DnsContent, content: Option<&str>, priority: Option<u16>, fields: &RecordFields) -> Result<DnsContent, String> {
+    let record_type = current.record_type();
+    let content = match content {
+        Some(content) => zonefile::parse_content(record_type, content, priority.or_else(|| current.priority()))?,
+        None => current,
     };
 
-    let response = api.request(&ListDnsRecords {
-        zone_identifier: params.zone_id,
+    let overrides: Vec<(usize, String)> = record_fields(record_type).unwrap_or(&[]).iter()
+        .enumerate()
+        .filter_map(|(i, flag)| field_value(flag, priority, fields).map(|value| (i, value)))
+        .collect();
+    if overrides.is_empty() {
+        return Ok(content);
+    }
+
+    let (_, data) = zonefile::rdata(&content);
+    let mut tokens = zonefile::split_rdata(&data).map_err(|e| e.to_string())?;
+    for (i, value) in overrides {
+        if i < tokens.len() {
+            tokens[i] = value;
+        } else {
+            tokens.push(value);
+        }
+    }
+    zonefile::parse_rdata(record_type, &tokens, None)
+}
+
+// Service and protocol of SRV records are part of their name, e.g. `_sip._tcp.example.com`
+fn srv_name(name: &str, fields: &RecordFields) -> Result<String, String> {
+    let label = |value: &str| format!("_{}", value.trim_start_matches('_'));
+
+    match (fields.get("srv-service"), fields.get("srv-proto")) {
+        (Some(service), Some(proto)) => Ok(format!("{}.{}.{}", label(service), label(proto), name)),
+        (None, None) => Ok(name.to_string()),
+        _ => Err("--srv-service and --srv-proto must be used together".to_string()),
+    }
+}
+
+// Fully qualified name of a record given relative to the zone, or already qualified
+fn qualified_name(name: &str, zone_name: &str) -> String {
+    let lower = name.to_lowercase();
+    let zone = zone_name.to_lowercase();
+    if lower == zone || lower.ends_with(&format!(".{}", zone)) {
+        name.to_string()
+    } else {
+        zonefile::absolute_name(name, zone_name)
+    }
+}
+
+// Single-value records show their content as is, others in zone file format
+fn display_content(content: &DnsContent) -> String {
+    match content {
+        DnsContent::A { content } => content.to_string(),
+        DnsContent::AAAA { content } => content.to_string(),
+        DnsContent::CNAME { content } | DnsContent::NS { content } | DnsContent::PTR { content }
+        | DnsContent::MX { content, .. } | DnsContent::TXT { content } | DnsContent::SPF { content }
+        | DnsContent::OPENPGPKEY { content } => content.clone(),
+        other => zonefile::rdata(other).1,
+    }
+}
+
+#[derive(Serialize)]
+pub struct ImportResult {
+    pub line: usize,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub record_type: String,
+    pub content: String,
+    pub status: ImportStatus,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStatus {
+    Created,
+    Skipped,
+    Failed,
+}
+
+// Fetches the records of a zone matching the filters. The API can't sort by modification date,
+// so those are sorted once fetched. When records are filtered or sorted here, every page is fetched
+// and the page and limit apply to the result.
+fn fetch_records(api: &Client, zone_id: &str, filters: &ListFilters, sort: Option<&ListSort>, pagination: &PageOptions) -> Result<Vec<endpoints::DnsRecord>, ApiFailure> {
+    let mut query = filters.params();
+    if let Some(sort) = sort {
+        query.order = match sort.field {
+            SortField::Name => Some(ListDnsRecordsOrder::Name),
+            SortField::Type => Some(ListDnsRecordsOrder::Type),
+            SortField::Ttl => Some(ListDnsRecordsOrder::Ttl),
+            SortField::Modified => None,
+        };
+        if query.order.is_some() {
+            query.direction = Some(if sort.descending { OrderDirection::Descending } else { OrderDirection::Ascending });
+        }
+    }
+    let sorted_here = matches!(sort, Some(ListSort { field: SortField::Modified, .. }));
+    let local = filters.is_local() || sorted_here;
+    let fetched = if local { PageOptions::all(pagination.per_page) } else { pagination.clone() };
+
+    let records = http::paginate(api, &fetched, |page, per_page| ListDnsRecords {
+        zone_identifier: zone_id,
         params: ListDnsRecordsParams {
-            record_type: None,
-            name,
-            page: Some(params.page),
-            per_page: Some(params.limit),
-            order: None,
-            direction: None,
-            search_match: None,
+            page: Some(page),
+            per_page: Some(per_page),
+            ..query.clone()
         },
-    });
-
-    match response {
-        Ok(success) => {
-            let list: Vec<DnsRecord> = success.result;
-
-            let columns = if params.wide {
-                vec![
-                    "ID",
-                    "NAME",
-                    "TYPE",
-                    "CONTENT",
-                    "TTL",
-                    "PROXY",
-                    "LOCKED",
-                    "CREATED",
-                    "MODIFIED",
-                ]
-            } else {
-                vec![
-                    "ID",
-                    "NAME",
-                    "TYPE",
-                    "CONTENT",
-                    "TTL",
-                    "PROXY",
-                ]
-            };
-
-            let mut table = table_from_cols(columns);
-
-            for record in list {
-                let mut row = Row::new().with_cell(record.id).with_cell(record.name);
-
-                match record.content {
-                    DnsContent::A { content: c } => row.add_cell("A").add_cell(c),
-                    DnsContent::AAAA { content: c } => row.add_cell("AAAA").add_cell(c),
-                    DnsContent::CNAME { content: c } => row.add_cell("CNAME").add_cell(c),
-                    DnsContent::NS { content: c } => row.add_cell("NS").add_cell(c),
-                    DnsContent::MX { content: c, priority: _ } => row.add_cell("MX").add_cell(c),
-                    DnsContent::TXT { content: c } => row.add_cell("TXT").add_cell(c),
-                };
-
-                let ttl = format!("{}", record.ttl);
-                row.add_cell(if ttl == "1" { "Auto" } else { &ttl })
-                    .add_cell(if record.proxied { "Yes" } else { "No" });
-
-                if params.wide {
-                    row.add_cell(if record.locked { "Yes" } else { "No" })
-                        .add_cell(record.created_on)
-                        .add_cell(record.modified_on);
-                }
+    })?;
+    if !local {
+        return Ok(records);
+    }
 
-                table.add_row(row);
-            }
-            print!("{}", table);
+    let mut records: Vec<endpoints::DnsRecord> = records.into_iter().filter(|r| filters.matches(r)).collect();
+    if let Some(ListSort { field: SortField::Modified, descending }) = sort {
+        records.sort_by_key(|r| r.modified_on);
+        if *descending {
+            records.reverse();
         }
-        Err(e) => println!("{:?}", e)
     }
+    Ok(http::page_of(records, pagination))
 }
 
-pub fn create(api: &HttpApiClient, record: CreateParams) {
-    let content = match resolve_content(record.record_type, record.content, record.priority) {
-        Ok(c) => c,
-        Err(e) => {
-            println!("{}", e);
-            return;
+// Fetches every record of a zone
+pub(crate) fn fetch_all_records(api: &Client, zone_id: &str) -> Result<Vec<endpoints::DnsRecord>, ApiFailure> {
+    fetch_records(api, zone_id, &ListFilters::default(), None, &PageOptions::all(ALL_RECORDS_PAGE_SIZE))
+}
+
+// ID, name, type and content of records, shown before changing them
+fn records_table(records: &[endpoints::DnsRecord]) -> Table {
+    let mut table = table_from_cols(vec!["ID", "NAME", "TYPE", "CONTENT"]);
+    for record in records {
+        table.add_row(Row::new()
+            .with_cell(&record.id)
+            .with_cell(&record.name)
+            .with_cell(record.content.record_type())
+            .with_cell(display_content(&record.content)));
+    }
+    table
+}
+
+pub fn list(api: &Client, params: ListParams) -> Result<(), Error> {
+    let list = fetch_records(api, params.zone_id, &params.filters, params.sort.as_ref(), &params.pagination)?;
+
+    output::render(params.format, &list, |records| {
+        let columns = if params.wide {
+            vec![
+                "ID",
+                "NAME",
+                "TYPE",
+                "CONTENT",
+                "TTL",
+                "PROXY",
+                "LOCKED",
+                "CREATED",
+                "MODIFIED",
+            ]
+        } else {
+            vec![
+                "ID",
+                "NAME",
+                "TYPE",
+                "CONTENT",
+                "TTL",
+                "PROXY",
+            ]
+        };
+
+        let mut table = table_from_cols(columns);
+
+        for record in records {
+            let mut row = Row::new().with_cell(&record.id).with_cell(&record.name);
+
+            row.add_cell(record.content.record_type())
+                .add_cell(display_content(&record.content));
+
+            let ttl = format!("{}", record.ttl);
+            row.add_cell(if ttl == "1" { "Auto" } else { &ttl })
+                .add_cell(if record.proxied { "Yes" } else { "No" });
+
+            if params.wide {
+                row.add_cell(if record.locked { "Yes" } else { "No" })
+                    .add_cell(record.created_on)
+                    .add_cell(record.modified_on);
+            }
+
+            table.add_row(row);
         }
-    };
+        table
+    })
+}
+
+pub fn create(api: &Client, record: CreateParams) -> Result<(), Error> {
+    let content = record_content(record.record_type, record.content, Some(record.priority), &record.fields)
+        .map_err(Error::Validation)?;
+    let name = srv_name(record.name, &record.fields).map_err(Error::Validation)?;
 
-    let response = api.request(&CreateDnsRecord {
+    let endpoint = CreateDnsRecord {
         zone_identifier: record.zone_id,
-        params: CreateDnsRecordParams {
+        params: DnsRecordParams {
             ttl: Some(record.ttl),
-            priority: None,
             proxied: Some(record.proxied),
-            name: &record.name,
+            name: &name,
             content,
         },
-    });
+    };
+    let Some(record) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+    // @todo print complete record formatted
+    println!("Record \"{}\" created", record.id);
+    Ok(())
+}
+
+fn delete_question(count: usize) -> String {
+    if count == 1 { "Delete this record?".to_string() } else { format!("Delete these {} records?", count) }
+}
 
-    match response {
-        Ok(success) => {
-            let record: DnsRecord = success.result;
-            // @todo print complete record formatted
-            println!("Record \"{}\" created", record.id)
+pub fn delete(api: &Client, zone_id: &str, ids: Vec<&str>, yes: bool) -> Result<(), Error> {
+    confirm(api, yes, || {
+        let records = ids.iter()
+            .map(|id| api.request(&DnsRecordDetails { zone_identifier: zone_id, identifier: id }).map(|r| r.result))
+            .collect::<Result<Vec<endpoints::DnsRecord>, _>>()?;
+        print!("{}", records_table(&records));
+        Ok(delete_question(ids.len()))
+    })?;
+
+    delete_records(api, zone_id, ids)
+}
+
+/// Deletes the records matching the filters, after showing them
+pub fn delete_matching(api: &Client, zone_id: &str, filters: ListFilters, yes: bool) -> Result<(), Error> {
+    if filters.is_empty() {
+        return Err(Error::Validation("Give the identifiers of the records to delete, or filters matching them".to_string()));
+    }
+
+    let records = fetch_records(api, zone_id, &filters, None, &PageOptions::all(ALL_RECORDS_PAGE_SIZE))?;
+    if records.is_empty() {
+        terminal::info("No records match the filters, nothing to delete");
+        return Ok(());
+    }
+
+    print!("{}", records_table(&records));
+    confirm(api, yes, || Ok(delete_question(records.len())))?;
+    delete_records(api, zone_id, records.iter().map(|r| r.id.as_str()).collect())
+}
+
+fn delete_records(api: &Client, zone_id: &str, ids: Vec<&str>) -> Result<(), Error> {
+    for_each_id(api, ids, "Record", "records", "deleted", |id| {
+        send(api, &DeleteDnsRecord { zone_identifier: zone_id, identifier: id })
+    })
+}
+
+pub fn update(api: &Client, input: UpdateParams) -> Result<(), Error> {
+    let record: endpoints::DnsRecord = api.request(&DnsRecordDetails {
+        zone_identifier: input.zone_id,
+        identifier: input.id,
+    })?.result;
+
+    update_record(api, record, input)
+}
+
+// Updates a record with the values given, keeping the current ones for the others
+fn update_record(api: &Client, record: endpoints::DnsRecord, input: UpdateParams) -> Result<(), Error> {
+    let content = updated_content(record.content, input.content, input.priority, &input.fields)
+        .map_err(Error::Validation)?;
+
+    let name: &str = match input.name {
+        Some(n) => n,
+        None => &record.name
+    };
+    let endpoint = UpdateDnsRecord {
+        zone_identifier: input.zone_id,
+        identifier: input.id,
+        params: DnsRecordParams {
+            ttl: input.ttl.or(Some(record.ttl)),
+            proxied: input.proxied.or(Some(record.proxied)),
+            name,
+            content,
+        },
+    };
+    let Some(record) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+    println!("Record {} updated", record.id);
+    Ok(())
+}
+
+/// Updates the record with the given name and type, or creates it when there is none
+pub fn upsert(api: &Client, input: UpsertParams) -> Result<(), Error> {
+    let name = qualified_name(input.name, input.zone_name);
+    let full_name = srv_name(&name, &input.fields).map_err(Error::Validation)?;
+
+    let filters = ListFilters {
+        name: Some(&full_name),
+        record_type: Some(input.record_type),
+        ..Default::default()
+    };
+    let mut records = fetch_records(api, input.zone_id, &filters, None, &PageOptions::all(ALL_RECORDS_PAGE_SIZE))?;
+
+    match records.len() {
+        0 => create(api, CreateParams {
+            zone_id: input.zone_id,
+            name: &name,
+            ttl: input.ttl.unwrap_or(1),
+            proxied: input.proxied.unwrap_or(false),
+            content: input.content,
+            record_type: input.record_type,
+            priority: input.priority.unwrap_or(0),
+            fields: input.fields,
+        }),
+        1 => {
+            let record = records.remove(0);
+            let id = record.id.clone();
+            update_record(api, record, UpdateParams {
+                id: &id,
+                zone_id: input.zone_id,
+                name: None,
+                ttl: input.ttl,
+                proxied: input.proxied,
+                content: input.content,
+                priority: input.priority,
+                fields: input.fields,
+            })
         }
-        // @todo abstract error formatting
-        Err(failure) => println!("An error occurred {:?}", failure)
+        n => Err(Error::Validation(format!(
+            "{} {} records are named \"{}\", use dns update with the identifier of one",
+            n, input.record_type, full_name
+        ))),
     }
 }
 
-pub fn delete(api: &HttpApiClient, zone_id: &str, ids: Vec<&str>) {
-    for id in ids {
-        let response = api.request(&DeleteDnsRecord {
-            zone_identifier: zone_id,
-            identifier: id,
-        });
+pub fn export(api: &Client, zone_id: &str, zone_name: &str, file: Option<&str>) -> Result<(), Error> {
+    let records = fetch_all_records(api, zone_id)?;
+    let zone = zonefile::render(zone_name, &records);
 
-        match response {
-            Ok(success) => {
-                let record: DeleteDnsRecordResponse = success.result;
-                println!("Record \"{}\" deleted", record.id)
-            }
-            // @todo abstract error formatting
-            Err(failure) => println!("An error occurred {:?}", failure)
+    match file {
+        Some(path) => {
+            fs::write(path, zone).map_err(|e| Error::io(path, e))?;
+            terminal::info(format!("Exported {} records to {}", records.len(), path).as_str());
         }
+        None => print!("{}", zone),
     }
+    Ok(())
 }
 
-pub fn update(api: &HttpApiClient, input: UpdateParams) {
-    let get_response = api.request(&DnsRecordDetails {
-        zone_identifier: input.zone_id,
-        identifier: input.id,
-    });
-
-    match get_response {
-        Err(failure) => println!("{:?}", failure),
-        Ok(success) => {
-            let record: DnsRecord = success.result;
-
-            let record_type = match record.content {
-                DnsContent::A { content: _ } => "A",
-                DnsContent::AAAA { content: _ } => "AAAA",
-                DnsContent::CNAME { content: _ } => "CNAME",
-                DnsContent::NS { content: _ } => "NS",
-                DnsContent::MX { content: _, priority: _ } => "MX",
-                DnsContent::TXT { content: _ } => "TXT",
-            };
-
-            let content = match input.content {
-                Some(content) => {
-                    match resolve_content(record_type, content, 1) {
-                        Ok(resolved) => resolved,
-                        Err(e) => {
-                            println!("{}", e);
-                            return;
-                        }
-                    }
-                }
-                None => record.content
-            };
-
-            let name: &str = match input.name {
-                Some(n) => n,
-                None => &record.name
-            };
-            let response = api.request(&UpdateDnsRecord {
-                zone_identifier: input.zone_id,
-                identifier: input.id,
-                params: UpdateDnsRecordParams {
-                    ttl: input.ttl.or(Some(record.ttl)),
-                    proxied: input.proxied.or(Some(record.proxied)),
-                    name,
-                    content,
-                },
-            });
-
-            match response {
-                Ok(success) => {
-                    let record: DnsRecord = success.result;
-                    println!("Record {} updated", record.id)
+// Builds the content of a zone file entry, resolving relative targets against its origin
+fn entry_content(entry: &zonefile::Entry) -> Result<DnsContent, String> {
+    let target = match entry.record_type.as_str() {
+        "CNAME" | "NS" | "PTR" => Some(0),
+        "MX" | "HTTPS" | "SVCB" => Some(1),
+        "SRV" => Some(3),
+        "NAPTR" => Some(5),
+        _ => None,
+    };
+
+    let mut data = entry.data.clone();
+    if let Some(name) = target.and_then(|i| data.get_mut(i)) {
+        if name != "." {
+            *name = zonefile::absolute_name(name, &entry.origin);
+        }
+    }
+    zonefile::parse_rdata(&entry.record_type, &data, None)
+}
+
+// `zone_name` is the origin of the file until it sets one with `$ORIGIN`
+pub fn import(api: &Client, zone_id: &str, zone_name: &str, input: &str, format: Format) -> Result<(), Error> {
+    let existing = fetch_all_records(api, zone_id)?;
+    let origin = zone_name.trim_end_matches('.').to_string();
+
+    let entries = zonefile::parse(input, &origin)
+        .map_err(|e| Error::Validation(format!("Invalid zone file: {}", e)))?;
+
+    let mut results = vec![];
+    for entry in entries {
+        let mut result = ImportResult {
+            line: entry.line,
+            name: entry.name.clone(),
+            record_type: entry.record_type.clone(),
+            content: entry.data.join(" "),
+            status: ImportStatus::Skipped,
+            message: None,
+        };
+
+        let content = match entry.record_type.as_str() {
+            "SOA" => Err("SOA record is managed by Cloudflare".to_string()),
+            "NS" if entry.name == origin => Err("Apex NS records are managed by Cloudflare".to_string()),
+            record_type if !RECORD_TYPES.contains(&record_type) => Err("Record type not supported".to_string()),
+            _ => {
+                let content = entry_content(&entry);
+                if content.is_err() {
+                    result.status = ImportStatus::Failed;
                 }
-                // @todo abstract error formatting
-                Err(failure) => println!("An error occurred {:?}", failure)
+                content
+            }
+        };
+
+        let content = match content {
+            Ok(content) => content,
+            Err(message) => {
+                result.message = Some(message);
+                results.push(result);
+                continue;
+            }
+        };
+
+        let (_, data) = zonefile::rdata(&content);
+        let duplicate = existing.iter().any(|r| {
+            r.name == entry.name && zonefile::rdata(&r.content) == zonefile::rdata(&content)
+        });
+        if duplicate {
+            result.message = Some("Record already exists".to_string());
+            results.push(result);
+            continue;
+        }
+
+        result.content = data;
+        let endpoint = CreateDnsRecord {
+            zone_identifier: zone_id,
+            params: DnsRecordParams {
+                ttl: Some(entry.ttl.unwrap_or(zonefile::AUTO_TTL)),
+                proxied: Some(entry.proxied),
+                name: &entry.name,
+                content,
+            },
+        };
+        match submit(api, &endpoint) {
+            Ok(None) => result.message = Some("Not created, dry run".to_string()),
+            Ok(Some(_)) => result.status = ImportStatus::Created,
+            Err(e) => {
+                result.status = ImportStatus::Failed;
+                result.message = Some(Error::from(e).to_string());
             }
         }
+        results.push(result);
+    }
+
+    output::render(format, &results, |results| {
+        let columns = vec![
+            "LINE",
+            "NAME",
+            "TYPE",
+            "CONTENT",
+            "STATUS",
+            "MESSAGE",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for result in results {
+            table.add_row(Row::new()
+                .with_cell(result.line)
+                .with_cell(&result.name)
+                .with_cell(&result.record_type)
+                .with_cell(&result.content)
+                .with_cell(format!("{:?}", result.status))
+                .with_cell(result.message.as_deref().unwrap_or("")));
+        }
+        table
+    })?;
+
+    let count = |status: ImportStatus| results.iter().filter(|r| r.status == status).count();
+    if format == Format::Table {
+        terminal::info(format!("{} created, {} skipped, {} failed",
+                               count(ImportStatus::Created),
+                               count(ImportStatus::Skipped),
+                               count(ImportStatus::Failed)).as_str());
+    }
+
+    let failed = count(ImportStatus::Failed);
+    if failed > 0 {
+        return Err(Error::Partial(format!("{} records could not be imported", failed)));
     }
+    Ok(())
 }
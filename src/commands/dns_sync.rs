@@ -0,0 +1,406 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use cloudflare::endpoints::dns::DeleteDnsRecord;
+use colored::Colorize;
+use tabular::Row;
+
+use crate::api::endpoints::dns::{CreateDnsRecord, DnsContent, DnsRecord, DnsRecordParams, UpdateDnsRecord};
+use crate::commands::dns::fetch_all_records;
+use crate::commands::{confirm, send, table_from_cols};
+use crate::error::Error;
+use crate::http::Client;
+use crate::output::{self, Format};
+use crate::{terminal, zonefile};
+
+const DEFAULT_OWNER: &str = "cflare";
+// Ownership records follow external-dns' TXT registry: `_cflare.<name>` holds one TXT per managed type
+const REGISTRY_PREFIX: &str = "_cflare.";
+const HERITAGE: &str = "heritage=cflare";
+// Stands for the `*` of wildcards in ownership records, where it can't be anything but the leftmost label
+const WILDCARD_LABEL: &str = "_wildcard";
+
+fn default_owner() -> String {
+    DEFAULT_OWNER.to_string()
+}
+
+/// Desired state of a zone, read from a YAML or TOML file
+#[derive(Deserialize, Debug)]
+pub struct DesiredState {
+    /// Identifies the records managed by this file in the ownership registry
+    #[serde(default = "default_owner")]
+    pub owner: String,
+    #[serde(default)]
+    pub records: Vec<DesiredRecord>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DesiredRecord {
+    /// Record name, relative to the zone or fully qualified. `@` is the zone apex.
+    pub name: String,
+    #[serde(rename = "type")]
+    pub record_type: String,
+    pub content: String,
+    /// Defaults to 1 (automatic)
+    pub ttl: Option<u32>,
+    /// Defaults to false
+    pub proxied: Option<bool>,
+    /// Used with MX, SRV and URI records
+    pub priority: Option<u16>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Add,
+    Change,
+    Destroy,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RecordState {
+    pub content: String,
+    pub ttl: u32,
+    pub proxied: bool,
+}
+
+/// A single step of the plan
+#[derive(Serialize, Debug)]
+pub struct Change {
+    pub action: Action,
+    /// Identifier of the existing record, for changes and destroys
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub record_type: String,
+    pub before: Option<RecordState>,
+    pub after: Option<RecordState>,
+    #[serde(skip)]
+    content: Option<DnsContent>,
+}
+
+impl Change {
+    fn describe(&self) -> String {
+        let state = |s: &RecordState| format!("{} (ttl {}, {})",
+                                               s.content,
+                                               if s.ttl == zonefile::AUTO_TTL { "auto".to_string() } else { s.ttl.to_string() },
+                                               if s.proxied { "proxied" } else { "dns only" });
+
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => format!("{} -> {}", state(before), state(after)),
+            (Some(state_), None) | (None, Some(state_)) => state(state_),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+struct Desired {
+    name: String,
+    record_type: String,
+    content: DnsContent,
+    state: RecordState,
+}
+
+fn key(name: &str, record_type: &str) -> (String, String) {
+    (name.to_lowercase(), record_type.to_string())
+}
+
+fn state_of(record: &DnsRecord) -> RecordState {
+    RecordState {
+        content: zonefile::rdata(&record.content).1,
+        ttl: record.ttl,
+        proxied: record.proxied,
+    }
+}
+
+fn is_registry(record: &DnsRecord) -> bool {
+    match &record.content {
+        DnsContent::TXT { content } => record.name.starts_with(REGISTRY_PREFIX) && content.starts_with(HERITAGE),
+        _ => false,
+    }
+}
+
+// Name of the ownership record of `name`, e.g. `_cflare._wildcard.example.com` for `*.example.com`
+fn registry_name(name: &str) -> String {
+    match name.strip_prefix("*.") {
+        Some(rest) => format!("{}{}.{}", REGISTRY_PREFIX, WILDCARD_LABEL, rest),
+        None => format!("{}{}", REGISTRY_PREFIX, name),
+    }
+}
+
+// Name of the record owned according to an ownership record, the reverse of `registry_name`
+fn owned_name(registry_name: &str) -> String {
+    let name = &registry_name[REGISTRY_PREFIX.len()..];
+    match name.strip_prefix(WILDCARD_LABEL).and_then(|rest| rest.strip_prefix('.')) {
+        Some(rest) => format!("*.{}", rest),
+        None => name.to_string(),
+    }
+}
+
+fn registry_content(owner: &str, record_type: &str) -> String {
+    format!("{},owner={},type={}", HERITAGE, owner, record_type)
+}
+
+pub fn read_desired_state(path: &str) -> Result<DesiredState, failure::Error> {
+    let input = fs::read_to_string(path)?;
+
+    match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("toml") => Ok(toml::from_str(&input)?),
+        _ => Ok(serde_yaml::from_str(&input)?),
+    }
+}
+
+fn resolve_desired(state: &DesiredState, zone_name: &str) -> Result<Vec<Desired>, failure::Error> {
+    state.records.iter().map(|record| {
+        let name = zonefile::absolute_name(&record.name, zone_name).to_lowercase();
+        let record_type = record.record_type.to_uppercase();
+        let content = zonefile::parse_content(&record_type, &record.content, Some(record.priority.unwrap_or(0)))
+            .map_err(|e| failure::format_err!("{} {}: {}", record_type, name, e))?;
+
+        Ok(Desired {
+            state: RecordState {
+                content: zonefile::rdata(&content).1,
+                ttl: record.ttl.unwrap_or(zonefile::AUTO_TTL),
+                proxied: record.proxied.unwrap_or(false),
+            },
+            name,
+            record_type,
+            content,
+        })
+    }).collect()
+}
+
+/// Computes the changes needed to go from `existing` to the desired state. Existing records are only
+/// changed or destroyed when the registry says we own their name and type, or with `prune`. Locked
+/// records are never touched.
+fn diff(state: &DesiredState, desired: Vec<Desired>, existing: &[DnsRecord], prune: bool) -> Vec<Change> {
+    let owner_marker = format!("{},owner={},", HERITAGE, state.owner);
+    let owned: HashSet<(String, String)> = existing.iter()
+        .filter(|r| is_registry(r))
+        .filter_map(|r| match &r.content {
+            DnsContent::TXT { content } if content.starts_with(&owner_marker) => {
+                let record_type = content.rsplit("type=").next().unwrap_or_default();
+                Some(key(&owned_name(&r.name), record_type))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let records: Vec<&DnsRecord> = existing.iter().filter(|r| !is_registry(r)).collect();
+    let desired_keys: Vec<(String, String)> = desired.iter()
+        .map(|d| key(&d.name, &d.record_type))
+        .fold(vec![], |mut keys, k| {
+            if !keys.contains(&k) {
+                keys.push(k);
+            }
+            keys
+        });
+
+    let mut changes = vec![];
+
+    for k in &desired_keys {
+        let managed = prune || owned.contains(k);
+        let mut used: HashSet<&str> = HashSet::new();
+        let group: Vec<&Desired> = desired.iter().filter(|d| &key(&d.name, &d.record_type) == k).collect();
+        let current: Vec<&DnsRecord> = records.iter().copied()
+            .filter(|r| &key(&r.name, zonefile::rdata(&r.content).0) == k)
+            .collect();
+        let mut unmatched = vec![];
+
+        // Records with the same content only need their TTL or proxy status updated
+        for d in group {
+            let same = current.iter().find(|r| !used.contains(r.id.as_str()) && state_of(r).content == d.state.content);
+            match same {
+                Some(record) => {
+                    used.insert(&record.id);
+                    if managed && !record.locked && state_of(record) != d.state {
+                        changes.push(change(Action::Change, Some(record), Some(d)));
+                    }
+                }
+                None => unmatched.push(d),
+            }
+        }
+
+        // Remaining records we manage are reused for the new contents, then destroyed
+        let mut leftovers = current.into_iter()
+            .filter(|r| managed && !r.locked && !used.contains(r.id.as_str()));
+        for d in unmatched {
+            match leftovers.next() {
+                Some(record) => changes.push(change(Action::Change, Some(record), Some(d))),
+                None => changes.push(change(Action::Add, None, Some(d))),
+            }
+        }
+        for record in leftovers {
+            changes.push(change(Action::Destroy, Some(record), None));
+        }
+    }
+
+    for record in records {
+        let k = key(&record.name, zonefile::rdata(&record.content).0);
+        if desired_keys.contains(&k) || record.locked {
+            continue;
+        }
+        if prune || owned.contains(&k) {
+            changes.push(change(Action::Destroy, Some(record), None));
+        }
+    }
+
+    // Keep the registry in line with the records managed by this file
+    for (name, record_type) in &desired_keys {
+        if !owned.contains(&(name.clone(), record_type.clone())) {
+            let content = DnsContent::TXT { content: registry_content(&state.owner, record_type) };
+            changes.push(Change {
+                action: Action::Add,
+                id: None,
+                name: registry_name(name),
+                record_type: "TXT".to_string(),
+                before: None,
+                after: Some(RecordState { content: zonefile::rdata(&content).1, ttl: zonefile::AUTO_TTL, proxied: false }),
+                content: Some(content),
+            });
+        }
+    }
+    for record in existing.iter().filter(|r| is_registry(r)) {
+        if let DnsContent::TXT { content } = &record.content {
+            let k = key(&owned_name(&record.name), content.rsplit("type=").next().unwrap_or_default());
+            if content.starts_with(&owner_marker) && !desired_keys.contains(&k) {
+                changes.push(change(Action::Destroy, Some(record), None));
+            }
+        }
+    }
+
+    changes
+}
+
+fn change(action: Action, record: Option<&DnsRecord>, desired: Option<&Desired>) -> Change {
+    let (name, record_type) = match (record, desired) {
+        (_, Some(d)) => (d.name.clone(), d.record_type.clone()),
+        (Some(r), None) => (r.name.clone(), zonefile::rdata(&r.content).0.to_string()),
+        (None, None) => unreachable!(),
+    };
+
+    Change {
+        action,
+        id: record.map(|r| r.id.clone()),
+        name,
+        record_type,
+        before: record.map(state_of),
+        after: desired.map(|d| d.state.clone()),
+        content: desired.map(|d| d.content.clone()),
+    }
+}
+
+// Builds the plan for a zone. The zone name is needed to resolve relative record names.
+pub fn build_plan(api: &Client, zone_id: &str, zone_name: &str, state: &DesiredState, prune: bool) -> Result<Vec<Change>, Error> {
+    let desired = resolve_desired(state, zone_name).map_err(Error::validation)?;
+    let existing = fetch_all_records(api, zone_id)?;
+
+    Ok(diff(state, desired, &existing, prune))
+}
+
+fn print_plan(changes: &[Change], format: Format) -> Result<(), Error> {
+    output::render(format, changes, |changes| {
+        let columns = vec![
+            "",
+            "TYPE",
+            "NAME",
+            "CONTENT",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for change in changes {
+            let symbol = match change.action {
+                Action::Add => "+".green(),
+                Action::Change => "~".yellow(),
+                Action::Destroy => "-".red(),
+            };
+            table.add_row(Row::new()
+                .with_cell(symbol)
+                .with_cell(&change.record_type)
+                .with_cell(&change.name)
+                .with_cell(change.describe()));
+        }
+        table
+    })?;
+
+    if format != Format::Table {
+        return Ok(());
+    }
+    if changes.is_empty() {
+        terminal::info("No changes. DNS records are up-to-date.");
+    } else {
+        let count = |action: Action| changes.iter().filter(|c| c.action == action).count();
+        terminal::info(format!("\nPlan: {} to add, {} to change, {} to destroy.",
+                               count(Action::Add),
+                               count(Action::Change),
+                               count(Action::Destroy)).as_str());
+    }
+    Ok(())
+}
+
+pub fn plan(api: &Client, zone_id: &str, zone_name: &str, state: &DesiredState, prune: bool, format: Format) -> Result<(), Error> {
+    let changes = build_plan(api, zone_id, zone_name, state, prune)?;
+    print_plan(&changes, format)
+}
+
+pub fn apply(api: &Client, zone_id: &str, zone_name: &str, state: &DesiredState, prune: bool, format: Format, yes: bool) -> Result<(), Error> {
+    let mut changes = build_plan(api, zone_id, zone_name, state, prune)?;
+    print_plan(&changes, format)?;
+
+    // The plan was just printed, records it destroys are lost for good
+    if changes.iter().any(|c| c.action == Action::Destroy) {
+        confirm(api, yes, || Ok("Apply this plan?".to_string()))?;
+    }
+    let total = changes.len();
+
+    // Destroy first so that a name can switch between conflicting types, e.g. A to CNAME
+    changes.sort_by_key(|c| match c.action {
+        Action::Destroy => 0,
+        Action::Change => 1,
+        Action::Add => 2,
+    });
+
+    let mut failed = 0;
+    for change in changes {
+        let result = match change.action {
+            Action::Add => send(api, &CreateDnsRecord {
+                zone_identifier: zone_id,
+                params: DnsRecordParams {
+                    ttl: change.after.as_ref().map(|s| s.ttl),
+                    proxied: change.after.as_ref().map(|s| s.proxied),
+                    name: &change.name,
+                    content: change.content.clone().unwrap(),
+                },
+            }),
+            Action::Change => send(api, &UpdateDnsRecord {
+                zone_identifier: zone_id,
+                identifier: change.id.as_deref().unwrap(),
+                params: DnsRecordParams {
+                    ttl: change.after.as_ref().map(|s| s.ttl),
+                    proxied: change.after.as_ref().map(|s| s.proxied),
+                    name: &change.name,
+                    content: change.content.clone().unwrap(),
+                },
+            }),
+            Action::Destroy => send(api, &DeleteDnsRecord {
+                zone_identifier: zone_id,
+                identifier: change.id.as_deref().unwrap(),
+            }),
+        };
+
+        match result {
+            Ok(_) if api.is_dry_run() => {}
+            Ok(_) => terminal::info(format!("{:?} {} {}: done", change.action, change.record_type, change.name).as_str()),
+            Err(e) => {
+                failed += 1;
+                terminal::error(format!("{:?} {} {}: {}", change.action, change.record_type, change.name, e).as_str());
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Partial(format!("Apply finished with {} of {} changes failed", failed, total)));
+    }
+    Ok(())
+}
@@ -0,0 +1,503 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use cloudflare::framework::apiclient::ApiClient;
+use tabular::Row;
+
+use crate::api::endpoints::firewall::{
+    AccessRule,
+    AccessRuleConfiguration,
+    AccessRuleScope,
+    CreateAccessRule,
+    CreateAccessRuleParams,
+    CreateFilters,
+    CreateFirewallRules,
+    DeleteAccessRule,
+    DeleteFilter,
+    DeleteFirewallRule,
+    DeleteFirewallRuleParams,
+    EditAccessRule,
+    EditAccessRuleParams,
+    Filter,
+    FilterDetails,
+    FilterParams,
+    FirewallRule,
+    FirewallRuleDetails,
+    FirewallRuleParams,
+    ListAccessRules,
+    ListAccessRulesParams,
+    ListFilters,
+    ListFirewallRules,
+    PageParams,
+    UpdateFilter,
+    UpdateFirewallRule,
+};
+use crate::commands::{confirm_items, for_each_id, send, submit, table_from_cols};
+use crate::error::Error;
+use crate::http::{self, Client, PageOptions};
+use crate::output::{self, Format};
+use crate::terminal;
+
+const ALL_RESULTS_PAGE_SIZE: u32 = 100;
+
+/// Actions of firewall rules
+pub const RULE_ACTIONS: [&str; 7] = ["block", "challenge", "js_challenge", "managed_challenge", "allow", "log", "bypass"];
+/// Modes of IP Access Rules
+pub const ACCESS_RULE_MODES: [&str; 5] = ["block", "challenge", "whitelist", "js_challenge", "managed_challenge"];
+/// What IP Access Rules match
+pub const ACCESS_RULE_TARGETS: [&str; 5] = ["ip", "ip6", "ip_range", "asn", "country"];
+
+pub struct RuleParams<'a> {
+    pub expression: &'a str,
+    pub action: &'a str,
+    pub description: Option<&'a str>,
+    pub priority: Option<u32>,
+    pub paused: bool,
+}
+
+/// Changes requested by `firewall rules update`, only given fields change
+pub struct UpdateRuleParams<'a> {
+    pub expression: Option<&'a str>,
+    pub action: Option<&'a str>,
+    pub description: Option<&'a str>,
+    pub priority: Option<u32>,
+}
+
+pub struct AccessRuleParams<'a> {
+    pub mode: &'a str,
+    pub target: Option<&'a str>,
+    pub value: &'a str,
+    pub notes: Option<&'a str>,
+}
+
+/// Guesses the target of an IP Access Rule from its value, e.g. `ip_range` for `198.51.100.0/24`
+pub fn access_rule_target(value: &str) -> Result<&'static str, String> {
+    let is_asn = value.len() > 2 && value[..2].eq_ignore_ascii_case("AS") && value[2..].chars().all(|c| c.is_ascii_digit());
+
+    if value.contains('/') {
+        Ok("ip_range")
+    } else if value.parse::<Ipv4Addr>().is_ok() {
+        Ok("ip")
+    } else if value.parse::<Ipv6Addr>().is_ok() {
+        Ok("ip6")
+    } else if is_asn {
+        Ok("asn")
+    } else if value.len() == 2 && value.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok("country")
+    } else {
+        Err(format!("Could not guess the target of \"{}\", use --target", value))
+    }
+}
+
+// Every page is fetched, as the API doesn't sort rules by priority
+fn fetch_rules(api: &Client, zone_id: &str, pagination: &PageOptions) -> Result<Vec<FirewallRule>, Error> {
+    let mut rules = http::paginate(api, &PageOptions::all(pagination.per_page), |page, per_page| ListFirewallRules {
+        zone_identifier: zone_id,
+        params: PageParams { page: Some(page), per_page: Some(per_page) },
+    })?;
+
+    // Same order as rules are evaluated, rules without priority last
+    rules.sort_by_key(|r| r.priority.unwrap_or(u32::MAX));
+    Ok(http::page_of(rules, pagination))
+}
+
+// Whether other rules use the filter of a rule, and would change with it
+fn is_filter_shared(api: &Client, zone_id: &str, rule: &FirewallRule) -> Result<bool, Error> {
+    let rules = fetch_rules(api, zone_id, &PageOptions::all(ALL_RESULTS_PAGE_SIZE))?;
+    Ok(rules.iter().any(|r| r.id != rule.id && r.filter.id == rule.filter.id))
+}
+
+// Parameters that recreate an existing rule, keeping its filter
+fn rule_params(rule: &FirewallRule) -> FirewallRuleParams {
+    FirewallRuleParams {
+        id: Some(rule.id.clone()),
+        filter: filter_params(&rule.filter),
+        action: rule.action.clone(),
+        paused: rule.paused,
+        description: rule.description.clone(),
+        priority: rule.priority,
+    }
+}
+
+fn filter_params(filter: &Filter) -> FilterParams {
+    FilterParams {
+        id: Some(filter.id.clone()),
+        expression: filter.expression.clone(),
+        paused: filter.paused,
+        description: filter.description.clone(),
+    }
+}
+
+fn print_rules(format: Format, rules: &[FirewallRule]) -> Result<(), Error> {
+    output::render(format, rules, |rules| {
+        let columns = vec![
+            "ID",
+            "PRIORITY",
+            "ACTION",
+            "PAUSED",
+            "DESCRIPTION",
+            "EXPRESSION",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for rule in rules {
+            table.add_row(Row::new()
+                .with_cell(&rule.id)
+                .with_cell(rule.priority.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string()))
+                .with_cell(&rule.action)
+                .with_cell(if rule.paused { "Yes" } else { "No" })
+                .with_cell(rule.description.as_deref().unwrap_or("-"))
+                .with_cell(&rule.filter.expression));
+        }
+        table
+    })
+}
+
+fn print_filters(format: Format, filters: &[Filter]) -> Result<(), Error> {
+    output::render(format, filters, |filters| {
+        let columns = vec![
+            "ID",
+            "PAUSED",
+            "DESCRIPTION",
+            "EXPRESSION",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for filter in filters {
+            table.add_row(Row::new()
+                .with_cell(&filter.id)
+                .with_cell(if filter.paused { "Yes" } else { "No" })
+                .with_cell(filter.description.as_deref().unwrap_or("-"))
+                .with_cell(&filter.expression));
+        }
+        table
+    })
+}
+
+fn print_access_rules(format: Format, rules: &[AccessRule]) -> Result<(), Error> {
+    output::render(format, rules, |rules| {
+        let columns = vec![
+            "ID",
+            "MODE",
+            "TARGET",
+            "VALUE",
+            "SCOPE",
+            "NOTES",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for rule in rules {
+            table.add_row(Row::new()
+                .with_cell(&rule.id)
+                .with_cell(&rule.mode)
+                .with_cell(&rule.configuration.target)
+                .with_cell(&rule.configuration.value)
+                .with_cell(rule.scope.as_ref().map(|s| s.scope_type.as_str()).unwrap_or("-"))
+                .with_cell(if rule.notes.is_empty() { "-" } else { &rule.notes }));
+        }
+        table
+    })
+}
+
+pub fn list_rules(api: &Client, zone_id: &str, pagination: &PageOptions, format: Format) -> Result<(), Error> {
+    let rules = fetch_rules(api, zone_id, pagination)?;
+
+    print_rules(format, &rules)
+}
+
+pub fn create_rule(api: &Client, zone_id: &str, input: RuleParams, format: Format) -> Result<(), Error> {
+    let endpoint = CreateFirewallRules {
+        zone_identifier: zone_id,
+        params: vec![FirewallRuleParams {
+            id: None,
+            filter: FilterParams {
+                expression: input.expression.to_string(),
+                ..Default::default()
+            },
+            action: input.action.to_string(),
+            paused: input.paused,
+            description: input.description.map(String::from),
+            priority: input.priority,
+        }],
+    };
+    let Some(rules) = submit(api, &endpoint)?.map(|result| result.rules) else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_rules(format, &rules);
+    }
+    for rule in rules {
+        terminal::info(format!("Firewall rule \"{}\" created", rule.id).as_str());
+    }
+    Ok(())
+}
+
+pub fn update_rule(api: &Client, zone_id: &str, id: &str, input: UpdateRuleParams, format: Format) -> Result<(), Error> {
+    let rule = api.request(&FirewallRuleDetails { zone_identifier: zone_id, identifier: id })?.result;
+    let mut params = rule_params(&rule);
+    params.action = input.action.map(String::from).unwrap_or(params.action);
+    params.description = input.description.map(String::from).or(params.description);
+    params.priority = input.priority.or(params.priority);
+
+    // Expressions belong to filters. A filter other rules use is left alone and the rule gets a new
+    // one, otherwise the filter is updated once the rule was.
+    let mut created_filter = None;
+    let mut filter_update = None;
+    if let Some(expression) = input.expression {
+        let filter = FilterParams { expression: expression.to_string(), ..filter_params(&rule.filter) };
+        if is_filter_shared(api, zone_id, &rule)? {
+            let endpoint = CreateFilters { zone_identifier: zone_id, params: vec![FilterParams { id: None, ..filter }] };
+            params.filter = match submit(api, &endpoint)? {
+                None => endpoint.params[0].clone(),
+                Some(created) => {
+                    let filter = created.filters.into_iter().next()
+                        .ok_or_else(|| Error::Api("No filter was created".to_string()))?;
+                    created_filter = Some(filter.id.clone());
+                    filter_params(&filter)
+                }
+            };
+        } else {
+            filter_update = Some(UpdateFilter { zone_identifier: zone_id, identifier: &rule.filter.id, params: filter });
+        }
+    }
+
+    let endpoint = UpdateFirewallRule {
+        zone_identifier: zone_id,
+        identifier: id,
+        params,
+    };
+    if api.dry_run(&endpoint) {
+        if let Some(filter_update) = &filter_update {
+            api.dry_run(filter_update);
+        }
+        return Ok(());
+    }
+    let mut updated = match api.request(&endpoint) {
+        Ok(success) => success.result,
+        Err(e) => {
+            // The new filter would be left unused
+            if let Some(filter_id) = &created_filter {
+                let _ = api.request(&DeleteFilter { zone_identifier: zone_id, identifier: filter_id });
+            }
+            return Err(e.into());
+        }
+    };
+    if let Some(filter_update) = filter_update {
+        match api.request(&filter_update) {
+            Ok(success) => updated.filter = success.result,
+            Err(e) => {
+                // Put the rule back as it was, so it doesn't end up half updated
+                let _ = api.request(&UpdateFirewallRule { zone_identifier: zone_id, identifier: id, params: rule_params(&rule) });
+                return Err(e.into());
+            }
+        }
+    }
+    let rule = updated;
+
+    if format != Format::Table {
+        return print_rules(format, &[rule]);
+    }
+    terminal::info(format!("Firewall rule \"{}\" updated", rule.id).as_str());
+    Ok(())
+}
+
+pub fn set_rules_paused(api: &Client, zone_id: &str, ids: Vec<&str>, paused: bool) -> Result<(), Error> {
+    let done = if paused { "paused" } else { "unpaused" };
+
+    for_each_id(api, ids, "Firewall rule", "firewall rules", done, |id| {
+        let rule = api.request(&FirewallRuleDetails { zone_identifier: zone_id, identifier: id })?.result;
+        if rule.paused == paused {
+            return Ok(());
+        }
+
+        send(api, &UpdateFirewallRule {
+            zone_identifier: zone_id,
+            identifier: id,
+            params: FirewallRuleParams { paused, ..rule_params(&rule) },
+        })
+    })
+}
+
+pub fn delete_rules(api: &Client, zone_id: &str, ids: Vec<&str>, yes: bool) -> Result<(), Error> {
+    confirm_items(api, yes, "Delete", "firewall rule", "firewall rules", &ids)?;
+
+    for_each_id(api, ids, "Firewall rule", "firewall rules", "deleted", |id| {
+        send(api, &DeleteFirewallRule {
+            zone_identifier: zone_id,
+            identifier: id,
+            params: DeleteFirewallRuleParams { delete_filter_if_unused: true },
+        })
+    })
+}
+
+/// Gives the given rules the first priorities, in order. Other rules keep their relative order after them.
+pub fn reorder_rules(api: &Client, zone_id: &str, ids: Vec<&str>) -> Result<(), Error> {
+    let rules = fetch_rules(api, zone_id, &PageOptions::all(ALL_RESULTS_PAGE_SIZE))?;
+
+    if let Some(unknown) = ids.iter().find(|id| !rules.iter().any(|r| &r.id == *id)) {
+        return Err(Error::NotFound(format!("Firewall rule \"{}\" not found", unknown)));
+    }
+
+    let mut ordered: Vec<&FirewallRule> = ids.iter()
+        .filter_map(|id| rules.iter().find(|r| &r.id == id))
+        .collect();
+    ordered.extend(rules.iter().filter(|r| !ids.contains(&r.id.as_str())));
+
+    let mut failed = 0;
+    for (i, rule) in ordered.into_iter().enumerate() {
+        let priority = i as u32 + 1;
+        if rule.priority == Some(priority) {
+            continue;
+        }
+
+        let endpoint = UpdateFirewallRule {
+            zone_identifier: zone_id,
+            identifier: &rule.id,
+            params: FirewallRuleParams { priority: Some(priority), ..rule_params(rule) },
+        };
+        match submit(api, &endpoint) {
+            Ok(None) => {}
+            Ok(Some(_)) => terminal::info(format!("Firewall rule \"{}\" moved to priority {}", rule.id, priority).as_str()),
+            Err(e) => {
+                failed += 1;
+                terminal::error(format!("Firewall rule \"{}\": {}", rule.id, Error::from(e)).as_str());
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Partial(format!("{} firewall rules could not be moved", failed)));
+    }
+    Ok(())
+}
+
+pub fn list_filters(api: &Client, zone_id: &str, pagination: &PageOptions, format: Format) -> Result<(), Error> {
+    let filters = http::paginate(api, pagination, |page, per_page| ListFilters {
+        zone_identifier: zone_id,
+        params: PageParams { page: Some(page), per_page: Some(per_page) },
+    })?;
+
+    print_filters(format, &filters)
+}
+
+pub fn create_filter(api: &Client, zone_id: &str, expression: &str, description: Option<&str>, format: Format) -> Result<(), Error> {
+    let endpoint = CreateFilters {
+        zone_identifier: zone_id,
+        params: vec![FilterParams {
+            id: None,
+            expression: expression.to_string(),
+            paused: false,
+            description: description.map(String::from),
+        }],
+    };
+    let Some(filters) = submit(api, &endpoint)?.map(|result| result.filters) else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_filters(format, &filters);
+    }
+    for filter in filters {
+        terminal::info(format!("Filter \"{}\" created", filter.id).as_str());
+    }
+    Ok(())
+}
+
+pub fn update_filter(api: &Client, zone_id: &str, id: &str, expression: Option<&str>, description: Option<&str>, format: Format) -> Result<(), Error> {
+    let filter = api.request(&FilterDetails { zone_identifier: zone_id, identifier: id })?.result;
+    let mut params = filter_params(&filter);
+    params.expression = expression.map(String::from).unwrap_or(params.expression);
+    params.description = description.map(String::from).or(params.description);
+
+    let endpoint = UpdateFilter {
+        zone_identifier: zone_id,
+        identifier: id,
+        params,
+    };
+    let Some(filter) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_filters(format, &[filter]);
+    }
+    terminal::info(format!("Filter \"{}\" updated", filter.id).as_str());
+    Ok(())
+}
+
+pub fn delete_filters(api: &Client, zone_id: &str, ids: Vec<&str>, yes: bool) -> Result<(), Error> {
+    confirm_items(api, yes, "Delete", "filter", "filters", &ids)?;
+
+    for_each_id(api, ids, "Filter", "filters", "deleted", |id| {
+        send(api, &DeleteFilter { zone_identifier: zone_id, identifier: id })
+    })
+}
+
+pub fn list_access_rules(api: &Client, scope: AccessRuleScope, mode: Option<&str>, pagination: &PageOptions, format: Format) -> Result<(), Error> {
+    let rules = http::paginate(api, pagination, |page, per_page| ListAccessRules {
+        scope,
+        params: ListAccessRulesParams {
+            mode: mode.map(String::from),
+            page: Some(page),
+            per_page: Some(per_page),
+        },
+    })?;
+
+    print_access_rules(format, &rules)
+}
+
+pub fn create_access_rule(api: &Client, scope: AccessRuleScope, input: AccessRuleParams, format: Format) -> Result<(), Error> {
+    let target = match input.target {
+        Some(target) => target,
+        None => access_rule_target(input.value).map_err(Error::Validation)?,
+    };
+
+    let endpoint = CreateAccessRule {
+        scope,
+        params: CreateAccessRuleParams {
+            mode: input.mode.to_string(),
+            configuration: AccessRuleConfiguration {
+                target: target.to_string(),
+                value: input.value.to_string(),
+            },
+            notes: input.notes.map(String::from),
+        },
+    };
+    let Some(rule) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_access_rules(format, &[rule]);
+    }
+    terminal::info(format!("IP Access rule \"{}\" created: {} {} {}", rule.id, rule.mode, rule.configuration.target, rule.configuration.value).as_str());
+    Ok(())
+}
+
+pub fn update_access_rule(api: &Client, scope: AccessRuleScope, id: &str, mode: Option<&str>, notes: Option<&str>, format: Format) -> Result<(), Error> {
+    let endpoint = EditAccessRule {
+        scope,
+        identifier: id,
+        params: EditAccessRuleParams {
+            mode: mode.map(String::from),
+            notes: notes.map(String::from),
+        },
+    };
+    let Some(rule) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_access_rules(format, &[rule]);
+    }
+    terminal::info(format!("IP Access rule \"{}\" updated", rule.id).as_str());
+    Ok(())
+}
+
+pub fn delete_access_rules(api: &Client, scope: AccessRuleScope, ids: Vec<&str>, yes: bool) -> Result<(), Error> {
+    confirm_items(api, yes, "Delete", "IP Access rule", "IP Access rules", &ids)?;
+
+    for_each_id(api, ids, "IP Access rule", "IP Access rules", "deleted", |id| {
+        send(api, &DeleteAccessRule { scope, identifier: id })
+    })
+}
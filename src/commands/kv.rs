@@ -0,0 +1,473 @@
+use std::fs;
+use std::io::{self, Read, Write};
+
+use chrono::{TimeZone, Utc};
+use cloudflare::endpoints::workerskv::{
+    create_namespace::{CreateNamespace, CreateNamespaceParams},
+    delete_bulk::DeleteBulk,
+    delete_key::DeleteKey,
+    list_namespaces::{ListNamespaces, ListNamespacesParams},
+    remove_namespace::RemoveNamespace,
+    rename_namespace::{RenameNamespace, RenameNamespaceParams},
+    WorkersKvNamespace,
+};
+use cloudflare::framework::apiclient::ApiClient;
+use reqwest::blocking::multipart::{Form, Part};
+use serde_json::Value;
+use tabular::Row;
+
+use crate::api::endpoints::kv::{
+    Key,
+    KeyValuePair,
+    ListKeys,
+    ListKeysParams,
+    ReadValue,
+    WriteBulk,
+    WriteValue,
+    WriteValueParams,
+};
+use crate::commands::{confirm_items, send, submit, table_from_cols};
+use crate::error::Error;
+use crate::http::{self, Client, PageOptions};
+use crate::output::{self, Format};
+use crate::terminal;
+
+const ALL_NAMESPACES_PAGE_SIZE: u32 = 100;
+/// Most keys returned by a single list request
+pub const MAX_KEYS_PAGE_SIZE: u32 = 1000;
+/// Most keys written or deleted by a single bulk request
+pub const MAX_BULK_ITEMS: usize = 10_000;
+/// Shortest expiration TTL accepted by Workers KV, in seconds
+pub const MIN_TTL: u32 = 60;
+
+/// Expiration of a written key
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Expiration {
+    Never,
+    /// Seconds since the UNIX epoch
+    At(i64),
+    /// Seconds from now
+    Ttl(i64),
+}
+
+impl Expiration {
+    fn params(self) -> WriteValueParams {
+        match self {
+            Expiration::Never => WriteValueParams::default(),
+            Expiration::At(time) => WriteValueParams { expiration: Some(time), ..Default::default() },
+            Expiration::Ttl(ttl) => WriteValueParams { expiration_ttl: Some(ttl), ..Default::default() },
+        }
+    }
+}
+
+/// Keys given in a bulk delete file, either names or the pairs of a bulk put file
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BulkKey {
+    Name(String),
+    Pair { key: String },
+}
+
+fn fetch_namespaces(api: &Client, account_id: &str, options: &PageOptions) -> Result<Vec<WorkersKvNamespace>, Error> {
+    http::paginate(api, options, |page, per_page| ListNamespaces {
+        account_identifier: account_id,
+        params: ListNamespacesParams {
+            page: Some(page),
+            per_page: Some(per_page),
+        },
+    }).map_err(Error::from)
+}
+
+/// Finds the identifier of a namespace given its identifier or its title
+pub fn resolve(api: &Client, account_id: &str, id_or_title: &str) -> Result<String, Error> {
+    let is_id = id_or_title.len() == 32 && id_or_title.chars().all(|c| c.is_ascii_hexdigit());
+    if is_id {
+        return Ok(id_or_title.to_string());
+    }
+
+    let matches: Vec<WorkersKvNamespace> = fetch_namespaces(api, account_id, &PageOptions::all(ALL_NAMESPACES_PAGE_SIZE))?
+        .into_iter()
+        .filter(|ns| ns.title == id_or_title)
+        .collect();
+
+    match matches.len() {
+        0 => Err(Error::NotFound(format!("Namespace \"{}\" not found", id_or_title))),
+        1 => Ok(matches[0].id.clone()),
+        _ => Err(Error::Validation(format!("Several namespaces are named \"{}\", use the namespace identifier instead", id_or_title))),
+    }
+}
+
+pub fn list_namespaces(api: &Client, account_id: &str, options: &PageOptions, format: Format) -> Result<(), Error> {
+    let namespaces = fetch_namespaces(api, account_id, options)?;
+
+    output::render(format, &namespaces, |namespaces| {
+        let columns = vec![
+            "ID",
+            "TITLE",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for namespace in namespaces {
+            table.add_row(Row::new()
+                .with_cell(&namespace.id)
+                .with_cell(&namespace.title));
+        }
+        table
+    })
+}
+
+pub fn create_namespace(api: &Client, account_id: &str, title: &str, format: Format) -> Result<(), Error> {
+    let endpoint = CreateNamespace {
+        account_identifier: account_id,
+        params: CreateNamespaceParams { title: title.to_string() },
+    };
+    let Some(namespace) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return output::print(format, &namespace);
+    }
+    terminal::info(format!("Namespace \"{}\" created with ID {}", namespace.title, namespace.id).as_str());
+    Ok(())
+}
+
+pub fn rename_namespace(api: &Client, account_id: &str, namespace_id: &str, title: &str) -> Result<(), Error> {
+    let endpoint = RenameNamespace {
+        account_identifier: account_id,
+        namespace_identifier: namespace_id,
+        params: RenameNamespaceParams { title: title.to_string() },
+    };
+    if submit(api, &endpoint)?.is_some() {
+        terminal::info(format!("Namespace renamed to \"{}\"", title).as_str());
+    }
+    Ok(())
+}
+
+pub fn delete_namespaces(api: &Client, account_id: &str, namespaces: Vec<&str>, yes: bool) -> Result<(), Error> {
+    confirm_items(api, yes, "Delete", "namespace", "namespaces", &namespaces)?;
+
+    let total = namespaces.len();
+    let mut failed = 0;
+
+    for namespace in namespaces {
+        let result = resolve(api, account_id, namespace).and_then(|id| {
+            send(api, &RemoveNamespace { account_identifier: account_id, namespace_identifier: &id })
+        });
+
+        match result {
+            Ok(_) if api.is_dry_run() => {}
+            Ok(_) => terminal::info(format!("Namespace \"{}\" deleted", namespace).as_str()),
+            Err(e) if total == 1 => return Err(e),
+            Err(e) => {
+                failed += 1;
+                terminal::error(format!("Namespace \"{}\": {}", namespace, e).as_str());
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Partial(format!("{} of {} namespaces could not be deleted", failed, total)));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct CursorInfo {
+    cursor: Option<String>,
+}
+
+/// Lists keys starting with `prefix`, following cursors until every key or `limit` keys have
+/// been fetched. Returns the cursor to continue from when keys are left.
+fn fetch_keys(api: &Client, account_id: &str, namespace_id: &str, prefix: Option<&str>,
+              mut cursor: Option<String>, limit: Option<u32>) -> Result<(Vec<Key>, Option<String>), Error> {
+    let mut keys = vec![];
+
+    loop {
+        let page_size = limit
+            .map(|limit| (limit - keys.len() as u32).clamp(10, MAX_KEYS_PAGE_SIZE))
+            .unwrap_or(MAX_KEYS_PAGE_SIZE);
+        let response = api.request(&ListKeys {
+            account_identifier: account_id,
+            namespace_identifier: namespace_id,
+            params: ListKeysParams {
+                limit: Some(page_size),
+                cursor: cursor.clone(),
+                prefix: prefix.map(String::from),
+            },
+        })?;
+
+        cursor = response.result_info
+            .and_then(|info| serde_json::from_value::<CursorInfo>(info).ok())
+            .and_then(|info| info.cursor)
+            .filter(|cursor| !cursor.is_empty());
+        keys.extend(response.result.keys);
+
+        if let Some(limit) = limit {
+            if keys.len() as u32 >= limit {
+                // The cursor would skip the keys dropped here
+                if keys.len() as u32 > limit {
+                    keys.truncate(limit as usize);
+                    cursor = None;
+                }
+                break;
+            }
+        }
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    Ok((keys, cursor))
+}
+
+pub fn list_keys(api: &Client, account_id: &str, namespace_id: &str, prefix: Option<&str>,
+                 cursor: Option<&str>, limit: Option<u32>, format: Format) -> Result<(), Error> {
+    let (keys, cursor) = fetch_keys(api, account_id, namespace_id, prefix, cursor.map(String::from), limit)?;
+
+    output::render(format, &keys, |keys| {
+        let columns = vec![
+            "NAME",
+            "EXPIRATION",
+            "METADATA",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for key in keys {
+            table.add_row(Row::new()
+                .with_cell(&key.name)
+                .with_cell(key.expiration.and_then(|t| Utc.timestamp_opt(t, 0).single()).map(|d| d.to_string()).unwrap_or_else(|| "-".to_string()))
+                .with_cell(key.metadata.as_ref().map(Value::to_string).unwrap_or_else(|| "-".to_string())));
+        }
+        table
+    })?;
+
+    if let Some(cursor) = cursor {
+        terminal::info(format!("More keys are available, continue with --cursor {}", cursor).as_str());
+    }
+    Ok(())
+}
+
+pub fn get_value(api: &Client, account_id: &str, namespace_id: &str, key: &str, file: Option<&str>) -> Result<(), Error> {
+    let value = api.download(&ReadValue {
+        account_identifier: account_id,
+        namespace_identifier: namespace_id,
+        key,
+    })?;
+
+    match file {
+        Some(path) => {
+            fs::write(path, value).map_err(|e| Error::io(path, e))?;
+            terminal::info(format!("Value of \"{}\" written to {}", key, path).as_str());
+        }
+        None => io::stdout().write_all(&value).map_err(|e| Error::io("stdout", e))?,
+    }
+    Ok(())
+}
+
+/// Reads a value from a file, or from stdin when the path is `-`
+pub fn read_value(path: &str) -> Result<Vec<u8>, Error> {
+    let value = if path == "-" {
+        let mut value = vec![];
+        io::stdin().read_to_end(&mut value).map(|_| value)
+    } else {
+        fs::read(path)
+    };
+    value.map_err(|e| Error::io(path, e))
+}
+
+pub fn put_value(api: &Client, account_id: &str, namespace_id: &str, key: &str, value: Vec<u8>,
+                 expiration: Expiration, metadata: Option<Value>) -> Result<(), Error> {
+    let endpoint = WriteValue {
+        account_identifier: account_id,
+        namespace_identifier: namespace_id,
+        key,
+        params: expiration.params(),
+    };
+    if api.dry_run(&endpoint) {
+        terminal::info(format!("Value of {} bytes", value.len()).as_str());
+        if let Some(metadata) = metadata {
+            terminal::info(format!("Metadata: {}", metadata).as_str());
+        }
+        return Ok(());
+    }
+
+    // Metadata can only be written along with the value in a multipart form
+    match metadata {
+        Some(metadata) => {
+            let form = Form::new()
+                .part("value", Part::bytes(value))
+                .text("metadata", metadata.to_string());
+            api.upload(&endpoint, form)?;
+        }
+        None => {
+            api.send(&endpoint, value)?;
+        }
+    }
+
+    terminal::info(format!("Key \"{}\" written", key).as_str());
+    Ok(())
+}
+
+pub fn delete_keys(api: &Client, account_id: &str, namespace_id: &str, keys: Vec<&str>, yes: bool) -> Result<(), Error> {
+    confirm_items(api, yes, "Delete", "key", "keys", &keys)?;
+
+    let total = keys.len();
+    let mut failed = 0;
+
+    for key in keys {
+        let endpoint = DeleteKey { account_identifier: account_id, namespace_identifier: namespace_id, key };
+        match submit(api, &endpoint) {
+            Ok(None) => {}
+            Ok(Some(_)) => terminal::info(format!("Key \"{}\" deleted", key).as_str()),
+            Err(e) if total == 1 => return Err(e.into()),
+            Err(e) => {
+                failed += 1;
+                terminal::error(format!("Key \"{}\": {}", key, Error::from(e)).as_str());
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Partial(format!("{} of {} keys could not be deleted", failed, total)));
+    }
+    Ok(())
+}
+
+fn read_bulk_file(path: &str) -> Result<String, Error> {
+    if path == "-" {
+        let mut input = String::new();
+        return io::stdin().read_to_string(&mut input).map(|_| input).map_err(|e| Error::io(path, e));
+    }
+    fs::read_to_string(path).map_err(|e| Error::io(path, e))
+}
+
+/// Reads the pairs of a bulk put, a JSON array of objects with `key` and `value`, and optionally
+/// `expiration`, `expiration_ttl`, `metadata` and `base64`.
+pub fn read_bulk_pairs(path: &str) -> Result<Vec<KeyValuePair>, Error> {
+    serde_json::from_str(&read_bulk_file(path)?)
+        .map_err(|e| Error::Validation(format!("Invalid bulk file {}: {}", path, e)))
+}
+
+/// Reads the keys of a bulk delete, a JSON array of key names. The file of a bulk put is also
+/// accepted, deleting the keys it writes.
+pub fn read_bulk_keys(path: &str) -> Result<Vec<String>, Error> {
+    let keys: Vec<BulkKey> = serde_json::from_str(&read_bulk_file(path)?)
+        .map_err(|e| Error::Validation(format!("Invalid bulk file {}: {}", path, e)))?;
+
+    Ok(keys.into_iter()
+        .map(|key| match key {
+            BulkKey::Name(key) | BulkKey::Pair { key } => key,
+        })
+        .collect())
+}
+
+// Sends items in batches of `MAX_BULK_ITEMS`, reporting each batch when there are several
+fn bulk<T, F>(api: &Client, items: Vec<T>, done: &str, failure: &str, send: F) -> Result<(), Error>
+    where F: Fn(Vec<T>) -> Result<(), Error>,
+          T: Clone {
+    if items.is_empty() {
+        return Err(Error::Validation("The bulk file has no keys".to_string()));
+    }
+    let count = items.len();
+    if count <= MAX_BULK_ITEMS {
+        send(items)?;
+        if !api.is_dry_run() {
+            terminal::info(format!("{} {} keys", done, count).as_str());
+        }
+        return Ok(());
+    }
+
+    let batches: Vec<&[T]> = items.chunks(MAX_BULK_ITEMS).collect();
+    let total = batches.len();
+    let mut failed = 0;
+    for (i, batch) in batches.into_iter().enumerate() {
+        match send(batch.to_vec()) {
+            Ok(_) if api.is_dry_run() => {}
+            Ok(_) => terminal::info(format!("Batch {}/{}: {} {} keys", i + 1, total, done.to_lowercase(), batch.len()).as_str()),
+            Err(e) => {
+                failed += 1;
+                terminal::error(format!("Batch {}/{}: {}", i + 1, total, e).as_str());
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Partial(format!("{} of {} batches could not be {}", failed, total, failure)));
+    }
+    if !api.is_dry_run() {
+        terminal::info(format!("{} {} keys", done, count).as_str());
+    }
+    Ok(())
+}
+
+pub fn put_bulk(api: &Client, account_id: &str, namespace_id: &str, pairs: Vec<KeyValuePair>) -> Result<(), Error> {
+    bulk(api, pairs, "Wrote", "written", |pairs| {
+        send(api, &WriteBulk {
+            account_identifier: account_id,
+            namespace_identifier: namespace_id,
+            pairs,
+        })
+    })
+}
+
+pub fn delete_bulk(api: &Client, account_id: &str, namespace_id: &str, keys: Vec<String>, yes: bool) -> Result<(), Error> {
+    let names: Vec<&str> = keys.iter().map(String::as_str).collect();
+    confirm_items(api, yes, "Delete", "key", "keys", &names)?;
+
+    bulk(api, keys, "Deleted", "deleted", |bulk_keys| {
+        send(api, &DeleteBulk {
+            account_identifier: account_id,
+            namespace_identifier: namespace_id,
+            bulk_keys,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn bulk_file(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("cflare-{}-{}.json", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn expiration_params() {
+        let params = Expiration::Never.params();
+        assert_eq!((params.expiration, params.expiration_ttl), (None, None));
+
+        let params = Expiration::At(1_700_000_000).params();
+        assert_eq!((params.expiration, params.expiration_ttl), (Some(1_700_000_000), None));
+
+        let params = Expiration::Ttl(120).params();
+        assert_eq!((params.expiration, params.expiration_ttl), (None, Some(120)));
+    }
+
+    #[test]
+    fn bulk_keys_from_names_or_pairs() {
+        let names = bulk_file("bulk-names", r#"["a", "b"]"#);
+        let pairs = bulk_file("bulk-pairs", r#"[{"key": "a", "value": "1"}, {"key": "b", "value": "2", "expiration_ttl": 60}]"#);
+        let invalid = bulk_file("bulk-invalid", r#"{"key": "a"}"#);
+
+        let from_names = read_bulk_keys(names.to_str().unwrap());
+        let from_pairs = read_bulk_keys(pairs.to_str().unwrap());
+        let from_invalid = read_bulk_keys(invalid.to_str().unwrap());
+        let written = read_bulk_pairs(pairs.to_str().unwrap());
+        for path in [names, pairs, invalid] {
+            fs::remove_file(path).unwrap();
+        }
+
+        assert_eq!(from_names.unwrap(), vec!["a", "b"]);
+        assert_eq!(from_pairs.unwrap(), vec!["a", "b"]);
+        assert!(matches!(from_invalid, Err(Error::Validation(_))));
+        let written = written.unwrap();
+        assert_eq!(written.len(), 2);
+        assert_eq!((written[1].key.as_str(), written[1].value.as_str(), written[1].expiration_ttl), ("b", "2", Some(60)));
+    }
+}
@@ -0,0 +1,462 @@
+use std::fs;
+
+use cloudflare::framework::apiclient::ApiClient;
+use serde_json::{json, Value};
+use tabular::{Row, Table};
+
+use crate::api::endpoints::load_balancing::{
+    CreateLoadBalancer,
+    CreateMonitor,
+    CreatePool,
+    DeleteLoadBalancer,
+    DeleteMonitor,
+    DeletePool,
+    EditLoadBalancer,
+    EditMonitor,
+    EditPool,
+    ListLoadBalancers,
+    ListMonitors,
+    ListPools,
+    LoadBalancer,
+    LoadBalancerDetails,
+    Monitor,
+    MonitorDetails,
+    Pool,
+    PoolDetails,
+    PoolHealthDetails,
+};
+use crate::commands::{confirm_items, for_each_id, send, submit, table_from_cols, yes_no};
+use crate::error::Error;
+use crate::http::Client;
+use crate::output::{self, Format};
+use crate::terminal;
+
+/// Health of an origin as seen from one data center, a row of `lb pools health`
+#[derive(Serialize)]
+pub struct OriginHealthRow {
+    pub pop: String,
+    pub origin: String,
+    pub address: String,
+    pub healthy: Option<bool>,
+    pub rtt: Option<String>,
+    pub response_code: Option<u16>,
+    pub failure_reason: Option<String>,
+}
+
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(T::to_string).unwrap_or_else(|| "-".to_string())
+}
+
+fn health(healthy: Option<bool>) -> &'static str {
+    healthy.map(yes_no).unwrap_or("-")
+}
+
+/// Reads a YAML or JSON file describing a load balancer, pool or monitor, as sent to the API
+pub fn read_spec(path: &str) -> Result<Value, Error> {
+    let input = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+    let spec: Value = serde_yaml::from_str(&input)
+        .map_err(|e| Error::Validation(format!("Invalid spec file {}: {}", path, e)))?;
+
+    if !spec.is_object() {
+        return Err(Error::Validation(format!("Invalid spec file {}: expected a map of fields", path)));
+    }
+    Ok(spec)
+}
+
+fn fetch_load_balancers(api: &Client, zone_id: &str) -> Result<Vec<LoadBalancer>, Error> {
+    Ok(api.request(&ListLoadBalancers { zone_identifier: zone_id })?.result.load_balancers)
+}
+
+/// Finds the identifier of a load balancer given its identifier or its hostname
+pub fn resolve(api: &Client, zone_id: &str, id_or_name: &str) -> Result<String, Error> {
+    fetch_load_balancers(api, zone_id)?
+        .into_iter()
+        .find(|lb| lb.id == id_or_name || lb.name.eq_ignore_ascii_case(id_or_name))
+        .map(|lb| lb.id)
+        .ok_or_else(|| Error::NotFound(format!("Load balancer \"{}\" not found", id_or_name)))
+}
+
+pub fn list(api: &Client, zone_id: &str, format: Format) -> Result<(), Error> {
+    let load_balancers = fetch_load_balancers(api, zone_id)?;
+
+    output::render(format, &load_balancers, |load_balancers| {
+        let columns = vec![
+            "ID",
+            "NAME",
+            "ENABLED",
+            "PROXIED",
+            "STEERING",
+            "DEFAULT POOLS",
+            "FALLBACK POOL",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for lb in load_balancers {
+            table.add_row(Row::new()
+                .with_cell(&lb.id)
+                .with_cell(&lb.name)
+                .with_cell(yes_no(lb.enabled))
+                .with_cell(yes_no(lb.proxied))
+                .with_cell(lb.steering_policy.as_deref().unwrap_or("off"))
+                .with_cell(lb.default_pools.join(", "))
+                .with_cell(lb.fallback_pool.as_deref().unwrap_or("-")));
+        }
+        table
+    })
+}
+
+fn print_load_balancer(format: Format, lb: &LoadBalancer) -> Result<(), Error> {
+    if format != Format::Table {
+        return output::print(format, lb);
+    }
+
+    let mut table = Table::new("{:<}    {:<}");
+    table.add_row(Row::new().with_cell("ID").with_cell(&lb.id))
+        .add_row(Row::new().with_cell("NAME").with_cell(&lb.name))
+        .add_row(Row::new().with_cell("DESCRIPTION").with_cell(lb.description.as_deref().unwrap_or("-")))
+        .add_row(Row::new().with_cell("ENABLED").with_cell(yes_no(lb.enabled)))
+        .add_row(Row::new().with_cell("PROXIED").with_cell(yes_no(lb.proxied)))
+        .add_row(Row::new().with_cell("TTL").with_cell(optional(&lb.ttl)))
+        .add_row(Row::new().with_cell("STEERING").with_cell(lb.steering_policy.as_deref().unwrap_or("off")))
+        .add_row(Row::new().with_cell("DEFAULT POOLS").with_cell(lb.default_pools.join(", ")))
+        .add_row(Row::new().with_cell("FALLBACK POOL").with_cell(lb.fallback_pool.as_deref().unwrap_or("-")))
+        .add_row(Row::new().with_cell("CREATED").with_cell(optional(&lb.created_on)))
+        .add_row(Row::new().with_cell("MODIFIED").with_cell(optional(&lb.modified_on)));
+    print!("{}", table);
+    Ok(())
+}
+
+pub fn get(api: &Client, zone_id: &str, lb_id: &str, format: Format) -> Result<(), Error> {
+    let lb = api.request(&LoadBalancerDetails { zone_identifier: zone_id, identifier: lb_id })?.result;
+
+    print_load_balancer(format, &lb)
+}
+
+pub fn create(api: &Client, zone_id: &str, spec: Value, format: Format) -> Result<(), Error> {
+    let endpoint = CreateLoadBalancer { zone_identifier: zone_id, params: spec };
+    let Some(lb) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_load_balancer(format, &lb);
+    }
+    terminal::info(format!("Load balancer \"{}\" created with ID {}", lb.name, lb.id).as_str());
+    Ok(())
+}
+
+pub fn update(api: &Client, zone_id: &str, lb_id: &str, spec: Value, format: Format) -> Result<(), Error> {
+    let endpoint = EditLoadBalancer { zone_identifier: zone_id, identifier: lb_id, params: spec };
+    let Some(lb) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_load_balancer(format, &lb);
+    }
+    terminal::info(format!("Load balancer \"{}\" updated", lb.name).as_str());
+    Ok(())
+}
+
+pub fn delete(api: &Client, zone_id: &str, load_balancers: Vec<&str>, yes: bool) -> Result<(), Error> {
+    confirm_items(api, yes, "Delete", "load balancer", "load balancers", &load_balancers)?;
+
+    for_each_id(api, load_balancers, "Load balancer", "load balancers", "deleted", |lb| {
+        let id = resolve(api, zone_id, lb)?;
+        send(api, &DeleteLoadBalancer { zone_identifier: zone_id, identifier: &id })
+    })
+}
+
+fn fetch_pools(api: &Client, account_id: &str) -> Result<Vec<Pool>, Error> {
+    Ok(api.request(&ListPools { account_identifier: account_id })?.result.pools)
+}
+
+/// Finds the identifier of a pool given its identifier or its name
+pub fn resolve_pool(api: &Client, account_id: &str, id_or_name: &str) -> Result<String, Error> {
+    let is_id = id_or_name.len() == 32 && id_or_name.chars().all(|c| c.is_ascii_hexdigit());
+    if is_id {
+        return Ok(id_or_name.to_string());
+    }
+
+    fetch_pools(api, account_id)?
+        .into_iter()
+        .find(|pool| pool.name == id_or_name)
+        .map(|pool| pool.id)
+        .ok_or_else(|| Error::NotFound(format!("Pool \"{}\" not found", id_or_name)))
+}
+
+pub fn list_pools(api: &Client, account_id: &str, format: Format) -> Result<(), Error> {
+    let pools = fetch_pools(api, account_id)?;
+
+    output::render(format, &pools, |pools| {
+        let columns = vec![
+            "ID",
+            "NAME",
+            "ENABLED",
+            "HEALTHY",
+            "ORIGINS",
+            "MONITOR",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for pool in pools {
+            let enabled = pool.origins.iter().filter(|o| o.enabled).count();
+            table.add_row(Row::new()
+                .with_cell(&pool.id)
+                .with_cell(&pool.name)
+                .with_cell(yes_no(pool.enabled))
+                .with_cell(health(pool.healthy))
+                .with_cell(format!("{}/{} enabled", enabled, pool.origins.len()))
+                .with_cell(pool.monitor.as_deref().unwrap_or("-")));
+        }
+        table
+    })
+}
+
+fn print_pool(format: Format, pool: &Pool) -> Result<(), Error> {
+    if format != Format::Table {
+        return output::print(format, pool);
+    }
+
+    let mut table = Table::new("{:<}    {:<}");
+    table.add_row(Row::new().with_cell("ID").with_cell(&pool.id))
+        .add_row(Row::new().with_cell("NAME").with_cell(&pool.name))
+        .add_row(Row::new().with_cell("DESCRIPTION").with_cell(pool.description.as_deref().unwrap_or("-")))
+        .add_row(Row::new().with_cell("ENABLED").with_cell(yes_no(pool.enabled)))
+        .add_row(Row::new().with_cell("HEALTHY").with_cell(health(pool.healthy)))
+        .add_row(Row::new().with_cell("MINIMUM ORIGINS").with_cell(optional(&pool.minimum_origins)))
+        .add_row(Row::new().with_cell("MONITOR").with_cell(pool.monitor.as_deref().unwrap_or("-")))
+        .add_row(Row::new().with_cell("NOTIFICATION EMAIL").with_cell(pool.notification_email.as_deref().unwrap_or("-")))
+        .add_row(Row::new().with_cell("CREATED").with_cell(optional(&pool.created_on)))
+        .add_row(Row::new().with_cell("MODIFIED").with_cell(optional(&pool.modified_on)));
+    print!("{}", table);
+
+    let mut origins = table_from_cols(vec!["ORIGIN", "ADDRESS", "ENABLED", "WEIGHT"]);
+    for origin in &pool.origins {
+        origins.add_row(Row::new()
+            .with_cell(&origin.name)
+            .with_cell(&origin.address)
+            .with_cell(yes_no(origin.enabled))
+            .with_cell(optional(&origin.weight)));
+    }
+    print!("\n{}", origins);
+    Ok(())
+}
+
+pub fn get_pool(api: &Client, account_id: &str, pool_id: &str, format: Format) -> Result<(), Error> {
+    let pool = api.request(&PoolDetails { account_identifier: account_id, identifier: pool_id })?.result;
+
+    print_pool(format, &pool)
+}
+
+pub fn create_pool(api: &Client, account_id: &str, spec: Value, format: Format) -> Result<(), Error> {
+    let endpoint = CreatePool { account_identifier: account_id, params: spec };
+    let Some(pool) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_pool(format, &pool);
+    }
+    terminal::info(format!("Pool \"{}\" created with ID {}", pool.name, pool.id).as_str());
+    Ok(())
+}
+
+pub fn update_pool(api: &Client, account_id: &str, pool_id: &str, spec: Value, format: Format) -> Result<(), Error> {
+    let endpoint = EditPool { account_identifier: account_id, identifier: pool_id, params: spec };
+    let Some(pool) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_pool(format, &pool);
+    }
+    terminal::info(format!("Pool \"{}\" updated", pool.name).as_str());
+    Ok(())
+}
+
+pub fn delete_pools(api: &Client, account_id: &str, pools: Vec<&str>, yes: bool) -> Result<(), Error> {
+    confirm_items(api, yes, "Delete", "pool", "pools", &pools)?;
+
+    for_each_id(api, pools, "Pool", "pools", "deleted", |pool| {
+        let id = resolve_pool(api, account_id, pool)?;
+        send(api, &DeletePool { account_identifier: account_id, identifier: &id })
+    })
+}
+
+/// Enables or disables origins of a pool, given by name or address. Other origins are unchanged.
+pub fn set_origins_enabled(api: &Client, account_id: &str, pool_id: &str, origins: Vec<&str>, enabled: bool) -> Result<(), Error> {
+    let mut pool = api.request(&PoolDetails { account_identifier: account_id, identifier: pool_id })?.result;
+
+    if let Some(unknown) = origins.iter().find(|o| !pool.origins.iter().any(|p| p.name == **o || p.address == **o)) {
+        return Err(Error::NotFound(format!("Origin \"{}\" not found in pool \"{}\"", unknown, pool.name)));
+    }
+    for origin in pool.origins.iter_mut().filter(|p| origins.iter().any(|o| p.name == *o || p.address == *o)) {
+        origin.enabled = enabled;
+    }
+
+    let endpoint = EditPool {
+        account_identifier: account_id,
+        identifier: pool_id,
+        params: json!({ "origins": pool.origins }),
+    };
+    let Some(pool) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    let state = if enabled { "enabled" } else { "disabled" };
+    for origin in origins {
+        terminal::info(format!("Origin \"{}\" {} in pool \"{}\"", origin, state, pool.name).as_str());
+    }
+    Ok(())
+}
+
+/// Shows the health of every origin of a pool from each data center checking it
+pub fn pool_health(api: &Client, account_id: &str, pool_id: &str, format: Format) -> Result<(), Error> {
+    let pool = api.request(&PoolDetails { account_identifier: account_id, identifier: pool_id })?.result;
+    let pool_health = api.request(&PoolHealthDetails { account_identifier: account_id, identifier: pool_id })?.result;
+
+    let mut rows = vec![];
+    for (pop, pop_health) in pool_health.pop_health {
+        for (address, origin_health) in pop_health.origins.into_iter().flatten() {
+            let origin = pool.origins.iter()
+                .find(|o| o.address == address)
+                .map(|o| o.name.clone())
+                .unwrap_or_else(|| "-".to_string());
+            rows.push(OriginHealthRow {
+                pop: pop.clone(),
+                origin,
+                address,
+                healthy: origin_health.healthy,
+                rtt: origin_health.rtt,
+                response_code: origin_health.response_code,
+                failure_reason: origin_health.failure_reason,
+            });
+        }
+    }
+    if rows.is_empty() && format == Format::Table {
+        terminal::warn(format!("No health checks reported for pool \"{}\", is a monitor attached?", pool.name).as_str());
+        return Ok(());
+    }
+
+    output::render(format, &rows, |rows| {
+        let columns = vec![
+            "DATA CENTER",
+            "ORIGIN",
+            "ADDRESS",
+            "HEALTHY",
+            "RTT",
+            "RESPONSE",
+            "FAILURE",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for row in rows {
+            table.add_row(Row::new()
+                .with_cell(&row.pop)
+                .with_cell(&row.origin)
+                .with_cell(&row.address)
+                .with_cell(health(row.healthy))
+                .with_cell(row.rtt.as_deref().unwrap_or("-"))
+                .with_cell(optional(&row.response_code))
+                .with_cell(row.failure_reason.as_deref().unwrap_or("-")));
+        }
+        table
+    })
+}
+
+pub fn list_monitors(api: &Client, account_id: &str, format: Format) -> Result<(), Error> {
+    let monitors = api.request(&ListMonitors { account_identifier: account_id })?.result.monitors;
+
+    output::render(format, &monitors, |monitors| {
+        let columns = vec![
+            "ID",
+            "TYPE",
+            "DESCRIPTION",
+            "CHECK",
+            "EXPECTED CODES",
+            "INTERVAL",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for monitor in monitors {
+            table.add_row(Row::new()
+                .with_cell(&monitor.id)
+                .with_cell(monitor.monitor_type.as_deref().unwrap_or("http"))
+                .with_cell(monitor.description.as_deref().unwrap_or("-"))
+                .with_cell(check(monitor))
+                .with_cell(monitor.expected_codes.as_deref().unwrap_or("-"))
+                .with_cell(monitor.interval.map(|s| format!("{}s", s)).unwrap_or_else(|| "-".to_string())));
+        }
+        table
+    })
+}
+
+// e.g. `GET /health`, or the port for TCP monitors
+fn check(monitor: &Monitor) -> String {
+    match (&monitor.method, &monitor.path) {
+        (Some(method), Some(path)) => format!("{} {}", method, path),
+        _ => monitor.port.map(|port| format!("port {}", port)).unwrap_or_else(|| "-".to_string()),
+    }
+}
+
+fn print_monitor(format: Format, monitor: &Monitor) -> Result<(), Error> {
+    if format != Format::Table {
+        return output::print(format, monitor);
+    }
+
+    let mut table = Table::new("{:<}    {:<}");
+    table.add_row(Row::new().with_cell("ID").with_cell(&monitor.id))
+        .add_row(Row::new().with_cell("TYPE").with_cell(monitor.monitor_type.as_deref().unwrap_or("http")))
+        .add_row(Row::new().with_cell("DESCRIPTION").with_cell(monitor.description.as_deref().unwrap_or("-")))
+        .add_row(Row::new().with_cell("CHECK").with_cell(check(monitor)))
+        .add_row(Row::new().with_cell("PORT").with_cell(optional(&monitor.port)))
+        .add_row(Row::new().with_cell("EXPECTED CODES").with_cell(monitor.expected_codes.as_deref().unwrap_or("-")))
+        .add_row(Row::new().with_cell("INTERVAL").with_cell(optional(&monitor.interval)))
+        .add_row(Row::new().with_cell("TIMEOUT").with_cell(optional(&monitor.timeout)))
+        .add_row(Row::new().with_cell("RETRIES").with_cell(optional(&monitor.retries)))
+        .add_row(Row::new().with_cell("CREATED").with_cell(optional(&monitor.created_on)))
+        .add_row(Row::new().with_cell("MODIFIED").with_cell(optional(&monitor.modified_on)));
+    print!("{}", table);
+    Ok(())
+}
+
+pub fn get_monitor(api: &Client, account_id: &str, monitor_id: &str, format: Format) -> Result<(), Error> {
+    let monitor = api.request(&MonitorDetails { account_identifier: account_id, identifier: monitor_id })?.result;
+
+    print_monitor(format, &monitor)
+}
+
+pub fn create_monitor(api: &Client, account_id: &str, spec: Value, format: Format) -> Result<(), Error> {
+    let endpoint = CreateMonitor { account_identifier: account_id, params: spec };
+    let Some(monitor) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_monitor(format, &monitor);
+    }
+    terminal::info(format!("Monitor created with ID {}", monitor.id).as_str());
+    Ok(())
+}
+
+pub fn update_monitor(api: &Client, account_id: &str, monitor_id: &str, spec: Value, format: Format) -> Result<(), Error> {
+    let endpoint = EditMonitor { account_identifier: account_id, identifier: monitor_id, params: spec };
+    let Some(monitor) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_monitor(format, &monitor);
+    }
+    terminal::info(format!("Monitor \"{}\" updated", monitor.id).as_str());
+    Ok(())
+}
+
+pub fn delete_monitors(api: &Client, account_id: &str, ids: Vec<&str>, yes: bool) -> Result<(), Error> {
+    confirm_items(api, yes, "Delete", "monitor", "monitors", &ids)?;
+
+    for_each_id(api, ids, "Monitor", "monitors", "deleted", |id| {
+        send(api, &DeleteMonitor { account_identifier: account_id, identifier: id })
+    })
+}
@@ -1,10 +1,114 @@
+use cloudflare::framework::apiclient::ApiClient;
+use cloudflare::framework::endpoint::Endpoint;
+use cloudflare::framework::response::{ApiFailure, ApiResult};
+use serde::Serialize;
 use tabular::{Row, Table};
 
+use crate::error::Error;
+use crate::http::Client;
+use crate::terminal;
+
 pub mod accounts;
 pub mod dns;
+pub mod dns_sync;
 pub mod zones;
+pub mod zone_settings;
 pub mod config;
 pub mod cache;
+pub mod pagerules;
+pub mod certs;
+pub mod firewall;
+pub mod workers;
+pub mod kv;
+pub mod lb;
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "Yes" } else { "No" }
+}
+
+// Sends a request whose response isn't needed. In dry-run mode, only prints it.
+fn send<R, Q, B>(api: &Client, endpoint: &dyn Endpoint<R, Q, B>) -> Result<(), Error>
+    where R: ApiResult,
+          Q: Serialize,
+          B: Serialize {
+    submit(api, endpoint)?;
+    Ok(())
+}
+
+// Sends a request and returns its result. In dry-run mode, only prints it and returns `None`.
+fn submit<R, Q, B>(api: &Client, endpoint: &dyn Endpoint<R, Q, B>) -> Result<Option<R>, ApiFailure>
+    where R: ApiResult,
+          Q: Serialize,
+          B: Serialize {
+    if api.dry_run(endpoint) {
+        return Ok(None);
+    }
+    api.request(endpoint).map(|success| Some(success.result))
+}
+
+/// Asks before a destructive operation. `preview` shows what will be affected and returns the
+/// question. Nothing is asked with `--yes`, nor in dry-run mode as nothing changes; when stdin is
+/// not a terminal, nobody can answer and the operation is refused.
+pub fn confirm<F>(api: &Client, yes: bool, preview: F) -> Result<(), Error>
+    where F: FnOnce() -> Result<String, Error> {
+    if yes || api.is_dry_run() {
+        return Ok(());
+    }
+    if !terminal::is_interactive() {
+        return Err(Error::Validation("Refusing to continue without confirmation as stdin is not a terminal, use --yes to confirm".to_string()));
+    }
+
+    let question = preview()?;
+    let answer = terminal::prompt(format!("{} [y/N]", question).as_str());
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(()),
+        _ => Err(Error::Validation("Aborted, nothing was changed".to_string())),
+    }
+}
+
+// Confirms an action on the given items, listing them when there are several
+fn confirm_items(api: &Client, yes: bool, action: &str, what: &str, plural: &str, items: &[&str]) -> Result<(), Error> {
+    confirm(api, yes, || {
+        if let [item] = items {
+            return Ok(format!("{} {} \"{}\"?", action, what, item));
+        }
+        for item in items {
+            terminal::info(format!("  {}", item).as_str());
+        }
+        Ok(format!("{} these {} {}?", action, items.len(), plural))
+    })
+}
+
+// Runs `f` for every identifier, reporting each failure. A single failure is returned as is.
+fn for_each_id<F>(api: &Client, ids: Vec<&str>, what: &str, plural: &str, done: &str, f: F) -> Result<(), Error>
+    where F: Fn(&str) -> Result<(), Error> {
+    let items = ids.into_iter().map(|id| (id.to_string(), id)).collect();
+    for_each_item(api, items, what, plural, done, f)
+}
+
+// Same as `for_each_id` for items that are reported by a label, e.g. rules that have no ID yet
+fn for_each_item<T, F>(api: &Client, items: Vec<(String, T)>, what: &str, plural: &str, done: &str, f: F) -> Result<(), Error>
+    where F: Fn(T) -> Result<(), Error> {
+    let total = items.len();
+    let mut failed = 0;
+
+    for (label, item) in items {
+        match f(item) {
+            Ok(_) if api.is_dry_run() => {}
+            Ok(_) => terminal::info(format!("{} \"{}\" {}", what, label, done).as_str()),
+            Err(e) if total == 1 => return Err(e),
+            Err(e) => {
+                failed += 1;
+                terminal::error(format!("{} \"{}\": {}", what, label, e).as_str());
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Partial(format!("{} of {} {} could not be {}", failed, total, plural, done)));
+    }
+    Ok(())
+}
 
 fn table_from_cols(columns: Vec<&str>) -> Table {
     let cols: Vec<&str> = columns.iter().map(|_| "{:<}").collect();
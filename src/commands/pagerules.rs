@@ -0,0 +1,319 @@
+use std::fs;
+
+use cloudflare::framework::apiclient::ApiClient;
+use serde_json::Value;
+use tabular::{Row, Table};
+
+use crate::api::endpoints::pagerules::{
+    Action,
+    CreatePageRule,
+    DeletePageRule,
+    EditPageRule,
+    EditPageRuleParams,
+    ListPageRules,
+    ListPageRulesParams,
+    PageRule,
+    PageRuleDetails,
+    PageRuleParams,
+    Status,
+    Target,
+    UpdatePageRule,
+};
+use crate::commands::{confirm_items, for_each_id, for_each_item, send, submit, table_from_cols};
+use crate::error::Error;
+use crate::http::Client;
+use crate::output::{self, Format};
+use crate::terminal;
+
+// Times `reorder` moves rules and checks their order before giving up
+const MAX_REORDER_PASSES: usize = 3;
+
+/// Changes requested by `pagerules update`. A spec replaces the whole rule, flags only change
+/// their own field.
+pub struct UpdateParams<'a> {
+    pub spec: Option<PageRuleParams>,
+    pub url: Option<&'a str>,
+    pub actions: Vec<Action>,
+    pub remove_actions: Vec<&'a str>,
+    pub priority: Option<u32>,
+    pub status: Option<Status>,
+}
+
+// A spec file holds one rule or a list of rules
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Spec {
+    One(PageRuleParams),
+    Many(Vec<PageRuleParams>),
+}
+
+/// Reads page rules from a JSON or YAML file
+pub fn read_spec(path: &str) -> Result<Vec<PageRuleParams>, failure::Error> {
+    let input = fs::read_to_string(path)?;
+    // YAML is a superset of JSON, so both are read the same way
+    let rules = match serde_yaml::from_str(&input)? {
+        Spec::One(rule) => vec![rule],
+        Spec::Many(rules) => rules,
+    };
+
+    for rule in &rules {
+        if rule.targets.is_empty() || rule.actions.is_empty() {
+            failure::bail!("every rule needs at least one target and one action");
+        }
+    }
+    Ok(rules)
+}
+
+/// Parses `--action` values such as `always_use_https`, `cache_level=cache_everything` or
+/// `forwarding_url={"url":"https://example.com/$1","status_code":301}`
+pub fn parse_action(arg: &str) -> Result<Action, String> {
+    let (id, value) = match arg.find('=') {
+        Some(i) => (&arg[..i], Some(&arg[i + 1..])),
+        None => (arg, None),
+    };
+    if id.is_empty() {
+        return Err(format!("Invalid action: {}", arg));
+    }
+
+    // Numbers, booleans and objects are sent as JSON, anything else as a string
+    let value = value.map(|v| serde_json::from_str(v).unwrap_or_else(|_| Value::String(v.to_string())));
+    Ok(Action { id: id.to_string(), value })
+}
+
+fn describe_target(target: &Target) -> String {
+    match target.constraint.operator.as_str() {
+        "matches" => target.constraint.value.clone(),
+        operator => format!("{} {}", operator, target.constraint.value),
+    }
+}
+
+fn describe_action(action: &Action) -> String {
+    let name = action.id.replace('_', " ");
+    let name = match name.chars().next() {
+        Some(first) => format!("{}{}", first.to_uppercase(), &name[first.len_utf8()..]),
+        None => name,
+    };
+
+    match (action.id.as_str(), &action.value) {
+        ("forwarding_url", Some(Value::Object(forward))) => format!("{} ({}): {}",
+                                                                    name,
+                                                                    forward.get("status_code").map(|c| c.to_string()).unwrap_or_default(),
+                                                                    forward.get("url").and_then(|u| u.as_str()).unwrap_or_default()),
+        (_, None) => name,
+        (_, Some(Value::String(value))) => format!("{}: {}", name, value),
+        (_, Some(Value::Object(values))) => {
+            let values: Vec<String> = values.iter()
+                .map(|(k, v)| format!("{}={}", k, v.as_str().map(String::from).unwrap_or_else(|| v.to_string())))
+                .collect();
+            format!("{}: {}", name, values.join(", "))
+        }
+        (_, Some(value)) => format!("{}: {}", name, value),
+    }
+}
+
+fn status_name(status: Status) -> &'static str {
+    match status {
+        Status::Active => "active",
+        Status::Disabled => "disabled",
+    }
+}
+
+fn fetch_rules(api: &Client, zone_id: &str, status: Option<Status>) -> Result<Vec<PageRule>, Error> {
+    let response = api.request(&ListPageRules {
+        zone_identifier: zone_id,
+        params: ListPageRulesParams {
+            status,
+            order: Some("priority".to_string()),
+            direction: Some("desc".to_string()),
+        },
+    })?;
+
+    Ok(response.result.rules)
+}
+
+// Prints a single rule, as a key/value listing for the table format
+fn print_rule(format: Format, rule: &PageRule) -> Result<(), Error> {
+    if format != Format::Table {
+        return output::print(format, rule);
+    }
+
+    let mut table = Table::new("{:<}    {:<}");
+    table.add_row(Row::new().with_cell("ID").with_cell(&rule.id))
+        .add_row(Row::new().with_cell("PRIORITY").with_cell(rule.priority))
+        .add_row(Row::new().with_cell("STATUS").with_cell(status_name(rule.status)));
+    for (i, target) in rule.targets.iter().enumerate() {
+        table.add_row(Row::new().with_cell(if i == 0 { "TARGETS" } else { "" }).with_cell(describe_target(target)));
+    }
+    for (i, action) in rule.actions.iter().enumerate() {
+        table.add_row(Row::new().with_cell(if i == 0 { "ACTIONS" } else { "" }).with_cell(describe_action(action)));
+    }
+    table.add_row(Row::new().with_cell("CREATED").with_cell(rule.created_on))
+        .add_row(Row::new().with_cell("MODIFIED").with_cell(rule.modified_on));
+    print!("{}", table);
+    Ok(())
+}
+
+pub fn list(api: &Client, zone_id: &str, status: Option<Status>, format: Format) -> Result<(), Error> {
+    let rules = fetch_rules(api, zone_id, status)?;
+
+    output::render(format, &rules, |rules| {
+        let columns = vec![
+            "ID",
+            "PRIORITY",
+            "STATUS",
+            "TARGET",
+            "ACTIONS",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for rule in rules {
+            let targets: Vec<String> = rule.targets.iter().map(describe_target).collect();
+            let actions: Vec<String> = rule.actions.iter().map(describe_action).collect();
+
+            table.add_row(Row::new()
+                .with_cell(&rule.id)
+                .with_cell(rule.priority)
+                .with_cell(status_name(rule.status))
+                .with_cell(targets.join(", "))
+                .with_cell(actions.join("; ")));
+        }
+        table
+    })
+}
+
+pub fn get(api: &Client, zone_id: &str, id: &str, format: Format) -> Result<(), Error> {
+    let response = api.request(&PageRuleDetails {
+        zone_identifier: zone_id,
+        identifier: id,
+    })?;
+
+    print_rule(format, &response.result)
+}
+
+pub fn create(api: &Client, zone_id: &str, rules: Vec<PageRuleParams>, format: Format) -> Result<(), Error> {
+    let rules = rules.into_iter()
+        .map(|rule| (rule.targets.iter().map(describe_target).collect::<Vec<String>>().join(", "), rule))
+        .collect();
+
+    for_each_item(api, rules, "Page rule", "page rules", "created", |params| {
+        let rule = submit(api, &CreatePageRule { zone_identifier: zone_id, params })?;
+        match rule {
+            Some(rule) if format != Format::Table => print_rule(format, &rule),
+            _ => Ok(()),
+        }
+    })
+}
+
+pub fn update(api: &Client, zone_id: &str, id: &str, input: UpdateParams, format: Format) -> Result<(), Error> {
+    let mut params = match input.spec {
+        Some(spec) => spec,
+        None => {
+            let rule = api.request(&PageRuleDetails { zone_identifier: zone_id, identifier: id })?.result;
+            PageRuleParams {
+                targets: rule.targets,
+                actions: rule.actions,
+                priority: Some(rule.priority),
+                status: Some(rule.status),
+            }
+        }
+    };
+
+    if let Some(url) = input.url {
+        params.targets = vec![Target::url(url)];
+    }
+    let remove_actions = input.remove_actions;
+    params.actions.retain(|a| !remove_actions.contains(&a.id.as_str()));
+    for action in input.actions {
+        match params.actions.iter_mut().find(|a| a.id == action.id) {
+            Some(existing) => *existing = action,
+            None => params.actions.push(action),
+        }
+    }
+    params.priority = input.priority.or(params.priority);
+    params.status = input.status.or(params.status);
+
+    if params.actions.is_empty() {
+        return Err(Error::Validation("A page rule needs at least one action".to_string()));
+    }
+
+    let endpoint = UpdatePageRule {
+        zone_identifier: zone_id,
+        identifier: id,
+        params,
+    };
+    let Some(rule) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format == Format::Table {
+        terminal::info(format!("Page rule \"{}\" updated", rule.id).as_str());
+        return Ok(());
+    }
+    print_rule(format, &rule)
+}
+
+pub fn delete(api: &Client, zone_id: &str, ids: Vec<&str>, yes: bool) -> Result<(), Error> {
+    confirm_items(api, yes, "Delete", "page rule", "page rules", &ids)?;
+
+    for_each_id(api, ids, "Page rule", "page rules", "deleted", |id| {
+        send(api, &DeletePageRule { zone_identifier: zone_id, identifier: id })
+    })
+}
+
+// Whether rules listed by priority are in the given order
+fn is_ordered(rules: &[PageRule], order: &[String]) -> bool {
+    rules.len() == order.len() && rules.iter().zip(order).all(|(rule, id)| &rule.id == id)
+}
+
+/// Moves the given rules to the top, in order. Other rules keep their relative order below them.
+pub fn reorder(api: &Client, zone_id: &str, ids: Vec<&str>) -> Result<(), Error> {
+    let mut rules = fetch_rules(api, zone_id, None)?;
+
+    if let Some(unknown) = ids.iter().find(|id| !rules.iter().any(|r| &r.id == *id)) {
+        return Err(Error::NotFound(format!("Page rule \"{}\" not found", unknown)));
+    }
+
+    let order: Vec<String> = ids.iter().map(|id| id.to_string())
+        .chain(rules.iter().filter(|r| !ids.contains(&r.id.as_str())).map(|r| r.id.clone()))
+        .collect();
+
+    // The first rule gets the highest priority. Cloudflare renumbers the other rules after each
+    // change, so the order is checked again and rules still out of place are moved once more.
+    let count = order.len() as u32;
+    for _ in 0..MAX_REORDER_PASSES {
+        let mut failed = 0;
+        for (i, id) in order.iter().enumerate() {
+            let priority = count - i as u32;
+            if rules.iter().any(|r| &r.id == id && r.priority == priority) {
+                continue;
+            }
+
+            let endpoint = EditPageRule {
+                zone_identifier: zone_id,
+                identifier: id,
+                params: EditPageRuleParams { priority },
+            };
+            match submit(api, &endpoint) {
+                Ok(None) => {}
+                Ok(Some(_)) => terminal::info(format!("Page rule \"{}\" moved to priority {}", id, priority).as_str()),
+                Err(e) => {
+                    failed += 1;
+                    terminal::error(format!("Page rule \"{}\": {}", id, Error::from(e)).as_str());
+                }
+            }
+        }
+
+        if failed > 0 {
+            return Err(Error::Partial(format!("{} page rules could not be moved, the order may be mixed", failed)));
+        }
+        if api.is_dry_run() {
+            return Ok(());
+        }
+        rules = fetch_rules(api, zone_id, None)?;
+        if is_ordered(&rules, &order) {
+            return Ok(());
+        }
+    }
+
+    Err(Error::Api("Page rules did not end up in the requested order, check them with `pagerules list`".to_string()))
+}
@@ -0,0 +1,234 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use cloudflare::endpoints::workers::{CreateRoute, CreateRouteParams, DeleteRoute, ListRoutes, WorkersRoute};
+use cloudflare::framework::apiclient::ApiClient;
+use reqwest::blocking::multipart::{Form, Part};
+use tabular::Row;
+
+use crate::api::endpoints::workers::{
+    Binding,
+    DeleteScript,
+    DownloadScript,
+    ListScripts,
+    ScriptMetadata,
+    UploadScript,
+};
+use crate::commands::{confirm_items, submit, table_from_cols};
+use crate::error::Error;
+use crate::http::Client;
+use crate::output::{self, Format};
+use crate::terminal;
+
+// Form part holding a service worker script
+const SCRIPT_PART: &str = "script";
+
+pub struct UploadParams<'a> {
+    pub name: &'a str,
+    pub file: &'a str,
+    /// The script is an ES module, e.g. `export default { fetch() {...} }`
+    pub module: bool,
+    pub bindings: Vec<Binding>,
+    pub compatibility_date: Option<&'a str>,
+}
+
+/// Parses `--kv`, `--var` and `--secret` values given as `NAME=VALUE`
+pub fn parse_binding(kind: &str, arg: &str) -> Result<Binding, String> {
+    let (name, value) = match arg.find('=') {
+        Some(i) if i > 0 => (arg[..i].to_string(), arg[i + 1..].to_string()),
+        _ => return Err(format!("Invalid binding \"{}\", expected NAME=VALUE", arg)),
+    };
+
+    match kind {
+        "kv" => Ok(Binding::KvNamespace { name, namespace_id: value }),
+        "var" => Ok(Binding::PlainText { name, text: value }),
+        "secret" => Ok(Binding::SecretText { name, text: value }),
+        _ => Err(format!("Unknown binding type: {}", kind)),
+    }
+}
+
+pub fn list(api: &Client, account_id: &str, format: Format) -> Result<(), Error> {
+    let scripts = api.request(&ListScripts { account_identifier: account_id })?.result.scripts;
+
+    output::render(format, &scripts, |scripts| {
+        let columns = vec![
+            "NAME",
+            "HANDLERS",
+            "USAGE MODEL",
+            "MODIFIED",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for script in scripts {
+            table.add_row(Row::new()
+                .with_cell(&script.id)
+                .with_cell(if script.handlers.is_empty() { "-".to_string() } else { script.handlers.join(", ") })
+                .with_cell(script.usage_model.as_deref().unwrap_or("-"))
+                .with_cell(script.modified_on.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string())));
+        }
+        table
+    })
+}
+
+pub fn upload(api: &Client, account_id: &str, params: UploadParams, format: Format) -> Result<(), Error> {
+    let content = fs::read_to_string(params.file).map_err(|e| Error::io(params.file, e))?;
+    let file_name = Path::new(params.file).file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| SCRIPT_PART.to_string());
+
+    // Modules are referenced by file name, service workers by part name
+    let (part_name, mime, metadata) = if params.module {
+        let metadata = ScriptMetadata { main_module: Some(file_name.clone()), ..Default::default() };
+        (file_name.clone(), "application/javascript+module", metadata)
+    } else {
+        let metadata = ScriptMetadata { body_part: Some(SCRIPT_PART.to_string()), ..Default::default() };
+        (SCRIPT_PART.to_string(), "application/javascript", metadata)
+    };
+    let metadata = ScriptMetadata {
+        bindings: params.bindings,
+        compatibility_date: params.compatibility_date.map(String::from),
+        ..metadata
+    };
+
+    let endpoint = UploadScript { account_identifier: account_id, name: params.name };
+    if api.dry_run(&endpoint) {
+        let printed = ScriptMetadata {
+            bindings: metadata.bindings.iter().map(Binding::redacted).collect(),
+            ..metadata
+        };
+        let printed = serde_json::to_string(&printed).map_err(|e| Error::Io(e.to_string()))?;
+        terminal::info(format!("Metadata: {}", printed).as_str());
+        terminal::info(format!("Script of {} bytes", content.len()).as_str());
+        return Ok(());
+    }
+
+    let metadata = serde_json::to_string(&metadata).map_err(|e| Error::Io(e.to_string()))?;
+
+    let form = Part::text(metadata).mime_str("application/json")
+        .and_then(|metadata| {
+            let script = Part::text(content).file_name(file_name).mime_str(mime)?;
+            Ok(Form::new().part("metadata", metadata).part(part_name, script))
+        })
+        .map_err(|e| Error::Io(format!("Could not build the upload: {}", e)))?;
+
+    let script = api.upload(&endpoint, form)?.result;
+
+    if format != Format::Table {
+        return output::print(format, &script);
+    }
+    terminal::info(format!("Worker \"{}\" uploaded", script.id).as_str());
+    Ok(())
+}
+
+pub fn download(api: &Client, account_id: &str, name: &str, file: Option<&str>) -> Result<(), Error> {
+    let script = api.download(&DownloadScript { account_identifier: account_id, name })?;
+
+    match file {
+        Some(path) => {
+            fs::write(path, script).map_err(|e| Error::io(path, e))?;
+            terminal::info(format!("Worker \"{}\" written to {}", name, path).as_str());
+        }
+        None => io::stdout().write_all(&script).map_err(|e| Error::io("stdout", e))?,
+    }
+    Ok(())
+}
+
+pub fn delete(api: &Client, account_id: &str, names: Vec<&str>, yes: bool) -> Result<(), Error> {
+    confirm_items(api, yes, "Delete", "Worker", "Workers", &names)?;
+
+    let total = names.len();
+    let mut failed = 0;
+
+    for name in names {
+        let endpoint = DeleteScript { account_identifier: account_id, name };
+        match submit(api, &endpoint) {
+            Ok(None) => {}
+            Ok(Some(_)) => terminal::info(format!("Worker \"{}\" deleted", name).as_str()),
+            Err(e) if total == 1 => return Err(e.into()),
+            Err(e) => {
+                failed += 1;
+                terminal::error(format!("Worker \"{}\": {}", name, Error::from(e)).as_str());
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Partial(format!("{} of {} workers could not be deleted", failed, total)));
+    }
+    Ok(())
+}
+
+fn fetch_routes(api: &Client, zone_id: &str) -> Result<Vec<WorkersRoute>, Error> {
+    Ok(api.request(&ListRoutes { zone_identifier: zone_id })?.result)
+}
+
+pub fn list_routes(api: &Client, zone_id: &str, format: Format) -> Result<(), Error> {
+    let routes = fetch_routes(api, zone_id)?;
+
+    output::render(format, &routes, |routes| {
+        let columns = vec![
+            "ID",
+            "PATTERN",
+            "WORKER",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for route in routes {
+            table.add_row(Row::new()
+                .with_cell(&route.id)
+                .with_cell(&route.pattern)
+                .with_cell(route.script.as_deref().unwrap_or("-")));
+        }
+        table
+    })
+}
+
+pub fn add_route(api: &Client, zone_id: &str, pattern: &str, script: Option<&str>) -> Result<(), Error> {
+    let endpoint = CreateRoute {
+        zone_identifier: zone_id,
+        params: CreateRouteParams {
+            pattern: pattern.to_string(),
+            script: script.map(String::from),
+        },
+    };
+    let Some(route) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    terminal::info(format!("Route \"{}\" added with ID {}", pattern, route.id).as_str());
+    Ok(())
+}
+
+/// Removes routes given by identifier or pattern
+pub fn remove_routes(api: &Client, zone_id: &str, routes: Vec<&str>, yes: bool) -> Result<(), Error> {
+    let existing = fetch_routes(api, zone_id)?;
+    let ids = routes.iter()
+        .map(|route| existing.iter()
+            .find(|r| r.id == *route || r.pattern == *route)
+            .map(|r| (r.id.as_str(), r.pattern.as_str()))
+            .ok_or_else(|| Error::NotFound(format!("Route \"{}\" not found", route))))
+        .collect::<Result<Vec<_>, _>>()?;
+    let patterns: Vec<&str> = ids.iter().map(|(_, pattern)| *pattern).collect();
+    confirm_items(api, yes, "Remove", "route", "routes", &patterns)?;
+
+    let total = ids.len();
+    let mut failed = 0;
+    for (id, pattern) in ids {
+        let endpoint = DeleteRoute { zone_identifier: zone_id, identifier: id };
+        match submit(api, &endpoint) {
+            Ok(None) => {}
+            Ok(Some(_)) => terminal::info(format!("Route \"{}\" removed", pattern).as_str()),
+            Err(e) if total == 1 => return Err(e.into()),
+            Err(e) => {
+                failed += 1;
+                terminal::error(format!("Route \"{}\": {}", pattern, Error::from(e)).as_str());
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Partial(format!("{} of {} routes could not be removed", failed, total)));
+    }
+    Ok(())
+}
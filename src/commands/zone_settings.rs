@@ -0,0 +1,288 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use cloudflare::endpoints::zone::ListZonesParams;
+use cloudflare::framework::{
+    apiclient::ApiClient,
+    OrderDirection,
+};
+use serde_json::Value;
+use tabular::{Row, Table};
+
+use crate::api::endpoints::zone_settings::{
+    EditZoneSetting,
+    EditZoneSettingParams,
+    EditZoneSettings,
+    EditZoneSettingsParams,
+    ListZoneSettings,
+    SettingValue,
+    ZoneSetting,
+    ZoneSettingDetails,
+};
+use crate::api::endpoints::zones::ListZones;
+use crate::commands::{confirm, submit, table_from_cols};
+use crate::error::Error;
+use crate::http::{self, Client, PageOptions};
+use crate::output::{self, Format};
+use crate::terminal;
+
+const ALL_ZONES_PAGE_SIZE: u32 = 50;
+const ON_OFF: &[&str] = &["on", "off"];
+
+/// Values accepted by a setting
+#[derive(Debug, Clone, Copy)]
+pub enum Kind {
+    /// One of these strings
+    Choice(&'static [&'static str]),
+    /// A number within this range, inclusive
+    Integer(u64, u64),
+}
+
+/// Settings checked before calling the API. Other settings are sent as given.
+pub const KNOWN_SETTINGS: [(&str, Kind); 24] = [
+    ("0rtt", Kind::Choice(ON_OFF)),
+    ("always_online", Kind::Choice(ON_OFF)),
+    ("always_use_https", Kind::Choice(ON_OFF)),
+    ("automatic_https_rewrites", Kind::Choice(ON_OFF)),
+    ("brotli", Kind::Choice(ON_OFF)),
+    ("browser_cache_ttl", Kind::Integer(0, 31_536_000)),
+    ("browser_check", Kind::Choice(ON_OFF)),
+    ("cache_level", Kind::Choice(&["basic", "simplified", "aggressive"])),
+    ("challenge_ttl", Kind::Integer(300, 31_536_000)),
+    ("development_mode", Kind::Choice(ON_OFF)),
+    ("email_obfuscation", Kind::Choice(ON_OFF)),
+    ("hotlink_protection", Kind::Choice(ON_OFF)),
+    ("http2", Kind::Choice(ON_OFF)),
+    ("http3", Kind::Choice(ON_OFF)),
+    ("ip_geolocation", Kind::Choice(ON_OFF)),
+    ("ipv6", Kind::Choice(ON_OFF)),
+    ("min_tls_version", Kind::Choice(&["1.0", "1.1", "1.2", "1.3"])),
+    ("opportunistic_encryption", Kind::Choice(ON_OFF)),
+    ("rocket_loader", Kind::Choice(ON_OFF)),
+    ("security_level", Kind::Choice(&["off", "essentially_off", "low", "medium", "high", "under_attack"])),
+    ("ssl", Kind::Choice(&["off", "flexible", "full", "strict"])),
+    ("tls_1_3", Kind::Choice(&["on", "off", "zrt"])),
+    ("websockets", Kind::Choice(ON_OFF)),
+    ("webp", Kind::Choice(ON_OFF)),
+];
+
+/// Converts a value given on the command line to the JSON value sent to the API, checking it
+/// against `KNOWN_SETTINGS`. Values of unknown settings are sent as JSON if they parse, e.g.
+/// `{"css":"on","html":"off","js":"on"}` for `minify`, and as strings otherwise.
+pub fn parse_value(setting: &str, value: &str) -> Result<Value, String> {
+    let kind = KNOWN_SETTINGS.iter().find(|(name, _)| *name == setting).map(|(_, kind)| *kind);
+
+    match kind {
+        Some(Kind::Choice(choices)) => {
+            if !choices.contains(&value) {
+                return Err(format!("Invalid value \"{}\" for {}, expected one of: {}", value, setting, choices.join(", ")));
+            }
+            Ok(Value::String(value.to_string()))
+        }
+        Some(Kind::Integer(min, max)) => match value.parse::<u64>() {
+            Ok(number) if (min..=max).contains(&number) => Ok(Value::from(number)),
+            _ => Err(format!("Invalid value \"{}\" for {}, expected a number between {} and {}", value, setting, min, max)),
+        },
+        None => Ok(serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))),
+    }
+}
+
+// Values as shown in tables, strings without quotes
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.to_owned(),
+        value => value.to_string(),
+    }
+}
+
+/// Reads a YAML or JSON file mapping setting names to values, e.g. `ssl: strict`
+pub fn read_spec(path: &str) -> Result<Vec<SettingValue>, Error> {
+    let input = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+    let spec: BTreeMap<String, Value> = serde_yaml::from_str(&input)
+        .map_err(|e| Error::Validation(format!("Invalid settings file {}: {}", path, e)))?;
+
+    spec.into_iter()
+        .map(|(id, value)| {
+            // YAML reads `1.2` as a number and `true` as a boolean, settings expect strings
+            let value = match value {
+                Value::Bool(on) => parse_value(&id, if on { "on" } else { "off" }),
+                Value::Number(n) => parse_value(&id, &n.to_string()),
+                Value::String(s) => parse_value(&id, &s),
+                value => Ok(value),
+            };
+            value.map(|value| SettingValue { id, value })
+        })
+        .collect::<Result<_, _>>()
+        .map_err(Error::Validation)
+}
+
+pub fn list(api: &Client, zone_id: &str, format: Format) -> Result<(), Error> {
+    let settings = api.request(&ListZoneSettings { zone_identifier: zone_id })?.result.settings;
+
+    output::render(format, &settings, |settings| {
+        let columns = vec![
+            "SETTING",
+            "VALUE",
+            "EDITABLE",
+            "MODIFIED",
+        ];
+        let mut table = table_from_cols(columns);
+
+        for setting in settings {
+            table.add_row(Row::new()
+                .with_cell(&setting.id)
+                .with_cell(display_value(&setting.value))
+                .with_cell(if setting.editable { "Yes" } else { "No" })
+                .with_cell(setting.modified_on.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string())));
+        }
+        table
+    })
+}
+
+fn print_setting(format: Format, setting: &ZoneSetting) -> Result<(), Error> {
+    if format != Format::Table {
+        return output::print(format, setting);
+    }
+
+    let mut table = Table::new("{:<}    {:<}");
+    table.add_row(Row::new().with_cell("SETTING").with_cell(&setting.id))
+        .add_row(Row::new().with_cell("VALUE").with_cell(display_value(&setting.value)))
+        .add_row(Row::new().with_cell("EDITABLE").with_cell(if setting.editable { "Yes" } else { "No" }))
+        .add_row(Row::new().with_cell("MODIFIED").with_cell(setting.modified_on.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string())));
+    if let Some(seconds) = setting.time_remaining {
+        table.add_row(Row::new().with_cell("TIME REMAINING").with_cell(format!("{}s", seconds)));
+    }
+    print!("{}", table);
+    Ok(())
+}
+
+pub fn get(api: &Client, zone_id: &str, setting: &str, format: Format) -> Result<(), Error> {
+    let setting = api.request(&ZoneSettingDetails {
+        zone_identifier: zone_id,
+        identifier: setting,
+    })?.result;
+
+    print_setting(format, &setting)
+}
+
+pub fn set(api: &Client, zone_id: &str, setting: &str, value: Value, format: Format) -> Result<(), Error> {
+    let endpoint = EditZoneSetting {
+        zone_identifier: zone_id,
+        identifier: setting,
+        params: EditZoneSettingParams { value },
+    };
+    let Some(setting) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_setting(format, &setting);
+    }
+    terminal::info(format!("Setting \"{}\" set to {}", setting.id, display_value(&setting.value)).as_str());
+    Ok(())
+}
+
+/// Applies the same settings to every zone, only sending those that differ
+pub fn apply(api: &Client, settings: &[SettingValue], yes: bool) -> Result<(), Error> {
+    let zones = http::paginate(api, &PageOptions::all(ALL_ZONES_PAGE_SIZE), |page, per_page| ListZones {
+        params: ListZonesParams {
+            name: None,
+            status: None,
+            page: Some(page),
+            per_page: Some(per_page),
+            order: None,
+            direction: Some(OrderDirection::Ascending),
+            search_match: None,
+        },
+    })?;
+    confirm(api, yes, || {
+        for setting in settings {
+            terminal::info(format!("  {}: {}", setting.id, display_value(&setting.value)).as_str());
+        }
+        Ok(format!("Apply these settings to all {} zones?", zones.len()))
+    })?;
+
+    let total = zones.len();
+    let mut failed = 0;
+    for zone in zones {
+        let changes = api.request(&ListZoneSettings { zone_identifier: &zone.id })
+            .map(|current| settings.iter()
+                .filter(|wanted| !current.result.settings.iter().any(|s| s.id == wanted.id && s.value == wanted.value))
+                .cloned()
+                .collect::<Vec<SettingValue>>());
+
+        let result = changes.and_then(|changes| {
+            if changes.is_empty() {
+                return Ok(changes);
+            }
+            let endpoint = EditZoneSettings {
+                zone_identifier: &zone.id,
+                params: EditZoneSettingsParams { items: changes.clone() },
+            };
+            submit(api, &endpoint).map(|_| changes)
+        });
+
+        match result {
+            Ok(changes) if changes.is_empty() => terminal::info(format!("{}: up to date", zone.name).as_str()),
+            Ok(_) if api.is_dry_run() => {}
+            Ok(changes) => {
+                let names: Vec<&str> = changes.iter().map(|c| c.id.as_str()).collect();
+                terminal::info(format!("{}: changed {}", zone.name, names.join(", ")).as_str());
+            }
+            Err(e) => {
+                failed += 1;
+                terminal::error(format!("{}: {}", zone.name, Error::from(e)).as_str());
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Partial(format!("Settings could not be applied to {} of {} zones", failed, total)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn parse_value_checks_known_settings() {
+        assert_eq!(parse_value("ssl", "strict"), Ok(Value::from("strict")));
+        assert_eq!(parse_value("cache_level", "aggressive"), Ok(Value::from("aggressive")));
+        assert!(parse_value("ssl", "everything").is_err());
+        assert!(parse_value("cache_level", "cache_everything").is_err());
+
+        assert_eq!(parse_value("browser_cache_ttl", "3600"), Ok(Value::from(3600)));
+        assert!(parse_value("challenge_ttl", "60").is_err());
+        assert!(parse_value("browser_cache_ttl", "soon").is_err());
+    }
+
+    #[test]
+    fn parse_value_passes_unknown_settings() {
+        assert_eq!(parse_value("minify", r#"{"css":"on"}"#), Ok(serde_json::json!({ "css": "on" })));
+        assert_eq!(parse_value("polish", "lossless"), Ok(Value::from("lossless")));
+    }
+
+    #[test]
+    fn read_spec_converts_yaml_scalars() {
+        let path = env::temp_dir().join(format!("cflare-settings-{}.yaml", std::process::id()));
+        fs::write(&path, "ssl: strict\nipv6: true\nmin_tls_version: 1.2\nbrowser_cache_ttl: 3600\n").unwrap();
+
+        let settings = read_spec(path.to_str().unwrap()).unwrap();
+        let settings: Vec<(&str, &Value)> = settings.iter().map(|s| (s.id.as_str(), &s.value)).collect();
+        assert_eq!(settings, vec![
+            ("browser_cache_ttl", &Value::from(3600)),
+            ("ipv6", &Value::from("on")),
+            ("min_tls_version", &Value::from("1.2")),
+            ("ssl", &Value::from("strict")),
+        ]);
+
+        fs::write(&path, "ssl: everything\n").unwrap();
+        let invalid = read_spec(path.to_str().unwrap());
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(invalid, Err(Error::Validation(_))));
+    }
+}
@@ -1,60 +1,229 @@
-use std::error::Error;
-
 use cloudflare::endpoints::zone::{ListZonesParams};
-use cloudflare::framework::{apiclient::ApiClient, HttpApiClient, OrderDirection};
-use cloudflare::framework::response::{ApiFailure, ApiResponse};
-use tabular::Row;
+use cloudflare::framework::{
+    apiclient::ApiClient,
+    OrderDirection,
+};
+use tabular::{Row, Table};
+
+use crate::commands::{confirm, for_each_id, submit, table_from_cols, yes_no};
+use crate::api::endpoints::plan::Plan;
+use crate::api::endpoints::zones::{
+    AccountIdentifier,
+    CreateZone,
+    CreateZoneParams,
+    DeleteZone,
+    EditZone,
+    EditZoneParams,
+    ListZones,
+    Owner,
+    Type,
+    Zone,
+    ZoneActivationCheck,
+    ZoneDetails,
+};
+use crate::error::Error;
+use crate::http::{self, Client, PageOptions};
+use crate::output::{self, Format};
+use crate::terminal;
+use crate::zone_cache::ZoneCache;
+
+/// Finds the identifier of a zone given its identifier or its name, looking names up in the cache first
+pub fn resolve(api: &Client, cache: &ZoneCache, id_or_name: &str) -> Result<String, Error> {
+    // Identifiers are 32 hexadecimal characters, zone names always have a dot
+    let is_id = id_or_name.len() == 32 && id_or_name.chars().all(|c| c.is_ascii_hexdigit());
+    if is_id {
+        return Ok(id_or_name.to_string());
+    }
+    if let Some(id) = cache.get(id_or_name) {
+        return Ok(id);
+    }
+
+    let zones = api.request(&ListZones {
+        params: ListZonesParams {
+            name: Some(id_or_name.to_string()),
+            status: None,
+            page: None,
+            per_page: Some(1),
+            order: None,
+            direction: None,
+            search_match: None,
+        },
+    })?.result.zones;
 
-use crate::commands::table_from_cols;
-use crate::api::endpoints::zones::{ListZones, ZoneVec};
+    match zones.into_iter().next() {
+        Some(zone) => {
+            cache.insert(id_or_name, &zone.id);
+            Ok(zone.id)
+        }
+        None => Err(Error::NotFound(format!("Zone \"{}\" not found", id_or_name))),
+    }
+}
+
+fn optional_list(values: &Option<Vec<String>>) -> String {
+    match values {
+        Some(values) if !values.is_empty() => values.join(", "),
+        _ => "-".to_string(),
+    }
+}
+
+// Prints every property of a zone, as a key/value listing for the table format
+fn print_zone(format: Format, zone: &Zone) -> Result<(), Error> {
+    if format != Format::Table {
+        return output::print(format, zone);
+    }
+
+    let owner = match &zone.owner {
+        Owner::User { email, .. } => email,
+        Owner::Organization { name, .. } => name,
+    };
+    let plan = |plan: &Option<Plan>| plan.as_ref()
+        .map(|p| p.name.to_owned())
+        .unwrap_or_else(|| "-".to_string());
+
+    let mut table = Table::new("{:<}    {:<}");
+    table.add_row(Row::new().with_cell("ID").with_cell(&zone.id))
+        .add_row(Row::new().with_cell("NAME").with_cell(&zone.name))
+        .add_row(Row::new().with_cell("STATUS").with_cell(format!("{:?}", zone.status)))
+        .add_row(Row::new().with_cell("TYPE").with_cell(format!("{:?}", zone.zone_type)))
+        .add_row(Row::new().with_cell("DNS ONLY").with_cell(yes_no(zone.paused)))
+        .add_row(Row::new().with_cell("PLAN").with_cell(plan(&zone.plan)))
+        .add_row(Row::new().with_cell("PENDING PLAN").with_cell(plan(&zone.plan_pending)))
+        .add_row(Row::new().with_cell("ACCOUNT").with_cell(format!("{} ({})", zone.account.name, zone.account.id)))
+        .add_row(Row::new().with_cell("OWNER").with_cell(owner))
+        .add_row(Row::new().with_cell("NAME SERVERS").with_cell(zone.name_servers.join(", ")))
+        .add_row(Row::new().with_cell("VANITY NAME SERVERS").with_cell(optional_list(&zone.vanity_name_servers)))
+        .add_row(Row::new().with_cell("ORIGINAL NAME SERVERS").with_cell(optional_list(&zone.original_name_servers)))
+        .add_row(Row::new().with_cell("ORIGINAL REGISTRAR").with_cell(zone.original_registrar.as_deref().unwrap_or("-")))
+        .add_row(Row::new().with_cell("ORIGINAL DNS HOST").with_cell(zone.original_dnshost.as_deref().unwrap_or("-")))
+        .add_row(Row::new().with_cell("DEVELOPMENT MODE").with_cell(yes_no(zone.development_mode > 0)))
+        .add_row(Row::new().with_cell("PERMISSIONS").with_cell(zone.permissions.join(", ")))
+        .add_row(Row::new().with_cell("CREATED").with_cell(zone.created_on))
+        .add_row(Row::new().with_cell("MODIFIED").with_cell(zone.modified_on));
+    if let Some(reason) = &zone.deactivation_reason {
+        table.add_row(Row::new().with_cell("DEACTIVATION REASON").with_cell(reason));
+    }
+    print!("{}", table);
+    Ok(())
+}
 
-pub fn list(api: &HttpApiClient, page: u32, limit: u32) {
-    let response: ApiResponse<ZoneVec> = api.request(&ListZones {
+pub fn list(api: &Client, pagination: &PageOptions, format: Format) -> Result<(), Error> {
+    let list = http::paginate(api, pagination, |page, per_page| ListZones {
         params: ListZonesParams {
             name: None,
             status: None,
             page: Some(page),
-            per_page: Some(limit),
+            per_page: Some(per_page),
             order: None,
             direction: Some(OrderDirection::Ascending),
             search_match: None,
         },
-    });
-    match response {
-        Ok(success) => {
-            let list: ZoneVec = success.result;
-            let columns = vec![
-                "ID",
-                "NAME",
-                "STATUS",
-                "PLAN",
-                "DNS ONLY"
-            ];
-
-            let mut table = table_from_cols(columns);
-
-            let vec1 = list.zones;
-            for record in vec1.iter() {
-                let plan: String;
-
-                match &record.plan {
-                    Some(p) => plan = p.name.to_owned(),
-                    _ => plan = "-".to_string()
-                }
-
-                table.add_row(Row::new()
-                    .with_cell(&record.id)
-                    .with_cell(&record.name)
-                    .with_cell(format!("{:?}", record.status))
-                    .with_cell(plan)
-                    .with_cell(if record.paused { "Yes" } else { "No" })
-                );
-            }
-            print!("{}", table);
+    })?;
+
+    output::render(format, &list, |zones| {
+        let columns = vec![
+            "ID",
+            "NAME",
+            "STATUS",
+            "PLAN",
+            "DNS ONLY"
+        ];
+
+        let mut table = table_from_cols(columns);
+
+        for record in zones {
+            let plan = match &record.plan {
+                Some(p) => p.name.to_owned(),
+                _ => "-".to_string()
+            };
+
+            table.add_row(Row::new()
+                .with_cell(&record.id)
+                .with_cell(&record.name)
+                .with_cell(format!("{:?}", record.status))
+                .with_cell(plan)
+                .with_cell(if record.paused { "Yes" } else { "No" })
+            );
         }
-        Err(e) => match e {
-            ApiFailure::Error(code, err) => println!("Error {}: {:?}", code, err),
-            ApiFailure::Invalid(err) => println!("{:?}", err.source())
+        table
+    })
+}
+
+pub fn get(api: &Client, zone_id: &str, format: Format) -> Result<(), Error> {
+    let zone = api.request(&ZoneDetails { identifier: zone_id })?.result;
+
+    print_zone(format, &zone)
+}
+
+pub fn create(api: &Client, name: &str, account_id: &str, zone_type: Type, jump_start: bool, format: Format) -> Result<(), Error> {
+    let endpoint = CreateZone {
+        params: CreateZoneParams {
+            name: name.to_string(),
+            account: AccountIdentifier { id: account_id.to_string() },
+            jump_start: Some(jump_start),
+            zone_type,
         },
+    };
+    let Some(zone) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    if format != Format::Table {
+        return print_zone(format, &zone);
+    }
+
+    terminal::info(format!("Zone \"{}\" created with ID {}", zone.name, zone.id).as_str());
+    match zone_type {
+        Type::Full => terminal::info(format!("Point the domain to these name servers to activate it: {}", zone.name_servers.join(", ")).as_str()),
+        Type::Partial => terminal::info("Add the verification TXT record to your DNS provider to activate it"),
     }
+    Ok(())
+}
+
+pub fn delete(api: &Client, cache: &ZoneCache, zone_ids: Vec<String>, yes: bool) -> Result<(), Error> {
+    confirm(api, yes, || {
+        let mut table = table_from_cols(vec!["ID", "NAME", "STATUS"]);
+        for zone_id in &zone_ids {
+            let zone = api.request(&ZoneDetails { identifier: zone_id })?.result;
+            table.add_row(Row::new()
+                .with_cell(&zone.id)
+                .with_cell(&zone.name)
+                .with_cell(format!("{:?}", zone.status)));
+        }
+        print!("{}", table);
+        terminal::warn("Deleting a zone also deletes its DNS records and settings");
+        Ok(if zone_ids.len() == 1 { "Delete this zone?".to_string() } else { format!("Delete these {} zones?", zone_ids.len()) })
+    })?;
+
+    let ids = zone_ids.iter().map(String::as_str).collect();
+    for_each_id(api, ids, "Zone", "zones", "deleted", |zone_id| {
+        if submit(api, &DeleteZone { identifier: zone_id })?.is_some() {
+            cache.remove_id(zone_id);
+        }
+        Ok(())
+    })
+}
+
+/// Pausing a zone turns off Cloudflare's security and performance features, DNS keeps working
+pub fn set_paused(api: &Client, zone_id: &str, paused: bool) -> Result<(), Error> {
+    let endpoint = EditZone {
+        identifier: zone_id,
+        params: EditZoneParams { paused: Some(paused) },
+    };
+    let Some(zone) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    let state = if zone.paused { "paused" } else { "unpaused" };
+    terminal::info(format!("Zone \"{}\" {}", zone.name, state).as_str());
+    Ok(())
+}
+
+pub fn activation_check(api: &Client, zone_id: &str) -> Result<(), Error> {
+    let endpoint = ZoneActivationCheck { identifier: zone_id };
+    let Some(zone) = submit(api, &endpoint)? else {
+        return Ok(());
+    };
+
+    terminal::info(format!("Activation check requested for zone \"{}\"", zone.id).as_str());
+    Ok(())
 }
@@ -17,6 +17,9 @@ pub enum GlobalCredential {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Context {
     pub name: String,
+    /// Base URL of the API, when not the production API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
     pub credential: GlobalCredential,
 }
 
@@ -29,7 +32,7 @@ pub struct Config {
 // set the permissions on the dir, we want to avoid that other user reads to file
 #[cfg(not(target_os = "windows"))]
 pub fn set_file_mode(file: &PathBuf) {
-    File::open(&file)
+    File::open(file)
         .unwrap()
         .set_permissions(PermissionsExt::from_mode(0o600))
         .expect("could not set permissions on file");
@@ -43,12 +46,17 @@ pub fn get_global_config_path() -> Result<PathBuf, failure::Error> {
     Ok(config_path)
 }
 
+/// Zone identifiers cached by `zone_cache`, next to the config file
+pub fn get_zone_cache_path() -> Result<PathBuf, failure::Error> {
+    Ok(get_global_config_path()?.with_file_name("zone-cache.json"))
+}
+
 impl Config {
     pub fn to_file(&self, config_path: &Path) -> Result<(), failure::Error> {
         let toml = toml::to_string(self)?;
 
-        fs::create_dir_all(&config_path.parent().unwrap())?;
-        fs::write(&config_path, toml)?;
+        fs::create_dir_all(config_path.parent().unwrap())?;
+        fs::write(config_path, toml)?;
 
         // set permissions on the file
         #[cfg(not(target_os = "windows"))]
@@ -79,6 +87,77 @@ impl Config {
             _ => failure::bail!("error while reading config file")
         }
     }
+
+    /// Returns the context selected by `name`, falling back to `current_context`
+    pub fn context(&self, name: Option<&str>) -> Result<&Context, failure::Error> {
+        let name = name.unwrap_or(&self.current_context);
+
+        match self.contexts.iter().find(|c| c.name == name) {
+            Some(context) => Ok(context),
+            None => failure::bail!("context \"{}\" not found", name)
+        }
+    }
+
+    fn position(&self, name: &str) -> Result<usize, failure::Error> {
+        match self.contexts.iter().position(|c| c.name == name) {
+            Some(index) => Ok(index),
+            None => failure::bail!("context \"{}\" not found", name)
+        }
+    }
+
+    /// Adds a new context. The first context added becomes the current one.
+    pub fn add_context(&mut self, context: Context) -> Result<(), failure::Error> {
+        if self.position(&context.name).is_ok() {
+            failure::bail!("context \"{}\" already exists", context.name)
+        }
+
+        if self.contexts.is_empty() {
+            self.current_context = context.name.clone();
+        }
+        self.contexts.push(context);
+        Ok(())
+    }
+
+    /// Replaces the credential of an existing context or adds it otherwise
+    pub fn set_context(&mut self, context: Context) -> Result<(), failure::Error> {
+        match self.position(&context.name) {
+            Ok(index) => {
+                self.contexts[index] = context;
+                Ok(())
+            }
+            Err(_) => self.add_context(context),
+        }
+    }
+
+    pub fn use_context(&mut self, name: &str) -> Result<(), failure::Error> {
+        self.position(name)?;
+        self.current_context = name.to_string();
+        Ok(())
+    }
+
+    pub fn rename_context(&mut self, name: &str, new_name: &str) -> Result<(), failure::Error> {
+        if self.position(new_name).is_ok() {
+            failure::bail!("context \"{}\" already exists", new_name)
+        }
+
+        let index = self.position(name)?;
+        self.contexts[index].name = new_name.to_string();
+        if self.current_context == name {
+            self.current_context = new_name.to_string();
+        }
+        Ok(())
+    }
+
+    /// Removes a context. Removing the current context clears `current_context`
+    /// so that it has to be explicitly selected again with `use-context`.
+    pub fn delete_context(&mut self, name: &str) -> Result<(), failure::Error> {
+        let index = self.position(name)?;
+        self.contexts.remove(index);
+        if self.current_context == name {
+            self.current_context = String::new();
+        }
+        Ok(())
+    }
 }
 
 impl From<GlobalCredential> for Credentials {
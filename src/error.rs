@@ -0,0 +1,95 @@
+use std::fmt;
+
+use cloudflare::framework::response::ApiFailure;
+
+use crate::http;
+
+/// Unexpected local failure, e.g. a file could not be read or written
+pub const EXIT_IO: i32 = 1;
+/// Invalid arguments or input files
+pub const EXIT_USAGE: i32 = 2;
+/// Missing or invalid configuration or credentials
+pub const EXIT_CONFIG: i32 = 3;
+/// Cloudflare rejected the request
+pub const EXIT_API: i32 = 4;
+/// The request could not be sent or its response was not understood
+pub const EXIT_NETWORK: i32 = 5;
+/// A zone, account or other resource does not exist
+pub const EXIT_NOT_FOUND: i32 = 6;
+/// Some operations of a batch failed
+pub const EXIT_PARTIAL: i32 = 7;
+
+/// Errors returned by commands. Each kind maps to its own exit code.
+#[derive(Debug)]
+pub enum Error {
+    Api(String),
+    Network(String),
+    NotFound(String),
+    Config(String),
+    Validation(String),
+    Io(String),
+    /// Each failed operation was already reported, this only sums them up
+    Partial(String),
+}
+
+impl Error {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Api(_) => EXIT_API,
+            Error::Network(_) => EXIT_NETWORK,
+            Error::NotFound(_) => EXIT_NOT_FOUND,
+            Error::Config(_) => EXIT_CONFIG,
+            Error::Validation(_) => EXIT_USAGE,
+            Error::Io(_) => EXIT_IO,
+            Error::Partial(_) => EXIT_PARTIAL,
+        }
+    }
+
+    pub fn config<E: fmt::Display>(e: E) -> Self {
+        Error::Config(e.to_string())
+    }
+
+    pub fn validation<E: fmt::Display>(e: E) -> Self {
+        Error::Validation(e.to_string())
+    }
+
+    /// Local file errors, prefixed with the path
+    pub fn io<E: fmt::Display>(path: &str, e: E) -> Self {
+        Error::Io(format!("{}: {}", path, e))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Api(message)
+            | Error::Network(message)
+            | Error::NotFound(message)
+            | Error::Config(message)
+            | Error::Validation(message)
+            | Error::Io(message)
+            | Error::Partial(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ApiFailure> for Error {
+    fn from(e: ApiFailure) -> Self {
+        let not_found = match &e {
+            ApiFailure::Error(status, _) => status.as_u16() == 404,
+            ApiFailure::Invalid(_) => false,
+        };
+        let network = matches!(e, ApiFailure::Invalid(_));
+        let message = http::format_error(e, Some(&http::error_hint));
+
+        if network {
+            Error::Network(message)
+        } else if not_found {
+            Error::NotFound(message)
+        } else {
+            Error::Api(message)
+        }
+    }
+}
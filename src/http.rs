@@ -1,21 +1,42 @@
-use cloudflare::framework::response::ApiFailure;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use cloudflare::framework::{
+    apiclient::ApiClient,
+    auth::{AuthClient, Credentials},
+    endpoint::{Endpoint, Method},
+    Environment,
+};
+use cloudflare::framework::response::{map_api_response, ApiError, ApiErrors, ApiFailure, ApiResponse, ApiResult};
+use reqwest::blocking::{multipart::Form, RequestBuilder, Response};
+use reqwest::header::{CONTENT_TYPE, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::Serialize;
+
+use crate::terminal;
 
 // Format errors from the cloudflare-rs cli for printing.
 // Optionally takes an argument for providing a function that maps error code numbers to
 // helpful additional information about why someone is getting an error message and how to fix it.
 pub fn format_error(e: ApiFailure, err_helper: Option<&dyn Fn(u16) -> &'static str>) -> String {
     match e {
-        ApiFailure::Error(_, api_errors) => {
+        ApiFailure::Error(status, api_errors) => {
+            if api_errors.errors.is_empty() {
+                return format!("Error: HTTP {}", status);
+            }
+
             let mut complete_err = "".to_string();
             for error in api_errors.errors {
                 let error_msg = format!("Code {}: {}\n", error.code, error.message);
 
-                if let Some(annotate_help) = err_helper {
-                    let suggestion_text = annotate_help(error.code);
-                    let help_msg = format!("{}\n", suggestion_text);
-                    complete_err.push_str(&format!("{}{}", error_msg, help_msg));
-                } else {
+                let suggestion_text = err_helper.map(|annotate_help| annotate_help(error.code)).unwrap_or("");
+                if suggestion_text.is_empty() {
                     complete_err.push_str(&error_msg)
+                } else {
+                    complete_err.push_str(&format!("{}{}\n", error_msg, suggestion_text));
                 }
             }
             complete_err.trim_end().to_string() // Trimming strings in place for String is apparently not a thing...
@@ -23,3 +44,484 @@ pub fn format_error(e: ApiFailure, err_helper: Option<&dyn Fn(u16) -> &'static s
         ApiFailure::Invalid(reqwest_err) => format!("Error: {}", reqwest_err),
     }
 }
+
+/// Suggestions for common API error codes, used as the `err_helper` of `format_error`
+pub fn error_hint(code: u16) -> &'static str {
+    match code {
+        6003 | 6103 | 6111 | 9103 | 9106 => "Hint: check the credentials of the current context with `cflare config get-contexts`, or pass --token",
+        9109 | 10000 => "Hint: the API token is invalid or lacks the permission required by this command",
+        7000 | 7003 => "Hint: the zone or resource identifier is probably wrong",
+        971 | 10429 => "Hint: too many requests, wait a few minutes before trying again",
+        1049 => "Hint: the domain must be registered before adding it to Cloudflare",
+        1061 => "Hint: the zone already exists, list it with `cflare zones list`",
+        1004 | 9005 | 9021 => "Hint: check the value of the fields sent, see `cflare <command> -h`",
+        _ => "",
+    }
+}
+
+/// Page selection shared by list commands
+#[derive(Debug, Clone)]
+pub struct PageOptions {
+    /// Only fetch this page instead of following every page
+    pub page: Option<u32>,
+    pub per_page: u32,
+    /// Stop once this many items have been fetched
+    pub limit: Option<u32>,
+}
+
+impl PageOptions {
+    /// Follow every page
+    pub fn all(per_page: u32) -> Self {
+        PageOptions { page: None, per_page, limit: None }
+    }
+}
+
+/// Results of list endpoints that can be fetched page by page
+pub trait Paginated: ApiResult {
+    type Item;
+
+    fn into_items(self) -> Vec<Self::Item>;
+}
+
+#[derive(Deserialize, Debug)]
+struct ResultInfo {
+    total_pages: Option<u32>,
+}
+
+/// Sends the endpoint built by `endpoint(page, per_page)` and follows `result_info` pages
+/// until they are exhausted or `options.limit` items have been fetched.
+pub fn paginate<R, Q, B, E, F>(api: &Client, options: &PageOptions, endpoint: F) -> Result<Vec<R::Item>, ApiFailure>
+    where R: Paginated,
+          Q: Serialize,
+          B: Serialize,
+          E: Endpoint<R, Q, B>,
+          F: Fn(u32, u32) -> E {
+    let mut items = vec![];
+    let mut page = options.page.unwrap_or(1);
+
+    loop {
+        let response = api.request(&endpoint(page, options.per_page))?;
+        let total_pages = response.result_info
+            .and_then(|info| serde_json::from_value::<ResultInfo>(info).ok())
+            .and_then(|info| info.total_pages);
+        let page_items = response.result.into_items();
+        let count = page_items.len() as u32;
+        items.extend(page_items);
+
+        if let Some(limit) = options.limit {
+            if items.len() as u32 >= limit {
+                items.truncate(limit as usize);
+                break;
+            }
+        }
+
+        let last_page = match total_pages {
+            Some(total) => page >= total,
+            None => count < options.per_page,
+        };
+        if options.page.is_some() || last_page || count == 0 {
+            break;
+        }
+        page += 1;
+    }
+
+    Ok(items)
+}
+
+/// Applies `options.page` and `options.limit` to items that were all fetched, e.g. to filter or
+/// sort them in ways the API can't before picking the requested ones.
+pub fn page_of<T>(items: Vec<T>, options: &PageOptions) -> Vec<T> {
+    let skip = options.page.map_or(0, |page| page.saturating_sub(1).saturating_mul(options.per_page)) as usize;
+    let take = match (options.page, options.limit) {
+        (Some(_), Some(limit)) => options.per_page.min(limit),
+        (Some(_), None) => options.per_page,
+        (None, Some(limit)) => limit,
+        (None, None) => u32::MAX,
+    } as usize;
+
+    items.into_iter().skip(skip).take(take).collect()
+}
+
+/// The production API, or the API at `api_url`, e.g. a local mock server
+pub fn environment(api_url: Option<&str>) -> Result<Environment, String> {
+    let api_url = match api_url {
+        Some(api_url) => api_url,
+        None => return Ok(Environment::Production),
+    };
+
+    // Endpoint paths are relative to the last segment of the URL, which must be a directory
+    let api_url = if api_url.ends_with('/') { api_url.to_string() } else { format!("{}/", api_url) };
+    match reqwest::Url::parse(&api_url) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => Ok(Environment::Custom(url)),
+        Ok(_) => Err(format!("Invalid API URL \"{}\", expected an http or https URL", api_url)),
+        Err(e) => Err(format!("Invalid API URL \"{}\": {}", api_url, e)),
+    }
+}
+
+// Length of the window of Cloudflare's rate limit
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(300);
+// First wait before retrying, doubled on each attempt
+const RETRY_BASE_WAIT: Duration = Duration::from_secs(1);
+const RETRY_MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Limits of the requests sent by `Client`
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    /// Time limit of each request
+    pub timeout: Duration,
+    /// Retries of rate limited requests, failed idempotent requests and connection failures
+    pub retries: u32,
+    /// Most requests sent in any 5 minutes. Cloudflare allows 1200 per user.
+    pub max_rate: u32,
+    /// Print requests that change something instead of sending them
+    pub dry_run: bool,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        ClientOptions {
+            timeout: Duration::from_secs(30),
+            retries: 3,
+            max_rate: 1200,
+            dry_run: false,
+        }
+    }
+}
+
+// Times requests were sent during the last rate limit window
+struct RateBudget {
+    max_rate: u32,
+    sent: VecDeque<Instant>,
+}
+
+impl RateBudget {
+    // Waits until another request fits in the window
+    fn wait(&mut self) {
+        let now = Instant::now();
+        while self.sent.front().is_some_and(|sent| now.duration_since(*sent) >= RATE_LIMIT_WINDOW) {
+            self.sent.pop_front();
+        }
+
+        if self.sent.len() as u32 >= self.max_rate {
+            if let Some(oldest) = self.sent.pop_front() {
+                let wait = RATE_LIMIT_WINDOW - now.duration_since(oldest);
+                terminal::warn(format!("Request budget of {} per 5 minutes used, waiting {} seconds", self.max_rate, wait.as_secs()).as_str());
+                thread::sleep(wait);
+            }
+        }
+        self.sent.push_back(Instant::now());
+    }
+}
+
+/// Backoff before the given retry, doubling from `RETRY_BASE_WAIT` with up to 50% of jitter
+/// so concurrent invocations don't retry in lockstep
+fn backoff(retry: u32) -> Duration {
+    let wait = RETRY_BASE_WAIT.checked_mul(1 << retry.min(16)).unwrap_or(RETRY_MAX_WAIT).min(RETRY_MAX_WAIT);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+    let jitter = wait.mul_f64(f64::from(nanos % 1000) / 2000.0);
+    wait - jitter
+}
+
+// Time to wait given by a 429 response
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    parse_retry_after(value, Utc::now())
+}
+
+// `Retry-After` holds seconds or an HTTP date. The wait never exceeds the rate limit window, so a
+// server can't keep the command waiting any longer.
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    let wait = match value.parse() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        // A date in the past means retrying right away
+        Err(_) => DateTime::parse_from_rfc2822(value).ok()?
+            .signed_duration_since(now)
+            .to_std()
+            .unwrap_or_default(),
+    };
+    Some(wait.min(RATE_LIMIT_WINDOW))
+}
+
+/// Client of the Cloudflare API. On top of `ApiClient::request`, it sends the requests
+/// `HttpApiClient` can't as it only sends and reads JSON: multipart uploads, raw bodies and
+/// raw downloads. Endpoints still provide the method, path and query.
+///
+/// Every request waits for room in the `max_rate` budget. Rate limited requests are retried
+/// after the time given by `Retry-After`; server errors and timeouts are retried with
+/// exponential backoff when the request is idempotent.
+///
+/// In dry-run mode, commands call `dry_run` before each request that changes something and skip
+/// it. Such requests are never sent, even when a command forgets to.
+pub struct Client {
+    environment: Environment,
+    credentials: Credentials,
+    http_client: reqwest::blocking::Client,
+    retries: u32,
+    dry_run: bool,
+    budget: RefCell<RateBudget>,
+}
+
+impl Client {
+    pub fn new(credentials: Credentials, environment: Environment, options: ClientOptions) -> Result<Client, reqwest::Error> {
+        Ok(Client {
+            environment,
+            credentials,
+            http_client: reqwest::blocking::Client::builder().timeout(options.timeout).build()?,
+            retries: options.retries,
+            dry_run: options.dry_run,
+            budget: RefCell::new(RateBudget { max_rate: options.max_rate, sent: VecDeque::new() }),
+        })
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// In dry-run mode, prints the method, URL and JSON body of an endpoint that changes something
+    /// and returns true: the request must not be sent. Otherwise returns false.
+    pub fn dry_run<R, Q, B>(&self, endpoint: &dyn Endpoint<R, Q, B>) -> bool
+        where R: ApiResult,
+              Q: Serialize,
+              B: Serialize {
+        if !self.dry_run || matches!(endpoint.method(), Method::Get) {
+            return false;
+        }
+
+        terminal::info(self.request_line(endpoint).as_str());
+        if let Some(body) = endpoint.body() {
+            terminal::info(serde_json::to_string_pretty(&body).unwrap().as_str());
+        }
+        true
+    }
+
+    // Refuses requests that change something in dry-run mode
+    fn check_dry_run<R, Q, B>(&self, endpoint: &dyn Endpoint<R, Q, B>) -> Result<(), ApiFailure>
+        where R: ApiResult,
+              Q: Serialize,
+              B: Serialize {
+        if !self.dry_run || matches!(endpoint.method(), Method::Get) {
+            return Ok(());
+        }
+
+        let error = ApiError {
+            code: 0,
+            message: format!("{} was not sent in dry-run mode", self.request_line(endpoint)),
+            other: HashMap::new(),
+        };
+        Err(ApiFailure::Error(StatusCode::PRECONDITION_FAILED, ApiErrors { errors: vec![error], other: HashMap::new() }))
+    }
+
+    // Method and URL, with the query string
+    fn request_line<R, Q, B>(&self, endpoint: &dyn Endpoint<R, Q, B>) -> String
+        where R: ApiResult,
+              Q: Serialize,
+              B: Serialize {
+        match self.request_builder(endpoint).build() {
+            Ok(request) => format!("{} {}", request.method(), request.url()),
+            Err(_) => format!("{}", endpoint.url(&self.environment)),
+        }
+    }
+
+    fn request_builder<R, Q, B>(&self, endpoint: &dyn Endpoint<R, Q, B>) -> RequestBuilder
+        where R: ApiResult,
+              Q: Serialize,
+              B: Serialize {
+        let method = match endpoint.method() {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+            Method::Put => reqwest::Method::PUT,
+            Method::Delete => reqwest::Method::DELETE,
+            Method::Patch => reqwest::Method::PATCH,
+        };
+
+        self.http_client
+            .request(method, endpoint.url(&self.environment))
+            .query(&endpoint.query())
+            .auth(&self.credentials)
+    }
+
+    // Sends the request, retrying it when possible. Requests with a streamed body, like
+    // multipart forms, can't be copied and are only sent once.
+    fn execute(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+        let idempotent = request.try_clone()
+            .and_then(|r| r.build().ok())
+            .is_some_and(|r| *r.method() != reqwest::Method::POST && *r.method() != reqwest::Method::PATCH);
+        let mut retry = 0;
+
+        loop {
+            let attempt = match request.try_clone() {
+                Some(attempt) if retry < self.retries => attempt,
+                _ => {
+                    self.budget.borrow_mut().wait();
+                    return request.send();
+                }
+            };
+
+            self.budget.borrow_mut().wait();
+            let result = attempt.send();
+            let wait = match &result {
+                // Rate limited requests were not processed, whatever their method
+                Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                    Some(("Rate limited", retry_after(response).unwrap_or_else(|| backoff(retry))))
+                }
+                Ok(response) if response.status().is_server_error() && idempotent => {
+                    Some(("Server error", backoff(retry)))
+                }
+                Err(e) if e.is_connect() || (e.is_timeout() && idempotent) => Some(("Request failed", backoff(retry))),
+                _ => None,
+            };
+
+            match wait {
+                Some((reason, wait)) => {
+                    retry += 1;
+                    terminal::warn(format!("{}, retrying in {:.1} seconds ({}/{})", reason, wait.as_secs_f64(), retry, self.retries).as_str());
+                    thread::sleep(wait);
+                }
+                None => return result,
+            }
+        }
+    }
+
+    /// Sends a multipart form as the body of the endpoint
+    pub fn upload<R, Q, B>(&self, endpoint: &dyn Endpoint<R, Q, B>, form: Form) -> ApiResponse<R>
+        where R: ApiResult,
+              Q: Serialize,
+              B: Serialize {
+        self.check_dry_run(endpoint)?;
+        let response = self.execute(self.request_builder(endpoint).multipart(form))?;
+
+        map_api_response(response)
+    }
+
+    /// Sends the bytes as the body of the endpoint
+    pub fn send<R, Q, B>(&self, endpoint: &dyn Endpoint<R, Q, B>, body: Vec<u8>) -> ApiResponse<R>
+        where R: ApiResult,
+              Q: Serialize,
+              B: Serialize {
+        self.check_dry_run(endpoint)?;
+        let request = self.request_builder(endpoint)
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .body(body);
+
+        map_api_response(self.execute(request)?)
+    }
+
+    /// Returns the response body as is, for endpoints that don't answer with JSON
+    pub fn download<R, Q, B>(&self, endpoint: &dyn Endpoint<R, Q, B>) -> Result<Vec<u8>, ApiFailure>
+        where R: ApiResult,
+              Q: Serialize,
+              B: Serialize {
+        self.check_dry_run(endpoint)?;
+        let response = self.execute(self.request_builder(endpoint))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let errors: ApiErrors = response.json().unwrap_or_default();
+            return Err(ApiFailure::Error(status, errors));
+        }
+        Ok(response.bytes()?.to_vec())
+    }
+}
+
+impl ApiClient for Client {
+    fn request<R, Q, B>(&self, endpoint: &dyn Endpoint<R, Q, B>) -> ApiResponse<R>
+        where R: ApiResult,
+              Q: Serialize,
+              B: Serialize {
+        self.check_dry_run(endpoint)?;
+        let mut request = self.request_builder(endpoint);
+        if let Some(body) = endpoint.body() {
+            request = request.body(serde_json::to_string(&body).unwrap())
+                .header(CONTENT_TYPE, endpoint.content_type());
+        }
+
+        map_api_response(self.execute(request)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_with_jitter() {
+        for retry in 0..20 {
+            let wait = RETRY_BASE_WAIT.checked_mul(1 << retry.min(16)).unwrap_or(RETRY_MAX_WAIT).min(RETRY_MAX_WAIT);
+            let backoff = backoff(retry);
+            assert!(backoff <= wait && backoff > wait / 2, "retry {}: {:?}", retry, backoff);
+        }
+        assert!(backoff(10) > RETRY_MAX_WAIT / 2);
+    }
+
+    #[test]
+    fn retry_after_seconds_or_date() {
+        let now = DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT").unwrap().with_timezone(&Utc);
+
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now), Some(Duration::from_secs(0)));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn retry_after_is_capped() {
+        let now = Utc::now();
+
+        assert_eq!(parse_retry_after("86400", now), Some(RATE_LIMIT_WINDOW));
+        assert_eq!(parse_retry_after("Fri, 31 Dec 9999 23:59:59 GMT", now), Some(RATE_LIMIT_WINDOW));
+    }
+
+    #[test]
+    fn rate_budget_forgets_requests_out_of_the_window() {
+        let Some(old) = Instant::now().checked_sub(RATE_LIMIT_WINDOW + Duration::from_secs(1)) else {
+            return;
+        };
+        let mut budget = RateBudget { max_rate: 2, sent: vec![old, old].into() };
+
+        let started = Instant::now();
+        budget.wait();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(budget.sent.len(), 1);
+    }
+
+    #[test]
+    fn rate_budget_waits_for_room() {
+        let pause = Duration::from_millis(200);
+        let Some(sent) = Instant::now().checked_sub(RATE_LIMIT_WINDOW - pause) else {
+            return;
+        };
+        let mut budget = RateBudget { max_rate: 1, sent: vec![sent].into() };
+
+        let started = Instant::now();
+        budget.wait();
+        let waited = started.elapsed();
+        assert!(waited >= pause / 2 && waited < Duration::from_secs(5), "{:?}", waited);
+        assert_eq!(budget.sent.len(), 1);
+    }
+
+    fn options(page: Option<u32>, per_page: u32, limit: Option<u32>) -> PageOptions {
+        PageOptions { page, per_page, limit }
+    }
+
+    #[test]
+    fn page_of_slices_the_requested_page() {
+        let items: Vec<u32> = (1..=7).collect();
+
+        assert_eq!(page_of(items.clone(), &options(Some(1), 3, None)), vec![1, 2, 3]);
+        assert_eq!(page_of(items.clone(), &options(Some(3), 3, None)), vec![7]);
+        assert_eq!(page_of(items.clone(), &options(Some(4), 3, None)), Vec::<u32>::new());
+        assert_eq!(page_of(items.clone(), &options(Some(u32::MAX), 3, None)), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn page_of_bounds_by_per_page_and_limit() {
+        let items: Vec<u32> = (1..=7).collect();
+
+        assert_eq!(page_of(items.clone(), &options(Some(1), 100, None)), items);
+        assert_eq!(page_of(items.clone(), &options(Some(2), 3, Some(2))), vec![4, 5]);
+        assert_eq!(page_of(items.clone(), &options(Some(1), 2, Some(5))), vec![1, 2]);
+        assert_eq!(page_of(items.clone(), &options(None, 3, Some(5))), vec![1, 2, 3, 4, 5]);
+        assert_eq!(page_of(items.clone(), &options(None, 3, None)), items);
+    }
+}
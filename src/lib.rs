@@ -3,7 +3,11 @@ extern crate serde;
 extern crate serde_json;
 
 pub mod config;
+pub mod error;
 pub mod commands;
 pub mod terminal;
 pub mod http;
 pub mod api;
+pub mod output;
+pub mod zonefile;
+pub mod zone_cache;
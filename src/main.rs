@@ -1,21 +1,36 @@
-use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
-use cloudflare::endpoints::zone::ListZonesParams;
+use std::fs;
+use std::io::{self, Read};
+use std::time::Duration;
+
+use clap::{App, AppSettings, Arg, ArgMatches, ErrorKind, SubCommand};
 #[allow(unused_imports)]
 use cloudflare::framework::{
     apiclient::ApiClient,
     auth::Credentials,
     Environment,
-    HttpApiClient,
-    HttpApiClientConfig,
+    SearchMatch,
 };
-use cloudflare::framework::response::ApiResponse;
+use regex::Regex;
 
-use cflare::commands::{accounts, config, dns, zones, cache};
-use cflare::config::{Config, get_global_config_path};
-use cflare::api::endpoints::zones::{ListZones, ZoneVec};
-use cflare::terminal;
+use cflare::commands::{accounts, config, dns, dns_sync, zones, zone_settings, cache, pagerules, certs, firewall, workers, kv, lb};
+use cflare::config::{Config, Context, GlobalCredential, get_global_config_path};
+use cflare::api::endpoints::dns::RECORD_TYPES;
+use cflare::api::endpoints::firewall::AccessRuleScope;
+use cflare::api::endpoints::workers::Binding;
+use cflare::api::endpoints::pagerules::{Action, PageRuleParams, Status, Target};
+use cflare::api::endpoints::zones::{Type, ZoneDetails};
+use cflare::{error, output, terminal};
+use cflare::error::Error;
+use cflare::http::{self, Client, ClientOptions, PageOptions};
+use cflare::zone_cache::{self, ZoneCache};
 
 const MAX_DNS_TTL: u32 = 2_147_483_647;
+const DEFAULT_PER_PAGE: &str = "50";
+const DEFAULT_TIMEOUT: &str = "30";
+const DEFAULT_RETRIES: &str = "3";
+const DEFAULT_MAX_RATE: &str = "1200";
+// Seconds zone identifiers are cached for
+const DEFAULT_ZONE_CACHE_TTL: &str = "3600";
 
 fn valid_u32(arg: String) -> Result<(), String> {
     match arg.parse::<u32>() {
@@ -24,6 +39,13 @@ fn valid_u32(arg: String) -> Result<(), String> {
     }
 }
 
+fn valid_positive(arg: String) -> Result<(), String> {
+    match arg.parse::<u32>() {
+        Ok(n) if n > 0 => Ok(()),
+        _ => Err(format!("Value must be a positive integer; received: {}", arg))
+    }
+}
+
 fn valid_priority(arg: String) -> Result<(), String> {
     match arg.parse::<u16>() {
         Ok(_) => Ok(()),
@@ -31,13 +53,21 @@ fn valid_priority(arg: String) -> Result<(), String> {
     }
 }
 
+fn valid_kv_ttl(arg: String) -> Result<(), String> {
+    match arg.parse::<u32>() {
+        Ok(ttl) if ttl >= kv::MIN_TTL => Ok(()),
+        Ok(_) => Err(format!("Value must be at least {} seconds", kv::MIN_TTL)),
+        Err(_) => Err(format!("Value must be an integer; received: {}", arg))
+    }
+}
+
 fn valid_ttl(arg: String) -> Result<(), String> {
     let ttl = arg.parse::<u32>();
 
     match ttl {
         Ok(value) => {
-            if value < 1 || value > MAX_DNS_TTL {
-                return Err(String::from(format!("Value must be between 1 and {} seconds", MAX_DNS_TTL)));
+            if !(1..=MAX_DNS_TTL).contains(&value) {
+                return Err(format!("Value must be between 1 and {} seconds", MAX_DNS_TTL));
             }
             Ok(())
         }
@@ -45,86 +75,206 @@ fn valid_ttl(arg: String) -> Result<(), String> {
     }
 }
 
-fn resolve_zone(api: &HttpApiClient, arg: &ArgMatches) -> String {
-    if arg.is_present("zone-id") { arg.value_of("zone-id").unwrap().to_owned() } else {
-        let zone = arg.value_of("zone").unwrap();
-        let res: ApiResponse<ZoneVec> = api.request(&ListZones {
-            params: ListZonesParams {
-                name: Some(String::from(zone)),
-                status: None,
-                page: None,
-                per_page: Some(1),
-                order: None,
-                direction: None,
-                search_match: None,
-            }
-        });
+fn page_options(arg: &ArgMatches) -> PageOptions {
+    let number = |name: &str| arg.value_of(name).map(|v| v.parse().unwrap());
 
-        match res {
-            Ok(success) => {
-                let res: ZoneVec = success.result;
-                let zones = res.zones;
-                match zones.len() {
-                    1 => zones[0].id.clone(),
-                    _ => {
-                        terminal::error(format!("Zone \"{}\" not found", zone).as_str());
-                        std::process::exit(1);
-                    }
-                }
-            }
-            Err(e) => {
-                println!("{}", e);
-                std::process::exit(1);
-            }
-        }
+    PageOptions {
+        page: number("page"),
+        per_page: number("per-page").unwrap(),
+        limit: number("limit"),
+    }
+}
+
+fn resolve_zone(api: &Client, cache: &ZoneCache, arg: &ArgMatches) -> Result<String, Error> {
+    match arg.value_of("zone-id") {
+        Some(zone_id) => Ok(zone_id.to_owned()),
+        None => zones::resolve(api, cache, arg.value_of("zone").unwrap()),
     }
 }
 
-fn get_api_client(args: &ArgMatches) -> HttpApiClient {
-    let credentials: Credentials;
-    let config_file = get_global_config_path().unwrap();
+// Builds a credential from the --email/--key/--token flags, if any of them was provided
+fn credential_from_args(args: &ArgMatches) -> Result<Option<GlobalCredential>, Error> {
     let cred_flags = args.is_present("email") || args.is_present("key") || args.is_present("token");
-    if !config_file.exists() && !cred_flags {
-        terminal::warn("Config file does not exist. Try running `cflare config`");
-        std::process::exit(1);
+    if !cred_flags {
+        return Ok(None);
     }
 
-    // Set credentials from flags/env
-    if cred_flags {
-        let email = args.value_of("email");
-        let key = args.value_of("key");
-        let token = args.value_of("token");
-
-        credentials = if let Some(key) = key {
-            Credentials::UserAuthKey {
-                email: email.unwrap().to_string(),
-                key: key.to_string(),
-            }
-        } else if let Some(token) = token {
-            Credentials::UserAuthToken {
-                token: token.to_string(),
-            }
-        } else {
-            terminal::error("Either API token or API key + email pair must be provided");
-            std::process::exit(1);
-        };
+    let email = args.value_of("email");
+    let key = args.value_of("key");
+    let token = args.value_of("token");
+
+    if let (Some(email), Some(key)) = (email, key) {
+        Ok(Some(GlobalCredential::GlobalKey {
+            email: email.to_string(),
+            api_key: key.to_string(),
+        }))
+    } else if let Some(token) = token {
+        Ok(Some(GlobalCredential::Token {
+            api_token: token.to_string(),
+        }))
     } else {
-        let config: Config = match Config::from_file(config_file) {
-            Ok(c) => c,
-            Err(e) => {
-                terminal::error(format!("{}", e).as_str());
-                std::process::exit(1);
-            }
-        };
-        let cred = &config.contexts[0].credential;
-        credentials = Credentials::from(cred.to_owned());
+        Err(Error::Config("Either API token or API key + email pair must be provided".to_string()))
+    }
+}
+
+// Zone name for commands that need it, looked up when only --zone-id was given
+fn resolve_zone_name(api: &Client, arg: &ArgMatches, zone_id: &str) -> Result<String, Error> {
+    if let Some(zone) = arg.value_of("zone") {
+        return Ok(zone.to_owned());
+    }
+
+    Ok(api.request(&ZoneDetails { identifier: zone_id })?.result.name)
+}
+
+fn record_fields<'a>(arg: &'a ArgMatches) -> dns::RecordFields<'a> {
+    dns::FIELD_FLAGS.iter()
+        .filter_map(|flag| arg.value_of(flag).map(|value| (*flag, value)))
+        .collect()
+}
+
+fn page_rule_status(arg: &ArgMatches) -> Option<Status> {
+    match arg.value_of("status") {
+        Some("active") => Some(Status::Active),
+        Some(_) => Some(Status::Disabled),
+        None => None,
+    }
+}
+
+fn page_rule_actions(arg: &ArgMatches, name: &str) -> Result<Vec<Action>, Error> {
+    let actions: Vec<&str> = arg.values_of(name).map(|v| v.collect()).unwrap_or_default();
+    actions.into_iter()
+        .map(pagerules::parse_action)
+        .collect::<Result<_, _>>()
+        .map_err(Error::Validation)
+}
+
+fn read_page_rules(file: &str) -> Result<Vec<PageRuleParams>, Error> {
+    pagerules::read_spec(file).map_err(|e| Error::Validation(format!("Invalid spec file {}: {}", file, e)))
+}
+
+// Credentials from flags/env, otherwise from the selected context
+fn get_credential(args: &ArgMatches) -> Result<GlobalCredential, Error> {
+    let config_file = get_global_config_path().map_err(Error::config)?;
+    let cred_flag = credential_from_args(args)?;
+    if !config_file.exists() && cred_flag.is_none() {
+        return Err(Error::Config("Config file does not exist. Try running `cflare config`".to_string()));
+    }
+
+    match cred_flag {
+        Some(cred) => Ok(cred),
+        None => {
+            let config = Config::from_file(config_file).map_err(Error::config)?;
+            let context = config.context(args.value_of("context")).map_err(Error::config)?;
+            Ok(context.credential.to_owned())
+        }
+    }
+}
+
+// Context selected by --context or the current one, if the config file has it
+fn selected_context(args: &ArgMatches) -> Option<Context> {
+    get_global_config_path().ok()
+        .filter(|path| path.exists())
+        .and_then(|path| Config::from_file(path).ok())
+        .and_then(|config| config.context(args.value_of("context")).ok().cloned())
+}
+
+// Zone lookups are cached per context and API URL, credentials given as flags don't belong to any
+fn get_zone_cache(args: &ArgMatches) -> ZoneCache {
+    let cred_flags = args.is_present("email") || args.is_present("key") || args.is_present("token");
+    if args.is_present("no-cache") || cred_flags {
+        return ZoneCache::disabled();
+    }
+
+    let ttl = Duration::from_secs(args.value_of("cache-ttl").unwrap().parse().unwrap());
+    match selected_context(args) {
+        Some(context) => ZoneCache::load(&context, get_api_url(args).as_deref(), ttl),
+        None => ZoneCache::disabled(),
+    }
+}
+
+// --api-url (or CFLARE_API_URL), otherwise the API URL of the selected context
+fn get_api_url(args: &ArgMatches) -> Option<String> {
+    match args.value_of("api-url") {
+        Some(api_url) => Some(api_url.to_string()),
+        None => selected_context(args).and_then(|context| context.api_url),
     }
+}
 
-    HttpApiClient::new(
-        credentials,
-        HttpApiClientConfig::default(),
-        Environment::Production,
-    ).unwrap()
+fn run_cache_local(cmd: &ArgMatches) -> Result<(), Error> {
+    match cmd.subcommand() {
+        ("clear", Some(_)) => {
+            let cleared = zone_cache::clear().map_err(|e| Error::Io(format!("Could not clear the cache: {}", e)))?;
+            terminal::info(if cleared { "Local cache cleared" } else { "Local cache is already empty" });
+            Ok(())
+        }
+        _ => Err(unknown_command())
+    }
+}
+
+fn get_api_client(args: &ArgMatches) -> Result<Client, Error> {
+    let number = |name: &str| args.value_of(name).unwrap().parse::<u32>().unwrap();
+    let options = ClientOptions {
+        timeout: Duration::from_secs(u64::from(number("timeout"))),
+        retries: number("retries"),
+        max_rate: number("max-rate"),
+        dry_run: args.is_present("dry-run"),
+    };
+
+    let environment = http::environment(get_api_url(args).as_deref()).map_err(Error::Config)?;
+
+    Client::new(Credentials::from(get_credential(args)?), environment, options)
+        .map_err(Error::config)
+}
+
+fn workers_bindings(arg: &ArgMatches) -> Result<Vec<Binding>, Error> {
+    let mut bindings = vec![];
+    for kind in &["kv", "var", "secret"] {
+        for value in arg.values_of(kind).into_iter().flatten() {
+            bindings.push(workers::parse_binding(kind, value).map_err(Error::Validation)?);
+        }
+    }
+    Ok(bindings)
+}
+
+fn kv_expiration(arg: &ArgMatches) -> kv::Expiration {
+    match (arg.value_of("expiration"), arg.value_of("ttl")) {
+        (Some(time), _) => kv::Expiration::At(time.parse().unwrap()),
+        (_, Some(ttl)) => kv::Expiration::Ttl(ttl.parse().unwrap()),
+        _ => kv::Expiration::Never,
+    }
+}
+
+fn kv_metadata(arg: &ArgMatches) -> Result<Option<serde_json::Value>, Error> {
+    arg.value_of("metadata")
+        .map(|metadata| serde_json::from_str(metadata)
+            .map_err(|e| Error::Validation(format!("Invalid metadata, expected JSON: {}", e))))
+        .transpose()
+}
+
+fn run_config(args: &ArgMatches, cmd: &ArgMatches) -> Result<(), Error> {
+    let credential = credential_from_args(args)?;
+    let prompt_credential = || credential.clone().unwrap_or_else(|| {
+        let input: String = terminal::prompt("Enter API Token:");
+        GlobalCredential::Token { api_token: input }
+    });
+
+    let result = match cmd.subcommand() {
+        ("add-context", Some(sub_cmd)) => {
+            config::add_context(sub_cmd.value_of("name").unwrap(), &prompt_credential(), args.value_of("api-url"))
+        }
+        ("use-context", Some(sub_cmd)) => config::use_context(sub_cmd.value_of("name").unwrap()),
+        ("get-contexts", Some(_)) => {
+            let format: output::Format = args.value_of("output").unwrap().parse().unwrap();
+            return config::get_contexts(format);
+        }
+        ("rename-context", Some(sub_cmd)) => config::rename_context(
+            sub_cmd.value_of("name").unwrap(),
+            sub_cmd.value_of("new-name").unwrap(),
+        ),
+        ("delete-context", Some(sub_cmd)) => config::delete_context(sub_cmd.value_of("name").unwrap()),
+        _ => config::save_credential(&prompt_credential(), args.value_of("context"), args.value_of("api-url")),
+    };
+    result.map_err(Error::config)
 }
 
 fn main() {
@@ -147,6 +297,70 @@ fn main() {
             .short("t")
             .help("API token generated on the \"My Account\" page")
             .takes_value(true),
+        Arg::with_name("context")
+            .global(true)
+            .long("context")
+            .env("CFLARE_CONTEXT")
+            .help("Config context to use instead of the current one")
+            .takes_value(true),
+        Arg::with_name("no-cache")
+            .global(true)
+            .long("no-cache")
+            .help("Look zones up by name instead of using the identifiers cached by previous commands"),
+        Arg::with_name("cache-ttl")
+            .global(true)
+            .long("cache-ttl")
+            .env("CFLARE_CACHE_TTL")
+            .validator(valid_u32)
+            .default_value(DEFAULT_ZONE_CACHE_TTL)
+            .help("Seconds a zone identifier stays cached")
+            .takes_value(true),
+        Arg::with_name("api-url")
+            .global(true)
+            .long("api-url")
+            .env("CFLARE_API_URL")
+            .help("Base URL of the API, e.g. a local mock server. Saved in the context by `cflare config`")
+            .takes_value(true),
+        Arg::with_name("timeout")
+            .global(true)
+            .long("timeout")
+            .env("CFLARE_TIMEOUT")
+            .validator(valid_positive)
+            .default_value(DEFAULT_TIMEOUT)
+            .help("Seconds before a request is given up")
+            .takes_value(true),
+        Arg::with_name("retries")
+            .global(true)
+            .long("retries")
+            .env("CFLARE_RETRIES")
+            .validator(valid_u32)
+            .default_value(DEFAULT_RETRIES)
+            .help("Retries of rate limited requests, and of reads and idempotent writes failing on a server error")
+            .takes_value(true),
+        Arg::with_name("max-rate")
+            .global(true)
+            .long("max-rate")
+            .env("CFLARE_MAX_RATE")
+            .validator(valid_positive)
+            .default_value(DEFAULT_MAX_RATE)
+            .help("Most requests sent in any 5 minutes, Cloudflare allows 1200")
+            .takes_value(true),
+        Arg::with_name("dry-run")
+            .global(true)
+            .long("dry-run")
+            .help("Print the requests that would change something instead of sending them"),
+        Arg::with_name("yes")
+            .global(true)
+            .long("yes")
+            .help("Don't ask before deleting or purging, required when stdin is not a terminal"),
+        Arg::with_name("output")
+            .global(true)
+            .long("output")
+            .short("o")
+            .help("Output format")
+            .possible_values(&output::FORMATS)
+            .default_value("table")
+            .takes_value(true),
     ];
     let zone = Arg::with_name("zone")
         .help("Zone name. e.g. mydomain.com")
@@ -161,29 +375,306 @@ fn main() {
 
     let zone_args = [zone, zone_id];
 
-    let limit = Arg::with_name("limit")
-        .short("l")
-        .long("limit")
-        .validator(valid_u32)
+    let expression = Arg::with_name("expression")
+        .long("expression")
+        .short("x")
+        .takes_value(true)
+        .help("Rules language expression. e.g. (ip.geoip.country eq \"CN\")");
+    let description = Arg::with_name("description")
+        .long("description")
+        .short("d")
         .takes_value(true);
+    let rule_action = Arg::with_name("action")
+        .long("action")
+        .short("a")
+        .takes_value(true)
+        .possible_values(&firewall::RULE_ACTIONS);
+    let firewall_priority = Arg::with_name("priority")
+        .long("priority")
+        .validator(valid_u32)
+        .takes_value(true)
+        .help("Rules with a lower priority are evaluated first");
+    let firewall_rule_ids = Arg::with_name("id")
+        .required(true)
+        .min_values(1)
+        .help("Firewall rule identifiers");
+    let access_scope_args = [
+        Arg::with_name("zone")
+            .help("Zone name. e.g. mydomain.com")
+            .long("zone")
+            .required_unless_one(&["zone-id", "account"])
+            .takes_value(true),
+        Arg::with_name("zone-id")
+            .long("zone-id")
+            .short("z")
+            .takes_value(true)
+            .conflicts_with("zone"),
+        Arg::with_name("account")
+            .long("account")
+            .takes_value(true)
+            .conflicts_with_all(&["zone", "zone-id"])
+            .help("Account identifier or name, its rules apply to all of its zones"),
+    ];
+    let access_mode = Arg::with_name("mode")
+        .long("mode")
+        .short("m")
+        .takes_value(true)
+        .possible_values(&firewall::ACCESS_RULE_MODES);
+    let access_notes = Arg::with_name("notes")
+        .long("notes")
+        .takes_value(true)
+        .help("Free text, e.g. why the rule was added");
+
+    let account = Arg::with_name("account")
+        .long("account")
+        .short("a")
+        .takes_value(true)
+        .help("Account identifier or name. Defaults to the only account of the credentials");
+    let worker_name = Arg::with_name("name")
+        .required(true)
+        .help("Worker name");
+
+    let namespace = Arg::with_name("namespace")
+        .required(true)
+        .help("Namespace title or identifier");
+
+    let spec_file = Arg::with_name("file")
+        .short("f")
+        .long("file")
+        .required(true)
+        .takes_value(true)
+        .help("YAML or JSON file with the fields of the API object");
+    let pool = Arg::with_name("pool")
+        .required(true)
+        .help("Pool name or identifier");
+
+    let setting_name = Arg::with_name("setting")
+        .required(true)
+        .help("Setting name, e.g. always_use_https");
+
+    let zone_name = Arg::with_name("zone")
+        .required(true)
+        .help("Zone name or identifier. e.g. mydomain.com");
+
+    let pagination_args = [
+        Arg::with_name("limit")
+            .short("l")
+            .long("limit")
+            .validator(valid_u32)
+            .takes_value(true)
+            .help("Maximum number of results, fetched across as many pages as needed"),
+        Arg::with_name("page")
+            .long("page")
+            .validator(valid_positive)
+            .takes_value(true)
+            .help("Only fetch this page of results"),
+        Arg::with_name("per-page")
+            .long("per-page")
+            .validator(valid_positive)
+            .takes_value(true)
+            .default_value(DEFAULT_PER_PAGE)
+            .help("Number of results requested per page"),
+        Arg::with_name("all")
+            .long("all")
+            .conflicts_with_all(&["limit", "page"])
+            .help("Fetch every page of results (default)"),
+    ];
     let record_type = Arg::with_name("type")
         .long("type")
         .takes_value(true)
-        .possible_values(&["A", "AAAA", "CNAME", "MX", "TXT", "NS"]);
+        .possible_values(&RECORD_TYPES);
+
+    // Structured alternative to --content for records with several fields
+    let record_field_args: Vec<Arg> = [
+        ("srv-service", "SRV service, prepended to the record name. e.g. _sip"),
+        ("srv-proto", "SRV protocol, prepended to the record name. e.g. _tcp"),
+        ("weight", "SRV and URI weight"),
+        ("port", "SRV port"),
+        ("target", "MX, SRV, URI, HTTPS and SVCB target"),
+        ("caa-flags", "CAA flags. Defaults to 0"),
+        ("caa-tag", "CAA tag: issue, issuewild or iodef"),
+        ("caa-value", "CAA value. e.g. letsencrypt.org"),
+        ("usage", "TLSA and SMIMEA certificate usage (0-3)"),
+        ("selector", "TLSA and SMIMEA selector (0-1)"),
+        ("matching-type", "TLSA and SMIMEA matching type (0-2)"),
+        ("certificate", "TLSA and SMIMEA certificate data (hex) or CERT certificate (base64)"),
+        ("svc-params", "HTTPS and SVCB parameters. e.g. alpn=\"h3,h2\""),
+        ("key-tag", "DS and CERT key tag"),
+        ("algorithm", "DS, DNSKEY, CERT and SSHFP algorithm"),
+        ("digest-type", "DS digest type"),
+        ("digest", "DS digest (hex)"),
+        ("flags", "DNSKEY flags"),
+        ("protocol", "DNSKEY protocol. Defaults to 3"),
+        ("public-key", "DNSKEY public key (base64)"),
+        ("cert-type", "CERT type"),
+        ("order", "NAPTR order"),
+        ("preference", "NAPTR preference"),
+        ("naptr-flags", "NAPTR flags. e.g. U"),
+        ("service", "NAPTR service. e.g. E2U+sip"),
+        ("regex", "NAPTR regular expression"),
+        ("replacement", "NAPTR replacement"),
+        ("fingerprint-type", "SSHFP fingerprint type: 1 (SHA-1) or 2 (SHA-256)"),
+        ("fingerprint", "SSHFP fingerprint (hex)"),
+    ].iter().map(|(name, help)| Arg::with_name(name).long(name).takes_value(true).help(help)).collect();
+
+    let zone_format = Arg::with_name("format")
+        .long("format")
+        .takes_value(true)
+        .possible_values(&["bind"])
+        .default_value("bind")
+        .help("Zone file format");
+
+    let sync_args = [
+        Arg::with_name("file")
+            .short("f")
+            .long("file")
+            .takes_value(true)
+            .required(true)
+            .help("Desired-state file (YAML or TOML)"),
+        Arg::with_name("prune")
+            .long("prune")
+            .help("Also destroy records that are not managed by the file"),
+    ];
+
+    let page_rule_args = [
+        Arg::with_name("url")
+            .long("url")
+            .takes_value(true)
+            .help("URL pattern the rule applies to. e.g. *mydomain.com/images/*"),
+        Arg::with_name("action")
+            .long("action")
+            .short("a")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .help("Action as id or id=value. e.g. cache_level=cache_everything. Can be repeated"),
+        Arg::with_name("priority")
+            .long("priority")
+            .validator(valid_u32)
+            .takes_value(true)
+            .help("Rules with a higher priority are applied first"),
+        Arg::with_name("status")
+            .long("status")
+            .takes_value(true)
+            .possible_values(&["active", "disabled"])
+            .help("Rule status"),
+        Arg::with_name("file")
+            .short("f")
+            .long("file")
+            .takes_value(true)
+            .conflicts_with("url")
+            .help("JSON or YAML spec file with one rule or a list of rules"),
+    ];
+
+    let context_name = Arg::with_name("name")
+        .required(true)
+        .help("Context name");
 
     let commands = vec![
-        SubCommand::with_name("config").help("Setup your Cloudflare account"),
+        SubCommand::with_name("cache-local")
+            .about("Manage the zone identifiers cached in ~/.cflare")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .subcommand(SubCommand::with_name("clear")
+                .about("Forget every cached zone, in every context")),
+        SubCommand::with_name("config")
+            .about("Setup your Cloudflare account")
+            .subcommands(vec![
+                SubCommand::with_name("add-context")
+                    .about("Add a context with its own credentials")
+                    .arg(context_name.clone()),
+                SubCommand::with_name("use-context")
+                    .about("Set the current context")
+                    .arg(context_name.clone()),
+                SubCommand::with_name("get-contexts")
+                    .about("List the configured contexts"),
+                SubCommand::with_name("rename-context")
+                    .about("Rename a context")
+                    .arg(context_name.clone())
+                    .arg(Arg::with_name("new-name")
+                        .required(true)
+                        .help("New context name")
+                    ),
+                SubCommand::with_name("delete-context")
+                    .about("Delete a context")
+                    .arg(context_name.clone()),
+            ]),
         SubCommand::with_name("accounts")
             .subcommands(vec![
-                SubCommand::with_name("list").arg(
-                    limit.clone()
-                ),
-                SubCommand::with_name("describe"),
+                SubCommand::with_name("list")
+                    .args(&pagination_args.clone()),
+                SubCommand::with_name("describe")
+                    .about("Show an account's settings, members and subscriptions")
+                    .arg(Arg::with_name("account")
+                        .required(true)
+                        .help("Account identifier or name")
+                    ),
             ]),
         SubCommand::with_name("zones")
             .subcommands(vec![
                 SubCommand::with_name("list")
-                    .arg(limit.clone()),
+                    .args(&pagination_args.clone()),
+                SubCommand::with_name("get")
+                    .about("Show every property of a zone")
+                    .arg(zone_name.clone()),
+                SubCommand::with_name("create")
+                    .about("Add a domain to an account")
+                    .arg(Arg::with_name("name")
+                        .required(true)
+                        .help("Domain name. e.g. mydomain.com"))
+                    .arg(Arg::with_name("account")
+                        .long("account")
+                        .short("a")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Account identifier or name"))
+                    .arg(Arg::with_name("type")
+                        .long("type")
+                        .takes_value(true)
+                        .possible_values(&["full", "partial"])
+                        .default_value("full")
+                        .help("Full zones use Cloudflare DNS, partial zones are set up with CNAME records"))
+                    .arg(Arg::with_name("jump-start")
+                        .long("jump-start")
+                        .help("Scan for existing DNS records and import them")),
+                SubCommand::with_name("delete")
+                    .about("Remove zones and all their settings")
+                    .arg(zone_name.clone().multiple(true)),
+                SubCommand::with_name("pause")
+                    .about("Only use Cloudflare DNS, without its security and performance features")
+                    .arg(zone_name.clone()),
+                SubCommand::with_name("unpause")
+                    .about("Turn Cloudflare's security and performance features back on")
+                    .arg(zone_name.clone()),
+                SubCommand::with_name("activation-check")
+                    .about("Check the name servers of a pending zone again")
+                    .arg(zone_name.clone()),
+                SubCommand::with_name("settings")
+                    .about("Manage zone settings such as ssl, always_use_https or min_tls_version")
+                    .setting(AppSettings::SubcommandRequiredElseHelp)
+                    .subcommands(vec![
+                        SubCommand::with_name("list")
+                            .about("Show every setting of a zone")
+                            .args(&zone_args.clone()),
+                        SubCommand::with_name("get")
+                            .about("Show a setting of a zone")
+                            .args(&zone_args.clone())
+                            .arg(setting_name.clone()),
+                        SubCommand::with_name("set")
+                            .about("Change a setting of a zone")
+                            .args(&zone_args.clone())
+                            .arg(setting_name.clone())
+                            .arg(Arg::with_name("value")
+                                .required(true)
+                                .help("New value, e.g. strict for ssl. Objects are given as JSON")),
+                        SubCommand::with_name("apply")
+                            .about("Apply the settings of a YAML or JSON file to every zone")
+                            .arg(Arg::with_name("file")
+                                .short("f")
+                                .long("file")
+                                .required(true)
+                                .takes_value(true)
+                                .help("File mapping setting names to values, e.g. `ssl: strict`")),
+                    ]),
             ]),
         SubCommand::with_name("cache")
             .subcommands(vec![
@@ -192,17 +683,513 @@ fn main() {
                     .arg(Arg::with_name("all")
                         .short("A")
                         .long("all")
+                        .conflicts_with_all(&["url", "file", "tag", "prefix", "host"])
                         .help("Remove ALL files from Cloudflare's cache")
                     )
                     .arg(Arg::with_name("url")
                         .short("u")
                         .long("url")
+                        .takes_value(true)
                         .multiple(true)
-                        .max_values(30)
-                        .required_unless("all")
-                        .conflicts_with("all")
+                        .required_unless_one(&["all", "file", "tag", "prefix", "host"])
                         .help("Remove one or more files from Cloudflare's cache by specifying URLs")
                     )
+                    .arg(Arg::with_name("file")
+                        .short("f")
+                        .long("file")
+                        .takes_value(true)
+                        .help("File with one URL per line. Use - to read from stdin")
+                    )
+                    .arg(Arg::with_name("tag")
+                        .long("tag")
+                        .takes_value(true)
+                        .multiple(true)
+                        .conflicts_with_all(&["url", "file", "prefix", "host"])
+                        .help("Remove files served with these Cache-Tag header values")
+                    )
+                    .arg(Arg::with_name("prefix")
+                        .long("prefix")
+                        .takes_value(true)
+                        .multiple(true)
+                        .conflicts_with_all(&["url", "file", "host"])
+                        .help("Remove files under these URL prefixes. e.g. www.mydomain.com/images")
+                    )
+                    .arg(Arg::with_name("host")
+                        .long("host")
+                        .takes_value(true)
+                        .multiple(true)
+                        .conflicts_with_all(&["url", "file"])
+                        .help("Remove every file of these hostnames")
+                    )
+            ]),
+        SubCommand::with_name("pagerules")
+            .about("Manage Page Rules")
+            .subcommands(vec![
+                SubCommand::with_name("list")
+                    .args(&zone_args.clone())
+                    .arg(Arg::with_name("status")
+                        .long("status")
+                        .takes_value(true)
+                        .possible_values(&["active", "disabled"])
+                        .help("Only list rules with this status")
+                    ),
+                SubCommand::with_name("get")
+                    .args(&zone_args.clone())
+                    .arg(Arg::with_name("id").required(true).help("Page rule identifier")),
+                SubCommand::with_name("create")
+                    .about("Create a rule from flags, or rules from a spec file")
+                    .args(&zone_args.clone())
+                    .args(&page_rule_args.clone()),
+                SubCommand::with_name("update")
+                    .about("Update a rule. A spec file replaces the whole rule")
+                    .args(&zone_args.clone())
+                    .arg(Arg::with_name("id").required(true).help("Page rule identifier"))
+                    .args(&page_rule_args.clone())
+                    .arg(Arg::with_name("remove-action")
+                        .long("remove-action")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Remove the action with this id. Can be repeated")
+                    ),
+                SubCommand::with_name("delete")
+                    .args(&zone_args.clone())
+                    .arg(Arg::with_name("id")
+                        .required(true)
+                        .min_values(1)
+                        .help("Page rule identifier. Multiple values can be provided")
+                    ),
+                SubCommand::with_name("reorder")
+                    .about("Move rules to the top, in the given order")
+                    .args(&zone_args.clone())
+                    .arg(Arg::with_name("id")
+                        .required(true)
+                        .min_values(1)
+                        .help("Page rule identifiers, highest priority first")
+                    ),
+            ]),
+        SubCommand::with_name("firewall")
+            .about("Manage firewall rules, filters and IP Access Rules")
+            .subcommands(vec![
+                SubCommand::with_name("rules")
+                    .about("Manage firewall rules")
+                    .setting(AppSettings::SubcommandRequiredElseHelp)
+                    .subcommands(vec![
+                        SubCommand::with_name("list")
+                            .about("List rules in evaluation order")
+                            .args(&zone_args.clone())
+                            .args(&pagination_args.clone()),
+                        SubCommand::with_name("create")
+                            .args(&zone_args.clone())
+                            .arg(expression.clone().required(true))
+                            .arg(rule_action.clone().required(true))
+                            .arg(description.clone())
+                            .arg(firewall_priority.clone())
+                            .arg(Arg::with_name("paused")
+                                .long("paused")
+                                .help("Create the rule without enabling it")),
+                        SubCommand::with_name("update")
+                            .about("Change the given fields of a rule")
+                            .args(&zone_args.clone())
+                            .arg(Arg::with_name("id").required(true).help("Firewall rule identifier"))
+                            .arg(expression.clone())
+                            .arg(rule_action.clone())
+                            .arg(description.clone())
+                            .arg(firewall_priority.clone()),
+                        SubCommand::with_name("pause")
+                            .args(&zone_args.clone())
+                            .arg(firewall_rule_ids.clone()),
+                        SubCommand::with_name("unpause")
+                            .args(&zone_args.clone())
+                            .arg(firewall_rule_ids.clone()),
+                        SubCommand::with_name("delete")
+                            .about("Delete rules, along with their filters when no other rule uses them")
+                            .args(&zone_args.clone())
+                            .arg(firewall_rule_ids.clone()),
+                        SubCommand::with_name("reorder")
+                            .about("Move rules to the top of the evaluation order")
+                            .args(&zone_args.clone())
+                            .arg(firewall_rule_ids.clone().help("Firewall rule identifiers, in evaluation order")),
+                    ]),
+                SubCommand::with_name("filters")
+                    .about("Manage the filters used by firewall rules")
+                    .setting(AppSettings::SubcommandRequiredElseHelp)
+                    .subcommands(vec![
+                        SubCommand::with_name("list")
+                            .args(&zone_args.clone())
+                            .args(&pagination_args.clone()),
+                        SubCommand::with_name("create")
+                            .args(&zone_args.clone())
+                            .arg(expression.clone().required(true))
+                            .arg(description.clone()),
+                        SubCommand::with_name("update")
+                            .args(&zone_args.clone())
+                            .arg(Arg::with_name("id").required(true).help("Filter identifier"))
+                            .arg(expression.clone())
+                            .arg(description.clone()),
+                        SubCommand::with_name("delete")
+                            .args(&zone_args.clone())
+                            .arg(Arg::with_name("id")
+                                .required(true)
+                                .min_values(1)
+                                .help("Filter identifiers")),
+                    ]),
+                SubCommand::with_name("access-rules")
+                    .about("Manage IP Access Rules of a zone, or of every zone of an account")
+                    .setting(AppSettings::SubcommandRequiredElseHelp)
+                    .subcommands(vec![
+                        SubCommand::with_name("list")
+                            .args(&access_scope_args.clone())
+                            .args(&pagination_args.clone())
+                            .arg(access_mode.clone().help("Only list rules with this mode")),
+                        SubCommand::with_name("create")
+                            .args(&access_scope_args.clone())
+                            .arg(access_mode.clone().required(true))
+                            .arg(Arg::with_name("target")
+                                .long("target")
+                                .takes_value(true)
+                                .possible_values(&firewall::ACCESS_RULE_TARGETS)
+                                .help("What the value is. Guessed from the value by default"))
+                            .arg(Arg::with_name("value")
+                                .required(true)
+                                .help("IP, IP range, ASN or country code. e.g. 198.51.100.0/24, AS12345 or US"))
+                            .arg(access_notes.clone()),
+                        SubCommand::with_name("update")
+                            .args(&access_scope_args.clone())
+                            .arg(Arg::with_name("id").required(true).help("IP Access rule identifier"))
+                            .arg(access_mode.clone())
+                            .arg(access_notes.clone()),
+                        SubCommand::with_name("delete")
+                            .args(&access_scope_args.clone())
+                            .arg(Arg::with_name("id")
+                                .required(true)
+                                .min_values(1)
+                                .help("IP Access rule identifiers")),
+                    ]),
+            ]),
+        SubCommand::with_name("workers")
+            .about("Deploy and manage Workers scripts and routes")
+            .subcommands(vec![
+                SubCommand::with_name("list")
+                    .arg(account.clone()),
+                SubCommand::with_name("upload")
+                    .about("Create or replace a Worker")
+                    .arg(account.clone())
+                    .arg(worker_name.clone())
+                    .arg(Arg::with_name("file")
+                        .short("f")
+                        .long("file")
+                        .required(true)
+                        .takes_value(true)
+                        .help("JavaScript file of the Worker"))
+                    .arg(Arg::with_name("module")
+                        .long("module")
+                        .help("The script is an ES module"))
+                    .arg(Arg::with_name("kv")
+                        .long("kv")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Bind a KV namespace, as NAME=NAMESPACE_ID"))
+                    .arg(Arg::with_name("var")
+                        .long("var")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Bind a plain text variable, as NAME=VALUE"))
+                    .arg(Arg::with_name("secret")
+                        .long("secret")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Bind a secret, as NAME=VALUE"))
+                    .arg(Arg::with_name("compatibility-date")
+                        .long("compatibility-date")
+                        .takes_value(true)
+                        .help("Workers runtime version. e.g. 2024-01-01")),
+                SubCommand::with_name("download")
+                    .about("Print the script of a Worker")
+                    .arg(account.clone())
+                    .arg(worker_name.clone())
+                    .arg(Arg::with_name("file")
+                        .short("f")
+                        .long("file")
+                        .takes_value(true)
+                        .help("Write the script to this file instead")),
+                SubCommand::with_name("delete")
+                    .arg(account.clone())
+                    .arg(worker_name.clone().min_values(1)),
+                SubCommand::with_name("routes")
+                    .about("Manage the routes sending requests of a zone to Workers")
+                    .setting(AppSettings::SubcommandRequiredElseHelp)
+                    .subcommands(vec![
+                        SubCommand::with_name("list")
+                            .args(&zone_args.clone()),
+                        SubCommand::with_name("add")
+                            .args(&zone_args.clone())
+                            .arg(Arg::with_name("pattern")
+                                .required(true)
+                                .help("e.g. mydomain.com/api/*"))
+                            .arg(Arg::with_name("script")
+                                .long("script")
+                                .short("s")
+                                .takes_value(true)
+                                .help("Worker handling the requests. Without it, matching requests skip Workers")),
+                        SubCommand::with_name("remove")
+                            .args(&zone_args.clone())
+                            .arg(Arg::with_name("route")
+                                .required(true)
+                                .min_values(1)
+                                .help("Route identifiers or patterns")),
+                    ]),
+            ]),
+        SubCommand::with_name("kv")
+            .about("Manage Workers KV namespaces and keys")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .subcommands(vec![
+                SubCommand::with_name("namespaces")
+                    .setting(AppSettings::SubcommandRequiredElseHelp)
+                    .subcommands(vec![
+                        SubCommand::with_name("list")
+                            .arg(account.clone())
+                            .args(&pagination_args),
+                        SubCommand::with_name("create")
+                            .arg(account.clone())
+                            .arg(Arg::with_name("title").required(true).help("Namespace title")),
+                        SubCommand::with_name("rename")
+                            .arg(account.clone())
+                            .arg(namespace.clone())
+                            .arg(Arg::with_name("title").required(true).help("New namespace title")),
+                        SubCommand::with_name("delete")
+                            .arg(account.clone())
+                            .arg(namespace.clone().min_values(1).help("Namespace titles or identifiers")),
+                    ]),
+                SubCommand::with_name("keys")
+                    .setting(AppSettings::SubcommandRequiredElseHelp)
+                    .subcommands(vec![
+                        SubCommand::with_name("list")
+                            .arg(account.clone())
+                            .arg(namespace.clone())
+                            .arg(Arg::with_name("prefix")
+                                .long("prefix")
+                                .takes_value(true)
+                                .help("Only list keys starting with this prefix"))
+                            .arg(Arg::with_name("limit")
+                                .short("l")
+                                .long("limit")
+                                .validator(valid_u32)
+                                .takes_value(true)
+                                .help("Maximum number of keys, fetched across as many pages as needed"))
+                            .arg(Arg::with_name("cursor")
+                                .long("cursor")
+                                .takes_value(true)
+                                .help("Continue a previous listing from this cursor")),
+                        SubCommand::with_name("get")
+                            .about("Print the value of a key")
+                            .arg(account.clone())
+                            .arg(namespace.clone())
+                            .arg(Arg::with_name("key-name").value_name("key").required(true))
+                            .arg(Arg::with_name("file")
+                                .short("f")
+                                .long("file")
+                                .takes_value(true)
+                                .help("Write the value to this file instead")),
+                        SubCommand::with_name("put")
+                            .about("Write the value of a key, or many keys with --bulk")
+                            .arg(account.clone())
+                            .arg(namespace.clone())
+                            .arg(Arg::with_name("key-name")
+                                .value_name("key")
+                                .required_unless("bulk"))
+                            .arg(Arg::with_name("value")
+                                .required_unless_one(&["file", "bulk"])
+                                .conflicts_with("file"))
+                            .arg(Arg::with_name("file")
+                                .short("f")
+                                .long("file")
+                                .takes_value(true)
+                                .help("Read the value from this file, or from stdin with -"))
+                            .arg(Arg::with_name("ttl")
+                                .long("ttl")
+                                .takes_value(true)
+                                .validator(valid_kv_ttl)
+                                .help("Seconds before the key expires, at least 60"))
+                            .arg(Arg::with_name("expiration")
+                                .long("expiration")
+                                .takes_value(true)
+                                .validator(valid_u32)
+                                .conflicts_with("ttl")
+                                .help("Time the key expires, in seconds since the UNIX epoch"))
+                            .arg(Arg::with_name("metadata")
+                                .long("metadata")
+                                .takes_value(true)
+                                .help("JSON stored along with the key. e.g. '{\"owner\":\"api\"}'"))
+                            .arg(Arg::with_name("bulk")
+                                .long("bulk")
+                                .takes_value(true)
+                                .conflicts_with_all(&["key-name", "value", "file", "ttl", "expiration", "metadata"])
+                                .help("JSON file of [{\"key\": ..., \"value\": ...}] pairs, or - for stdin")),
+                        SubCommand::with_name("delete")
+                            .arg(account.clone())
+                            .arg(namespace.clone())
+                            .arg(Arg::with_name("key-name")
+                                .value_name("key")
+                                .required_unless("bulk")
+                                .min_values(1))
+                            .arg(Arg::with_name("bulk")
+                                .long("bulk")
+                                .takes_value(true)
+                                .conflicts_with("key-name")
+                                .help("JSON file of key names, or - for stdin")),
+                    ]),
+            ]),
+        SubCommand::with_name("lb")
+            .about("Manage load balancers, their pools and monitors")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .subcommands(vec![
+                SubCommand::with_name("list")
+                    .args(&zone_args.clone()),
+                SubCommand::with_name("get")
+                    .args(&zone_args.clone())
+                    .arg(Arg::with_name("lb").required(true).help("Load balancer hostname or identifier")),
+                SubCommand::with_name("create")
+                    .args(&zone_args.clone())
+                    .arg(spec_file.clone()),
+                SubCommand::with_name("update")
+                    .about("Change the fields given in the spec file")
+                    .args(&zone_args.clone())
+                    .arg(Arg::with_name("lb").required(true).help("Load balancer hostname or identifier"))
+                    .arg(spec_file.clone()),
+                SubCommand::with_name("delete")
+                    .args(&zone_args.clone())
+                    .arg(Arg::with_name("lb")
+                        .required(true)
+                        .min_values(1)
+                        .help("Load balancer hostnames or identifiers")),
+                SubCommand::with_name("pools")
+                    .setting(AppSettings::SubcommandRequiredElseHelp)
+                    .subcommands(vec![
+                        SubCommand::with_name("list")
+                            .arg(account.clone()),
+                        SubCommand::with_name("get")
+                            .arg(account.clone())
+                            .arg(pool.clone()),
+                        SubCommand::with_name("create")
+                            .arg(account.clone())
+                            .arg(spec_file.clone()),
+                        SubCommand::with_name("update")
+                            .about("Change the fields given in the spec file")
+                            .arg(account.clone())
+                            .arg(pool.clone())
+                            .arg(spec_file.clone()),
+                        SubCommand::with_name("delete")
+                            .arg(account.clone())
+                            .arg(pool.clone().min_values(1).help("Pool names or identifiers")),
+                        SubCommand::with_name("health")
+                            .about("Show the health of each origin, as seen from each data center")
+                            .arg(account.clone())
+                            .arg(pool.clone()),
+                        SubCommand::with_name("enable-origin")
+                            .about("Send traffic to origins again")
+                            .arg(account.clone())
+                            .arg(pool.clone())
+                            .arg(Arg::with_name("origin")
+                                .required(true)
+                                .min_values(1)
+                                .help("Origin names or addresses")),
+                        SubCommand::with_name("disable-origin")
+                            .about("Stop sending traffic to origins, e.g. during maintenance")
+                            .arg(account.clone())
+                            .arg(pool.clone())
+                            .arg(Arg::with_name("origin")
+                                .required(true)
+                                .min_values(1)
+                                .help("Origin names or addresses")),
+                    ]),
+                SubCommand::with_name("monitors")
+                    .setting(AppSettings::SubcommandRequiredElseHelp)
+                    .subcommands(vec![
+                        SubCommand::with_name("list")
+                            .arg(account.clone()),
+                        SubCommand::with_name("get")
+                            .arg(account.clone())
+                            .arg(Arg::with_name("id").required(true).help("Monitor identifier")),
+                        SubCommand::with_name("create")
+                            .arg(account.clone())
+                            .arg(spec_file.clone()),
+                        SubCommand::with_name("update")
+                            .about("Change the fields given in the spec file")
+                            .arg(account.clone())
+                            .arg(Arg::with_name("id").required(true).help("Monitor identifier"))
+                            .arg(spec_file.clone()),
+                        SubCommand::with_name("delete")
+                            .arg(account.clone())
+                            .arg(Arg::with_name("id")
+                                .required(true)
+                                .min_values(1)
+                                .help("Monitor identifiers")),
+                    ]),
+            ]),
+        SubCommand::with_name("certs")
+            .about("Manage certificates")
+            .subcommands(vec![
+                SubCommand::with_name("origin")
+                    .about("Manage Origin CA certificates")
+                    .subcommands(vec![
+                        SubCommand::with_name("create")
+                            .about("Generate a private key and CSR locally and have Cloudflare sign it")
+                            .arg(Arg::with_name("hostnames")
+                                .long("hostnames")
+                                .takes_value(true)
+                                .required(true)
+                                .min_values(1)
+                                .help("Hostnames or wildcards to cover. e.g. mydomain.com *.mydomain.com")
+                            )
+                            .arg(Arg::with_name("validity")
+                                .long("validity")
+                                .takes_value(true)
+                                .possible_values(&certs::VALIDITY_DAYS)
+                                .default_value("5475")
+                                .help("Validity in days")
+                            )
+                            .arg(Arg::with_name("type")
+                                .long("type")
+                                .takes_value(true)
+                                .possible_values(&["rsa", "ecc"])
+                                .default_value("rsa")
+                                .help("Private key type")
+                            )
+                            .arg(Arg::with_name("key-file")
+                                .long("key-file")
+                                .takes_value(true)
+                                .help("Where to write the private key. Defaults to <hostname>.key")
+                            )
+                            .arg(Arg::with_name("cert-file")
+                                .long("cert-file")
+                                .takes_value(true)
+                                .help("Where to write the certificate. Defaults to <hostname>.pem")
+                            )
+                            .arg(Arg::with_name("force")
+                                .long("force")
+                                .help("Overwrite existing key and certificate files")
+                            ),
+                        SubCommand::with_name("list")
+                            .args(&zone_args.clone())
+                            .args(&pagination_args.clone()),
+                        SubCommand::with_name("get")
+                            .arg(Arg::with_name("id").required(true).help("Certificate identifier"))
+                            .arg(Arg::with_name("cert-file")
+                                .long("cert-file")
+                                .takes_value(true)
+                                .help("Write the certificate to this file instead of printing it")
+                            ),
+                        SubCommand::with_name("revoke")
+                            .arg(Arg::with_name("id")
+                                .required(true)
+                                .min_values(1)
+                                .help("Certificate identifier. Multiple values can be provided")
+                            ),
+                    ]),
             ]),
         SubCommand::with_name("dns")
             .subcommands(vec![
@@ -211,15 +1198,85 @@ fn main() {
                     .arg(Arg::with_name("wide").long("wide").short("w"))
                     .arg(Arg::with_name("name").long("name").short("n")
                         .takes_value(true).help("Filter by name. Performs partial matching"))
-                    .arg(limit.clone()),
+                    .arg(record_type.clone().help("Filter by record type"))
+                    .arg(Arg::with_name("content")
+                        .long("content")
+                        .takes_value(true)
+                        .help("Filter by exact content. e.g. 192.0.2.1"))
+                    .arg(Arg::with_name("proxied")
+                        .long("proxied")
+                        .takes_value(true)
+                        .possible_values(&["0", "1", "true", "false"])
+                        .help("Filter by proxy status"))
+                    .arg(Arg::with_name("match")
+                        .long("match")
+                        .takes_value(true)
+                        .possible_values(&["any", "all"])
+                        .help("Whether records must match all of --name, --type, --content and --proxied, \
+                               or any of them. Defaults to all"))
+                    .arg(Arg::with_name("regex")
+                        .long("regex")
+                        .takes_value(true)
+                        .help("Only show records whose name or content matches this regular expression. e.g. '^_acme'"))
+                    .arg(Arg::with_name("sort")
+                        .long("sort")
+                        .takes_value(true)
+                        .possible_values(&["name", "type", "ttl", "modified"])
+                        .help("Sort records by this field"))
+                    .arg(Arg::with_name("desc")
+                        .long("desc")
+                        .requires("sort")
+                        .help("Sort in descending order"))
+                    .args(&pagination_args.clone()),
+                SubCommand::with_name("export")
+                    .about("Export all records as a zone file")
+                    .args(&zone_args.clone())
+                    .arg(zone_format.clone())
+                    .arg(Arg::with_name("file")
+                        .short("f")
+                        .long("file")
+                        .takes_value(true)
+                        .help("Write the zone file to this path instead of stdout")
+                    ),
+                SubCommand::with_name("import")
+                    .about("Create records from a zone file")
+                    .args(&zone_args.clone())
+                    .arg(zone_format.clone())
+                    .arg(Arg::with_name("file")
+                        .short("f")
+                        .long("file")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Zone file to import. Use - to read from stdin")
+                    ),
+                SubCommand::with_name("plan")
+                    .about("Show the changes needed to match a desired-state file")
+                    .args(&zone_args.clone())
+                    .args(&sync_args.clone()),
+                SubCommand::with_name("apply")
+                    .about("Apply the changes needed to match a desired-state file")
+                    .args(&zone_args.clone())
+                    .args(&sync_args.clone()),
                 SubCommand::with_name("delete")
+                    .about("Delete records given by identifier, or every record matching the filters")
                     .args(&zone_args.clone())
                     .arg(
                         Arg::with_name("id")
-                            .required(true)
+                            .required_unless_one(&["name", "type", "content-match"])
+                            .conflicts_with_all(&["name", "type", "content-match"])
                             .min_values(1)
                             .help("Record identifier. Multiple values can be provided")
-                    ),
+                    )
+                    .arg(Arg::with_name("name")
+                        .long("name")
+                        .short("n")
+                        .takes_value(true)
+                        .help("Delete the records with this exact name. e.g. foo.mydomain.com"))
+                    .arg(record_type.clone().help("Delete the records of this type"))
+                    .arg(Arg::with_name("content-match")
+                        .long("content-match")
+                        .takes_value(true)
+                        .help("Delete the records whose content matches this pattern, * matching any characters. e.g. 'v=spf1*'")),
                 SubCommand::with_name("create")
                     .arg(Arg::with_name("name")
                         .takes_value(true)
@@ -231,8 +1288,8 @@ fn main() {
                         .short("c")
                         .long("content")
                         .takes_value(true)
-                        .required(true)
-                        .help("DNS record content")
+                        .help("DNS record content, in zone file format for records with several fields. \
+                               e.g. '0 issue \"letsencrypt.org\"'")
                     )
                     .arg(record_type.clone().required(true))
                     .arg(Arg::with_name("ttl")
@@ -251,7 +1308,8 @@ fn main() {
                         .validator(valid_priority)
                         .takes_value(true)
                         .help("Used with some records like MX and SRV to determine priority")
-                    ),
+                    )
+                    .args(&record_field_args),
                 SubCommand::with_name("update")
                     .arg(Arg::with_name("id")
                         .takes_value(true)
@@ -283,6 +1341,49 @@ fn main() {
                         .possible_values(&["0", "1", "true", "false"])
                         .help("Whether the record would be proxied by Cloudflare")
                     )
+                    .arg(Arg::with_name("priority")
+                        .long("priority")
+                        .validator(valid_priority)
+                        .takes_value(true)
+                        .help("Used with some records like MX and SRV to determine priority")
+                    )
+                    .args(&record_field_args),
+                SubCommand::with_name("upsert")
+                    .about("Update the record with this name and type, or create it when there is none")
+                    .arg(Arg::with_name("name")
+                        .takes_value(true)
+                        .required(true)
+                        .help("DNS record name, relative to the zone or fully qualified. Use @ for the apex")
+                    )
+                    .args(&zone_args.clone())
+                    .arg(Arg::with_name("content")
+                        .short("c")
+                        .long("content")
+                        .takes_value(true)
+                        .help("DNS record content, in zone file format for records with several fields")
+                    )
+                    .arg(record_type.clone().required(true))
+                    .arg(Arg::with_name("ttl")
+                        .long("ttl")
+                        .validator(valid_ttl)
+                        .takes_value(true)
+                        .help("Time to live for DNS record. Value of 1 is 'automatic'. \
+                               Defaults to the current value, or automatic for new records")
+                    )
+                    .arg(Arg::with_name("proxied")
+                        .long("proxied")
+                        .takes_value(true)
+                        .possible_values(&["0", "1", "true", "false"])
+                        .help("Whether the record would be proxied by Cloudflare. \
+                               Defaults to the current value, or false for new records")
+                    )
+                    .arg(Arg::with_name("priority")
+                        .long("priority")
+                        .validator(valid_priority)
+                        .takes_value(true)
+                        .help("Used with some records like MX and SRV to determine priority")
+                    )
+                    .args(&record_field_args)
             ]),
     ];
 
@@ -290,73 +1391,494 @@ fn main() {
         .name(env!("CARGO_PKG_NAME"))
         .about(env!("CARGO_PKG_DESCRIPTION"))
         .version(env!("CARGO_PKG_VERSION"))
-        .setting(AppSettings::ArgRequiredElseHelp)
+        .setting(AppSettings::SubcommandRequiredElseHelp)
         .setting(AppSettings::DeriveDisplayOrder)
         .setting(AppSettings::VersionlessSubcommands)
         .subcommands(commands)
         .args(&auth_args)
-        .get_matches();
+        .get_matches_safe()
+        .unwrap_or_else(|e| match e.kind {
+            ErrorKind::HelpDisplayed | ErrorKind::VersionDisplayed => e.exit(),
+            _ => {
+                eprintln!("{}", e.message);
+                std::process::exit(error::EXIT_USAGE);
+            }
+        });
 
-    if app.subcommand().0 == "config" {
-        let input: String = terminal::prompt("Enter API Token:");
-        let credential = cflare::config::GlobalCredential::Token { api_token: input };
-        if let Err(e) = config::save_credential(&credential) {
-            terminal::error(format!("{}", e).as_str());
-            std::process::exit(1);
-        }
-        return;
+    if let Err(e) = run(&app) {
+        terminal::error(e.to_string().as_str());
+        std::process::exit(e.exit_code());
+    }
+}
+
+fn run(app: &ArgMatches) -> Result<(), Error> {
+    if let ("config", Some(cmd)) = app.subcommand() {
+        return run_config(app, cmd);
+    }
+    if let ("cache-local", Some(cmd)) = app.subcommand() {
+        return run_cache_local(cmd);
     }
 
-    let api = get_api_client(&app);
+    let api = get_api_client(app)?;
+    let zone_cache = get_zone_cache(app);
+    let format: output::Format = app.value_of("output").unwrap().parse().unwrap();
+    let yes = app.is_present("yes");
     match app.subcommand() {
         ("accounts", Some(sub_cmd)) => match sub_cmd.subcommand() {
-            ("list", Some(cmd)) => {
-                let limit: u32 = cmd.value_of("limit").unwrap_or("50").parse().unwrap();
-                accounts::list(&api, 1, limit)
+            ("list", Some(cmd)) => accounts::list(&api, &page_options(cmd), format),
+            ("describe", Some(cmd)) => {
+                let account = accounts::resolve(&api, cmd.value_of("account").unwrap())?;
+                accounts::describe(&api, &account, format)
             }
-            _ => terminal::error("Unknown command")
+            _ => Err(unknown_command())
         },
         ("zones", Some(sub_cmd)) => match sub_cmd.subcommand() {
-            ("list", Some(cmd)) => {
-                let limit: u32 = cmd.value_of("limit").unwrap_or("50").parse().unwrap();
-                zones::list(&api, 1, limit)
+            ("list", Some(cmd)) => zones::list(&api, &page_options(cmd), format),
+            ("get", Some(cmd)) => {
+                let zone = zones::resolve(&api, &zone_cache, cmd.value_of("zone").unwrap())?;
+                zones::get(&api, &zone, format)
+            }
+            ("create", Some(cmd)) => {
+                let account = accounts::resolve(&api, cmd.value_of("account").unwrap())?;
+                let zone_type = match cmd.value_of("type") {
+                    Some("partial") => Type::Partial,
+                    _ => Type::Full,
+                };
+                zones::create(&api, cmd.value_of("name").unwrap(), &account, zone_type, cmd.is_present("jump-start"), format)
+            }
+            ("delete", Some(cmd)) => {
+                let zones = cmd.values_of("zone").unwrap()
+                    .map(|zone| zones::resolve(&api, &zone_cache, zone))
+                    .collect::<Result<_, _>>()?;
+                zones::delete(&api, &zone_cache, zones, yes)
+            }
+            (action @ "pause", Some(cmd)) | (action @ "unpause", Some(cmd)) => {
+                let zone = zones::resolve(&api, &zone_cache, cmd.value_of("zone").unwrap())?;
+                zones::set_paused(&api, &zone, action == "pause")
+            }
+            ("activation-check", Some(cmd)) => {
+                let zone = zones::resolve(&api, &zone_cache, cmd.value_of("zone").unwrap())?;
+                zones::activation_check(&api, &zone)
             }
-            _ => unimplemented!()
+            ("settings", Some(settings_cmd)) => match settings_cmd.subcommand() {
+                ("list", Some(cmd)) => {
+                    let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                    zone_settings::list(&api, &zone, format)
+                }
+                ("get", Some(cmd)) => {
+                    let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                    zone_settings::get(&api, &zone, cmd.value_of("setting").unwrap(), format)
+                }
+                ("set", Some(cmd)) => {
+                    let setting = cmd.value_of("setting").unwrap();
+                    let value = zone_settings::parse_value(setting, cmd.value_of("value").unwrap())
+                        .map_err(Error::Validation)?;
+                    let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                    zone_settings::set(&api, &zone, setting, value, format)
+                }
+                ("apply", Some(cmd)) => {
+                    let settings = zone_settings::read_spec(cmd.value_of("file").unwrap())?;
+                    zone_settings::apply(&api, &settings, yes)
+                }
+                _ => Err(unknown_command())
+            },
+            _ => Err(unknown_command())
         },
         ("cache", Some(sub_cmd)) => match sub_cmd.subcommand() {
             ("purge", Some(cmd)) => {
-                let all = cmd.is_present("all");
-                let zone = resolve_zone(&api, cmd);
+                let values = |name| cmd.values_of(name).map(|v| v.map(String::from).collect()).unwrap_or_default();
+                let (kind, items): (_, Vec<String>) = if cmd.is_present("tag") {
+                    (cache::PurgeKind::Tags, values("tag"))
+                } else if cmd.is_present("prefix") {
+                    (cache::PurgeKind::Prefixes, values("prefix"))
+                } else if cmd.is_present("host") {
+                    (cache::PurgeKind::Hosts, values("host"))
+                } else {
+                    let mut urls = values("url");
+                    if let Some(file) = cmd.value_of("file") {
+                        urls.extend(cache::read_urls(file)?);
+                    }
+                    (cache::PurgeKind::Files, urls)
+                };
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+
+                if cmd.is_present("all") {
+                    cache::purge_all(&api, zone.as_str(), yes)
+                } else {
+                    cache::purge(&api, zone.as_str(), kind, items)
+                }
+            }
+            _ => Err(unknown_command())
+        },
+        ("pagerules", Some(sub_cmd)) => match sub_cmd.subcommand() {
+            ("list", Some(cmd)) => {
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                pagerules::list(&api, &zone, page_rule_status(cmd), format)
+            }
+            ("get", Some(cmd)) => {
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                pagerules::get(&api, &zone, cmd.value_of("id").unwrap(), format)
+            }
+            ("create", Some(cmd)) => {
+                let rules = match cmd.value_of("file") {
+                    Some(file) => read_page_rules(file)?,
+                    None => {
+                        let url = cmd.value_of("url").ok_or_else(|| {
+                            Error::Validation("Either --file or --url and --action must be provided".to_string())
+                        })?;
+                        let actions = page_rule_actions(cmd, "action")?;
+                        if actions.is_empty() {
+                            return Err(Error::Validation("At least one --action must be provided".to_string()));
+                        }
+                        vec![PageRuleParams {
+                            targets: vec![Target::url(url)],
+                            actions,
+                            priority: cmd.value_of("priority").map(|p| p.parse().unwrap()),
+                            status: page_rule_status(cmd),
+                        }]
+                    }
+                };
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
 
-                if all {
-                    cache::purge_all(&api, zone.as_str());
+                pagerules::create(&api, &zone, rules, format)
+            }
+            ("update", Some(cmd)) => {
+                let spec = match cmd.value_of("file") {
+                    Some(file) => {
+                        let mut rules = read_page_rules(file)?;
+                        if rules.len() != 1 {
+                            return Err(Error::Validation("The spec file must contain a single rule".to_string()));
+                        }
+                        Some(rules.remove(0))
+                    }
+                    None => None,
+                };
+                let input = pagerules::UpdateParams {
+                    spec,
+                    url: cmd.value_of("url"),
+                    actions: page_rule_actions(cmd, "action")?,
+                    remove_actions: cmd.values_of("remove-action").map(|v| v.collect()).unwrap_or_default(),
+                    priority: cmd.value_of("priority").map(|p| p.parse().unwrap()),
+                    status: page_rule_status(cmd),
+                };
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+
+                pagerules::update(&api, &zone, cmd.value_of("id").unwrap(), input, format)
+            }
+            ("delete", Some(cmd)) => {
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                pagerules::delete(&api, &zone, cmd.values_of("id").unwrap().collect(), yes)
+            }
+            ("reorder", Some(cmd)) => {
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                pagerules::reorder(&api, &zone, cmd.values_of("id").unwrap().collect())
+            }
+            _ => Err(unknown_command())
+        },
+        ("firewall", Some(sub_cmd)) => match sub_cmd.subcommand() {
+            ("rules", Some(rules_cmd)) => {
+                let (action, cmd) = match rules_cmd.subcommand() {
+                    (action, Some(cmd)) => (action, cmd),
+                    _ => return Err(unknown_command()),
+                };
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                let ids = || cmd.values_of("id").unwrap().collect();
+                let priority = cmd.value_of("priority").map(|p| p.parse().unwrap());
+
+                match action {
+                    "list" => firewall::list_rules(&api, &zone, &page_options(cmd), format),
+                    "create" => firewall::create_rule(&api, &zone, firewall::RuleParams {
+                        expression: cmd.value_of("expression").unwrap(),
+                        action: cmd.value_of("action").unwrap(),
+                        description: cmd.value_of("description"),
+                        priority,
+                        paused: cmd.is_present("paused"),
+                    }, format),
+                    "update" => firewall::update_rule(&api, &zone, cmd.value_of("id").unwrap(), firewall::UpdateRuleParams {
+                        expression: cmd.value_of("expression"),
+                        action: cmd.value_of("action"),
+                        description: cmd.value_of("description"),
+                        priority,
+                    }, format),
+                    "pause" => firewall::set_rules_paused(&api, &zone, ids(), true),
+                    "unpause" => firewall::set_rules_paused(&api, &zone, ids(), false),
+                    "delete" => firewall::delete_rules(&api, &zone, ids(), yes),
+                    "reorder" => firewall::reorder_rules(&api, &zone, ids()),
+                    _ => Err(unknown_command())
+                }
+            }
+            ("filters", Some(filters_cmd)) => {
+                let (action, cmd) = match filters_cmd.subcommand() {
+                    (action, Some(cmd)) => (action, cmd),
+                    _ => return Err(unknown_command()),
+                };
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+
+                match action {
+                    "list" => firewall::list_filters(&api, &zone, &page_options(cmd), format),
+                    "create" => firewall::create_filter(&api, &zone, cmd.value_of("expression").unwrap(), cmd.value_of("description"), format),
+                    "update" => firewall::update_filter(&api, &zone, cmd.value_of("id").unwrap(), cmd.value_of("expression"), cmd.value_of("description"), format),
+                    "delete" => firewall::delete_filters(&api, &zone, cmd.values_of("id").unwrap().collect(), yes),
+                    _ => Err(unknown_command())
+                }
+            }
+            ("access-rules", Some(access_cmd)) => {
+                let (action, cmd) = match access_cmd.subcommand() {
+                    (action, Some(cmd)) => (action, cmd),
+                    _ => return Err(unknown_command()),
+                };
+                let scope_id = match cmd.value_of("account") {
+                    Some(account) => accounts::resolve(&api, account)?,
+                    None => resolve_zone(&api, &zone_cache, cmd)?,
+                };
+                let scope = if cmd.is_present("account") {
+                    AccessRuleScope::Account(&scope_id)
                 } else {
-                    let urls = cmd.values_of("url").unwrap();
-                    cache::purge_url(&api, zone.as_str(), urls);
+                    AccessRuleScope::Zone(&scope_id)
+                };
+
+                match action {
+                    "list" => firewall::list_access_rules(&api, scope, cmd.value_of("mode"), &page_options(cmd), format),
+                    "create" => firewall::create_access_rule(&api, scope, firewall::AccessRuleParams {
+                        mode: cmd.value_of("mode").unwrap(),
+                        target: cmd.value_of("target"),
+                        value: cmd.value_of("value").unwrap(),
+                        notes: cmd.value_of("notes"),
+                    }, format),
+                    "update" => firewall::update_access_rule(&api, scope, cmd.value_of("id").unwrap(), cmd.value_of("mode"), cmd.value_of("notes"), format),
+                    "delete" => firewall::delete_access_rules(&api, scope, cmd.values_of("id").unwrap().collect(), yes),
+                    _ => Err(unknown_command())
+                }
+            }
+            _ => Err(unknown_command())
+        },
+        ("workers", Some(sub_cmd)) => match sub_cmd.subcommand() {
+            ("routes", Some(routes_cmd)) => match routes_cmd.subcommand() {
+                ("list", Some(cmd)) => {
+                    let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                    workers::list_routes(&api, &zone, format)
+                }
+                ("add", Some(cmd)) => {
+                    let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                    workers::add_route(&api, &zone, cmd.value_of("pattern").unwrap(), cmd.value_of("script"))
+                }
+                ("remove", Some(cmd)) => {
+                    let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                    workers::remove_routes(&api, &zone, cmd.values_of("route").unwrap().collect(), yes)
                 }
+                _ => Err(unknown_command())
+            },
+            ("list", Some(cmd)) => {
+                let account = accounts::resolve_or_default(&api, cmd.value_of("account"))?;
+                workers::list(&api, &account, format)
+            }
+            ("upload", Some(cmd)) => {
+                let params = workers::UploadParams {
+                    name: cmd.value_of("name").unwrap(),
+                    file: cmd.value_of("file").unwrap(),
+                    module: cmd.is_present("module"),
+                    bindings: workers_bindings(cmd)?,
+                    compatibility_date: cmd.value_of("compatibility-date"),
+                };
+                let account = accounts::resolve_or_default(&api, cmd.value_of("account"))?;
+                workers::upload(&api, &account, params, format)
+            }
+            ("download", Some(cmd)) => {
+                let account = accounts::resolve_or_default(&api, cmd.value_of("account"))?;
+                workers::download(&api, &account, cmd.value_of("name").unwrap(), cmd.value_of("file"))
+            }
+            ("delete", Some(cmd)) => {
+                let account = accounts::resolve_or_default(&api, cmd.value_of("account"))?;
+                workers::delete(&api, &account, cmd.values_of("name").unwrap().collect(), yes)
+            }
+            _ => Err(unknown_command())
+        },
+        ("kv", Some(sub_cmd)) => {
+            let (group, cmd) = match sub_cmd.subcommand() {
+                (group, Some(group_cmd)) => match group_cmd.subcommand() {
+                    (name, Some(cmd)) => ((group, name), cmd),
+                    _ => return Err(unknown_command()),
+                },
+                _ => return Err(unknown_command()),
+            };
+            let account = accounts::resolve_or_default(&api, cmd.value_of("account"))?;
+            let namespace = match cmd.value_of("namespace") {
+                Some(namespace) if group != ("namespaces", "delete") => kv::resolve(&api, &account, namespace)?,
+                _ => String::new(),
+            };
 
+            match group {
+                ("namespaces", "list") => kv::list_namespaces(&api, &account, &page_options(cmd), format),
+                ("namespaces", "create") => kv::create_namespace(&api, &account, cmd.value_of("title").unwrap(), format),
+                ("namespaces", "rename") => kv::rename_namespace(&api, &account, &namespace, cmd.value_of("title").unwrap()),
+                ("namespaces", "delete") => kv::delete_namespaces(&api, &account, cmd.values_of("namespace").unwrap().collect(), yes),
+                ("keys", "list") => {
+                    let limit = cmd.value_of("limit").map(|limit| limit.parse().unwrap());
+                    kv::list_keys(&api, &account, &namespace, cmd.value_of("prefix"), cmd.value_of("cursor"), limit, format)
+                }
+                ("keys", "get") => {
+                    kv::get_value(&api, &account, &namespace, cmd.value_of("key-name").unwrap(), cmd.value_of("file"))
+                }
+                ("keys", "put") => match cmd.value_of("bulk") {
+                    Some(path) => kv::put_bulk(&api, &account, &namespace, kv::read_bulk_pairs(path)?),
+                    None => {
+                        let value = match cmd.value_of("file") {
+                            Some(path) => kv::read_value(path)?,
+                            None => cmd.value_of("value").unwrap().as_bytes().to_vec(),
+                        };
+                        let key = cmd.value_of("key-name").unwrap();
+                        kv::put_value(&api, &account, &namespace, key, value, kv_expiration(cmd), kv_metadata(cmd)?)
+                    }
+                },
+                ("keys", "delete") => match cmd.value_of("bulk") {
+                    Some(path) => kv::delete_bulk(&api, &account, &namespace, kv::read_bulk_keys(path)?, yes),
+                    None => kv::delete_keys(&api, &account, &namespace, cmd.values_of("key-name").unwrap().collect(), yes),
+                },
+                _ => Err(unknown_command())
             }
-            _ => unimplemented!()
+        }
+        ("lb", Some(sub_cmd)) => match sub_cmd.subcommand() {
+            ("pools", Some(pools_cmd)) => {
+                let cmd = match pools_cmd.subcommand() {
+                    (_, Some(cmd)) => cmd,
+                    _ => return Err(unknown_command()),
+                };
+                let account = accounts::resolve_or_default(&api, cmd.value_of("account"))?;
+                let pool = || lb::resolve_pool(&api, &account, cmd.value_of("pool").unwrap());
+
+                match pools_cmd.subcommand_name() {
+                    Some("list") => lb::list_pools(&api, &account, format),
+                    Some("get") => lb::get_pool(&api, &account, &pool()?, format),
+                    Some("create") => lb::create_pool(&api, &account, lb::read_spec(cmd.value_of("file").unwrap())?, format),
+                    Some("update") => {
+                        let spec = lb::read_spec(cmd.value_of("file").unwrap())?;
+                        lb::update_pool(&api, &account, &pool()?, spec, format)
+                    }
+                    Some("delete") => lb::delete_pools(&api, &account, cmd.values_of("pool").unwrap().collect(), yes),
+                    Some("health") => lb::pool_health(&api, &account, &pool()?, format),
+                    Some("enable-origin") => lb::set_origins_enabled(&api, &account, &pool()?, cmd.values_of("origin").unwrap().collect(), true),
+                    Some("disable-origin") => lb::set_origins_enabled(&api, &account, &pool()?, cmd.values_of("origin").unwrap().collect(), false),
+                    _ => Err(unknown_command())
+                }
+            }
+            ("monitors", Some(monitors_cmd)) => {
+                let cmd = match monitors_cmd.subcommand() {
+                    (_, Some(cmd)) => cmd,
+                    _ => return Err(unknown_command()),
+                };
+                let account = accounts::resolve_or_default(&api, cmd.value_of("account"))?;
+
+                match monitors_cmd.subcommand_name() {
+                    Some("list") => lb::list_monitors(&api, &account, format),
+                    Some("get") => lb::get_monitor(&api, &account, cmd.value_of("id").unwrap(), format),
+                    Some("create") => lb::create_monitor(&api, &account, lb::read_spec(cmd.value_of("file").unwrap())?, format),
+                    Some("update") => {
+                        let spec = lb::read_spec(cmd.value_of("file").unwrap())?;
+                        lb::update_monitor(&api, &account, cmd.value_of("id").unwrap(), spec, format)
+                    }
+                    Some("delete") => lb::delete_monitors(&api, &account, cmd.values_of("id").unwrap().collect(), yes),
+                    _ => Err(unknown_command())
+                }
+            }
+            ("list", Some(cmd)) => {
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                lb::list(&api, &zone, format)
+            }
+            ("get", Some(cmd)) => {
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                let id = lb::resolve(&api, &zone, cmd.value_of("lb").unwrap())?;
+                lb::get(&api, &zone, &id, format)
+            }
+            ("create", Some(cmd)) => {
+                let spec = lb::read_spec(cmd.value_of("file").unwrap())?;
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                lb::create(&api, &zone, spec, format)
+            }
+            ("update", Some(cmd)) => {
+                let spec = lb::read_spec(cmd.value_of("file").unwrap())?;
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                let id = lb::resolve(&api, &zone, cmd.value_of("lb").unwrap())?;
+                lb::update(&api, &zone, &id, spec, format)
+            }
+            ("delete", Some(cmd)) => {
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                lb::delete(&api, &zone, cmd.values_of("lb").unwrap().collect(), yes)
+            }
+            _ => Err(unknown_command())
+        },
+        ("certs", Some(sub_cmd)) => match sub_cmd.subcommand() {
+            ("origin", Some(origin_cmd)) => match origin_cmd.subcommand() {
+                ("create", Some(cmd)) => {
+                    let params = certs::CreateParams {
+                        hostnames: cmd.values_of("hostnames").unwrap().collect(),
+                        validity: cmd.value_of("validity").unwrap().parse().unwrap(),
+                        key_type: match cmd.value_of("type") {
+                            Some("ecc") => certs::KeyType::Ecc,
+                            _ => certs::KeyType::Rsa,
+                        },
+                        key_file: cmd.value_of("key-file"),
+                        cert_file: cmd.value_of("cert-file"),
+                        force: cmd.is_present("force"),
+                    };
+                    certs::create(&api, params, format)
+                }
+                ("list", Some(cmd)) => {
+                    let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                    certs::list(&api, &zone, &page_options(cmd), format)
+                }
+                ("get", Some(cmd)) => certs::get(&api, cmd.value_of("id").unwrap(), cmd.value_of("cert-file"), format),
+                ("revoke", Some(cmd)) => certs::revoke(&api, cmd.values_of("id").unwrap().collect(), yes),
+                _ => Err(unknown_command())
+            },
+            _ => Err(unknown_command())
         },
         ("dns", Some(sub_cmd)) => match sub_cmd.subcommand() {
             ("list", Some(cmd)) => {
-                let zone = resolve_zone(&api, cmd);
-                let limit: u32 = cmd.value_of("limit").unwrap_or("50").parse().unwrap();
+                let regex = match cmd.value_of("regex") {
+                    Some(regex) => Some(Regex::new(regex)
+                        .map_err(|e| Error::Validation(format!("Invalid --regex: {}", e)))?),
+                    None => None,
+                };
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
                 let wide = cmd.is_present("wide");
                 let name = cmd.value_of("name");
 
+                let filters = dns::ListFilters {
+                    all: name,
+                    record_type: cmd.value_of("type"),
+                    content: cmd.value_of("content"),
+                    proxied: cmd.value_of("proxied").map(|val| matches!(val, "1" | "true")),
+                    regex,
+                    search_match: match cmd.value_of("match") {
+                        Some("any") => Some(SearchMatch::Any),
+                        Some(_) => Some(SearchMatch::All),
+                        None => None,
+                    },
+                    ..Default::default()
+                };
+                let sort = cmd.value_of("sort").map(|field| dns::ListSort {
+                    field: match field {
+                        "type" => dns::SortField::Type,
+                        "ttl" => dns::SortField::Ttl,
+                        "modified" => dns::SortField::Modified,
+                        _ => dns::SortField::Name,
+                    },
+                    descending: cmd.is_present("desc"),
+                });
+
                 let params = dns::ListParams {
                     zone_id: &zone,
-                    page: 1,
-                    limit,
+                    pagination: page_options(cmd),
                     wide,
-                    filters: dns::ListFilters { all: name },
+                    format,
+                    filters,
+                    sort,
                 };
                 dns::list(&api, params)
             }
             ("create", Some(cmd)) => {
-                let zone = resolve_zone(&api, cmd);
-                let content = cmd.value_of("content").unwrap();
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                let content = cmd.value_of("content");
                 let record_type = cmd.value_of("type").unwrap_or("A");
                 let proxied = cmd.is_present("proxied");
                 let name = cmd.value_of("name").unwrap();
@@ -371,23 +1893,19 @@ fn main() {
                     content,
                     record_type,
                     priority,
+                    fields: record_fields(cmd),
                 };
 
                 dns::create(&api, record)
             }
             ("update", Some(cmd)) => {
                 let id = cmd.value_of("id").unwrap();
-                let zone_id = resolve_zone(&api, cmd);
+                let zone_id = resolve_zone(&api, &zone_cache, cmd)?;
                 let content = cmd.value_of("content");
                 let name = cmd.value_of("name");
 
                 let ttl: Option<u32> = match cmd.value_of("ttl") {
-                    Some(val) => {
-                        match val.parse() {
-                            Ok(ttl) => Some(ttl),
-                            Err(_) => None
-                        }
-                    }
+                    Some(val) => val.parse().ok(),
                     None => None,
                 };
                 let proxied = match cmd.value_of("proxied") {
@@ -405,18 +1923,86 @@ fn main() {
                     ttl,
                     proxied,
                     content,
+                    priority: cmd.value_of("priority").map(|p| p.parse().unwrap()),
+                    fields: record_fields(cmd),
                 };
 
                 dns::update(&api, record)
             }
+            ("upsert", Some(cmd)) => {
+                let zone_id = resolve_zone(&api, &zone_cache, cmd)?;
+                let zone_name = resolve_zone_name(&api, cmd, &zone_id)?;
+                let proxied = cmd.value_of("proxied").map(|val| matches!(val, "1" | "true"));
+
+                let record = dns::UpsertParams {
+                    zone_id: &zone_id,
+                    zone_name: &zone_name,
+                    name: cmd.value_of("name").unwrap(),
+                    record_type: cmd.value_of("type").unwrap(),
+                    ttl: cmd.value_of("ttl").map(|t| t.parse().unwrap()),
+                    proxied,
+                    content: cmd.value_of("content"),
+                    priority: cmd.value_of("priority").map(|p| p.parse().unwrap()),
+                    fields: record_fields(cmd),
+                };
+
+                dns::upsert(&api, record)
+            }
+            ("export", Some(cmd)) => {
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                let zone_name = resolve_zone_name(&api, cmd, &zone)?;
+
+                dns::export(&api, &zone, &zone_name, cmd.value_of("file"))
+            }
+            ("import", Some(cmd)) => {
+                let file = cmd.value_of("file").unwrap();
+                let input = if file == "-" {
+                    let mut input = String::new();
+                    io::stdin().read_to_string(&mut input).map(|_| input)
+                } else {
+                    fs::read_to_string(file)
+                };
+                let input = input.map_err(|e| Error::Io(format!("Could not read {}: {}", file, e)))?;
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                let zone_name = resolve_zone_name(&api, cmd, &zone)?;
+
+                dns::import(&api, &zone, &zone_name, &input, format)
+            }
+            (action @ "plan", Some(cmd)) | (action @ "apply", Some(cmd)) => {
+                let state = dns_sync::read_desired_state(cmd.value_of("file").unwrap())
+                    .map_err(|e| Error::Validation(format!("Invalid desired-state file: {}", e)))?;
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
+                let zone_name = resolve_zone_name(&api, cmd, &zone)?;
+                let prune = cmd.is_present("prune");
+
+                if action == "plan" {
+                    dns_sync::plan(&api, &zone, &zone_name, &state, prune, format)
+                } else {
+                    dns_sync::apply(&api, &zone, &zone_name, &state, prune, format, yes)
+                }
+            }
             ("delete", Some(cmd)) => {
-                let id: Vec<_> = cmd.values_of("id").unwrap().collect();
-                let zone = resolve_zone(&api, cmd);
+                let zone = resolve_zone(&api, &zone_cache, cmd)?;
 
-                dns::delete(&api, &zone, id)
+                match cmd.values_of("id") {
+                    Some(ids) => dns::delete(&api, &zone, ids.collect(), yes),
+                    None => {
+                        let filters = dns::ListFilters {
+                            name: cmd.value_of("name"),
+                            record_type: cmd.value_of("type"),
+                            content_match: cmd.value_of("content-match"),
+                            ..Default::default()
+                        };
+                        dns::delete_matching(&api, &zone, filters, yes)
+                    }
+                }
             }
-            _ => {}
+            _ => Err(unknown_command())
         },
-        _ => unreachable!()
+        _ => Err(unknown_command())
     }
 }
+
+fn unknown_command() -> Error {
+    Error::Validation("Unknown command".to_string())
+}
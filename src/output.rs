@@ -0,0 +1,125 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tabular::Table;
+
+use crate::error::Error;
+
+pub const FORMATS: [&str; 4] = ["table", "json", "yaml", "csv"];
+
+/// Output format selected with the global `--output` flag
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Table,
+    Json,
+    Yaml,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "yaml" => Ok(Format::Yaml),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("Unknown output format: {}", s))
+        }
+    }
+}
+
+// Nested objects become dotted columns and arrays are joined with ';' so every
+// struct fits in a single CSV row.
+fn flatten(prefix: &str, value: Value, row: &mut Map<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+                flatten(&key, value, row);
+            }
+        }
+        Value::Array(values) => {
+            let joined: Vec<String> = values.into_iter().map(|v| match v {
+                Value::String(s) => s,
+                other => other.to_string(),
+            }).collect();
+            row.insert(prefix.to_string(), Value::String(joined.join(";")));
+        }
+        other => {
+            row.insert(prefix.to_string(), other);
+        }
+    }
+}
+
+fn to_csv<T: Serialize>(items: &[T]) -> Result<String, failure::Error> {
+    let mut rows = vec![];
+    let mut headers: Vec<String> = vec![];
+
+    for item in items {
+        let mut row = Map::new();
+        flatten("", serde_json::to_value(item)?, &mut row);
+        for key in row.keys() {
+            if !headers.contains(key) {
+                headers.push(key.clone());
+            }
+        }
+        rows.push(row);
+    }
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(&headers)?;
+    for row in rows {
+        writer.write_record(headers.iter().map(|h| match row.get(h) {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+        }))?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+pub fn serialize<T: Serialize + ?Sized>(format: Format, value: &T) -> Result<String, failure::Error> {
+    match format {
+        Format::Yaml => Ok(serde_yaml::to_string(value)?),
+        _ => Ok(serde_json::to_string_pretty(value)?),
+    }
+}
+
+/// Prints a list of items in the requested format. `table` is only called for the table format.
+pub fn render<T, F>(format: Format, items: &[T], table: F) -> Result<(), Error>
+    where T: Serialize,
+          F: FnOnce(&[T]) -> Table {
+    let rendered = match format {
+        Format::Table => Ok(table(items).to_string()),
+        Format::Csv => to_csv(items),
+        _ => serialize(format, items).map(|s| format!("{}\n", s)),
+    };
+
+    match rendered {
+        Ok(output) => {
+            print!("{}", output);
+            Ok(())
+        }
+        Err(e) => Err(Error::Io(format!("Could not render output: {}", e)))
+    }
+}
+
+/// Prints a single item in a structured format. Callers print their own view for the table format.
+pub fn print<T: Serialize>(format: Format, item: &T) -> Result<(), Error> {
+    let rendered = match format {
+        Format::Csv => to_csv(std::slice::from_ref(item)),
+        _ => serialize(format, item).map(|s| format!("{}\n", s)),
+    };
+
+    match rendered {
+        Ok(output) => {
+            print!("{}", output);
+            Ok(())
+        }
+        Err(e) => Err(Error::Io(format!("Could not render output: {}", e)))
+    }
+}
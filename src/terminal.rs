@@ -1,3 +1,5 @@
+use std::io::{self, IsTerminal};
+
 use text_io::read;
 use colored::Colorize;
 
@@ -6,12 +8,17 @@ pub fn prompt(message: &str) -> String {
     read!("{}\n")
 }
 
+/// Whether someone can answer prompts
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal()
+}
+
 pub fn error(message: &str) {
-    println!("{}", message.bright_red());
+    eprintln!("{}", message.bright_red());
 }
 
 pub fn warn(message: &str) {
-    println!("{}", message.bright_yellow());
+    eprintln!("{}", message.bright_yellow());
 }
 
 pub fn info(message: &str) {
@@ -0,0 +1,141 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::{get_zone_cache_path, Context, GlobalCredential};
+
+#[derive(Default, Debug, Serialize, Deserialize)]
+struct CacheFile {
+    /// Zones by name, for each context as given by `context_key`
+    contexts: BTreeMap<String, BTreeMap<String, Entry>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    id: String,
+    /// Seconds since the UNIX epoch
+    cached_at: u64,
+}
+
+/// Zone identifiers by zone name, kept on disk between invocations so scripts calling cflare in a loop
+/// don't look up the same zone every time. Each config context has its own entries, which are
+/// no longer used once its API URL or credential changes.
+///
+/// The cache is best-effort: a file that can't be read or written is ignored.
+pub struct ZoneCache {
+    /// `None` when caching is disabled
+    path: Option<PathBuf>,
+    context: String,
+    ttl: Duration,
+    file: RefCell<CacheFile>,
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+impl ZoneCache {
+    /// Loads the cache of a context from `~/.cflare`, for requests sent to `api_url`
+    pub fn load(context: &Context, api_url: Option<&str>, ttl: Duration) -> Self {
+        let path = get_zone_cache_path().ok();
+        let file = path.as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        ZoneCache { path, context: context_key(context, api_url), ttl, file: RefCell::new(file) }
+    }
+
+    /// A cache that never remembers anything, e.g. for `--no-cache`
+    pub fn disabled() -> Self {
+        ZoneCache { path: None, context: String::new(), ttl: Duration::from_secs(0), file: RefCell::default() }
+    }
+
+    /// Identifier of the zone, unless it isn't cached or expired
+    pub fn get(&self, name: &str) -> Option<String> {
+        // Nothing is cached when disabled
+        self.path.as_ref()?;
+
+        let file = self.file.borrow();
+        let entry = file.contexts.get(&self.context)?.get(&name.to_lowercase())?;
+        if now().saturating_sub(entry.cached_at) >= self.ttl.as_secs() {
+            return None;
+        }
+        Some(entry.id.clone())
+    }
+
+    pub fn insert(&self, name: &str, id: &str) {
+        if self.path.is_none() {
+            return;
+        }
+
+        let entry = Entry { id: id.to_string(), cached_at: now() };
+        let mut file = self.file.borrow_mut();
+        file.contexts
+            .entry(self.context.clone())
+            .or_default()
+            .insert(name.to_lowercase(), entry);
+
+        // Drop what expired, including the entries of contexts that changed since
+        let ttl = self.ttl.as_secs();
+        for zones in file.contexts.values_mut() {
+            zones.retain(|_, entry| now().saturating_sub(entry.cached_at) < ttl);
+        }
+        file.contexts.retain(|_, zones| !zones.is_empty());
+        drop(file);
+        self.save();
+    }
+
+    /// Forgets the names of a zone, e.g. once it has been deleted
+    pub fn remove_id(&self, id: &str) {
+        if self.path.is_none() {
+            return;
+        }
+
+        if let Some(zones) = self.file.borrow_mut().contexts.get_mut(&self.context) {
+            zones.retain(|_, entry| entry.id != id);
+        }
+        self.save();
+    }
+
+    fn save(&self) {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return,
+        };
+        let content = match serde_json::to_string(&*self.file.borrow()) {
+            Ok(content) => content,
+            Err(_) => return,
+        };
+
+        // A failed write only means looking the zone up again next time
+        let _ = fs::create_dir_all(path.parent().unwrap()).and_then(|_| fs::write(path, content));
+    }
+}
+
+// Entries are kept apart for each context name, API URL and credential. Credentials are only
+// stored as a fingerprint.
+fn context_key(context: &Context, api_url: Option<&str>) -> String {
+    let credential = match &context.credential {
+        GlobalCredential::Token { api_token } => format!("token:{}", api_token),
+        GlobalCredential::GlobalKey { email, api_key } => format!("key:{}:{}", email, api_key),
+    };
+    let fingerprint: String = openssl::sha::sha256(credential.as_bytes())[..8].iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    format!("{} {} {}", context.name, api_url.unwrap_or("production"), fingerprint)
+}
+
+/// Removes the cache file of every context. Returns whether there was one.
+pub fn clear() -> Result<bool, failure::Error> {
+    let path = get_zone_cache_path()?;
+    if !path.exists() {
+        return Ok(false);
+    }
+
+    fs::remove_file(path)?;
+    Ok(true)
+}
@@ -0,0 +1,761 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use crate::api::endpoints::dns::{
+    CaaData,
+    CertData,
+    DnsContent,
+    DnskeyData,
+    DnsRecord,
+    DsData,
+    LocData,
+    NaptrData,
+    SrvData,
+    SshfpData,
+    SvcbData,
+    TlsaData,
+    UriData,
+};
+
+/// Cloudflare's "automatic" TTL, written as-is so that imports keep it automatic
+pub const AUTO_TTL: u32 = 1;
+const PROXIED_TAG: &str = "cf-proxied:true";
+const MAX_STRING_LEN: usize = 255;
+
+/// A resource record read from an RFC 1035 zone file
+#[derive(Debug)]
+pub struct Entry {
+    /// Fully qualified owner name, without the trailing dot
+    pub name: String,
+    pub ttl: Option<u32>,
+    pub record_type: String,
+    /// RDATA fields, with quoted strings already unescaped unless they are part of a field, e.g. alpn="h3,h2"
+    pub data: Vec<String>,
+    /// `$ORIGIN` in effect for the record, used to resolve relative names in `data`
+    pub origin: String,
+    /// Whether the record was tagged with `cf_tags=cf-proxied:true`
+    pub proxied: bool,
+    /// Line where the record starts
+    pub line: usize,
+}
+
+/// Turns a possibly relative domain name into an absolute one, without the trailing dot
+pub fn absolute_name(name: &str, origin: &str) -> String {
+    if name == "@" {
+        origin.to_string()
+    } else if let Some(name) = name.strip_suffix('.') {
+        name.to_string()
+    } else if origin.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", name, origin)
+    }
+}
+
+// Parses TTLs such as `3600`, `1h` or `1h30m`
+fn parse_ttl(value: &str) -> Option<u32> {
+    if let Ok(ttl) = value.parse() {
+        return Some(ttl);
+    }
+
+    let mut total: u32 = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            'w' => 604_800,
+            _ => return None,
+        };
+        let n: u32 = number.parse().ok()?;
+        total = total.checked_add(n.checked_mul(unit)?)?;
+        number.clear();
+    }
+
+    if number.is_empty() { Some(total) } else { None }
+}
+
+// Splits a line into tokens. Returns the comment, if any, and updates the parentheses depth.
+fn tokenize(line: &str, line_no: usize, tokens: &mut Vec<String>, depth: &mut i32) -> Result<Option<String>, failure::Error> {
+    let mut chars = line.chars().peekable();
+    let mut current = String::new();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => {
+                if !current.is_empty() {
+                    tokens.push(current);
+                }
+                return Ok(Some(chars.collect::<String>().trim().to_string()));
+            }
+            '"' => {
+                // Bytes, as `\DDD` escapes may be parts of multi-byte UTF-8 characters
+                let mut quoted: Vec<u8> = vec![];
+                // As written, kept for quoted strings within a token
+                let mut raw = String::from('"');
+                let mut closed = false;
+                while let Some(c) = chars.next() {
+                    raw.push(c);
+                    match c {
+                        '\\' => {
+                            let digits: String = std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_digit()))
+                                .take(3)
+                                .collect();
+                            raw.push_str(&digits);
+                            if digits.is_empty() {
+                                if let Some(escaped) = chars.next() {
+                                    raw.push(escaped);
+                                    quoted.extend_from_slice(escaped.encode_utf8(&mut [0; 4]).as_bytes());
+                                }
+                            } else {
+                                let code: u8 = digits.parse()
+                                    .map_err(|_| failure::format_err!("line {}: invalid escape \\{}", line_no, digits))?;
+                                quoted.push(code);
+                            }
+                        }
+                        '"' => {
+                            closed = true;
+                            break;
+                        }
+                        _ => quoted.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+                    }
+                }
+                if !closed {
+                    failure::bail!("line {}: unterminated quoted string", line_no)
+                }
+                let quoted = String::from_utf8(quoted)
+                    .map_err(|_| failure::format_err!("line {}: quoted string is not valid UTF-8", line_no))?;
+                // A quoted string within a token keeps its quotes, e.g. SvcParams such as alpn="h3,h2",
+                // which the API stores as written
+                if current.is_empty() {
+                    tokens.push(quoted);
+                } else {
+                    current.push_str(&raw);
+                }
+            }
+            '(' | ')' => {
+                *depth += if c == '(' { 1 } else { -1 };
+                if *depth < 0 {
+                    failure::bail!("line {}: unbalanced parentheses", line_no)
+                }
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(None)
+}
+
+/// Parses a zone file. Relative names are resolved against `$ORIGIN`, or `origin` until one is set.
+pub fn parse(input: &str, origin: &str) -> Result<Vec<Entry>, failure::Error> {
+    let mut origin = origin.trim_end_matches('.').to_string();
+    let mut default_ttl: Option<u32> = None;
+    let mut last_name: Option<String> = None;
+    let mut entries = vec![];
+
+    let mut tokens: Vec<String> = vec![];
+    let mut comments: Vec<String> = vec![];
+    let mut depth = 0;
+    let mut inherits_name = false;
+    let mut start_line = 0;
+
+    for (i, line) in input.lines().enumerate() {
+        if depth == 0 {
+            tokens.clear();
+            comments.clear();
+            inherits_name = line.starts_with(' ') || line.starts_with('\t');
+            start_line = i + 1;
+        }
+
+        if let Some(comment) = tokenize(line, i + 1, &mut tokens, &mut depth)? {
+            comments.push(comment);
+        }
+        if depth > 0 || tokens.is_empty() {
+            continue;
+        }
+
+        match tokens[0].to_uppercase().as_str() {
+            "$ORIGIN" => {
+                let name = tokens.get(1).ok_or_else(|| failure::format_err!("line {}: $ORIGIN without a name", start_line))?;
+                origin = absolute_name(name, &origin);
+                continue;
+            }
+            "$TTL" => {
+                default_ttl = tokens.get(1).and_then(|ttl| parse_ttl(ttl));
+                if default_ttl.is_none() {
+                    failure::bail!("line {}: invalid $TTL", start_line)
+                }
+                continue;
+            }
+            directive if directive.starts_with('$') => {
+                failure::bail!("line {}: unsupported directive {}", start_line, directive)
+            }
+            _ => {}
+        }
+
+        let mut fields = tokens.drain(..);
+        let name = if inherits_name {
+            last_name.clone().ok_or_else(|| failure::format_err!("line {}: record without owner name", start_line))?
+        } else {
+            absolute_name(&fields.next().unwrap(), &origin)
+        };
+
+        // TTL and class are both optional and may come in any order
+        let mut ttl = None;
+        let mut record_type = None;
+        for field in fields.by_ref().take(3) {
+            if ttl.is_none() {
+                if let Some(value) = parse_ttl(&field) {
+                    ttl = Some(value);
+                    continue;
+                }
+            }
+            match field.to_uppercase().as_str() {
+                "IN" | "CH" | "HS" => continue,
+                t => {
+                    record_type = Some(t.to_string());
+                    break;
+                }
+            }
+        }
+        let record_type = record_type.ok_or_else(|| failure::format_err!("line {}: missing record type", start_line))?;
+
+        entries.push(Entry {
+            name: name.clone(),
+            ttl: ttl.or(default_ttl),
+            record_type,
+            data: fields.collect(),
+            origin: origin.clone(),
+            proxied: comments.iter().any(|c| c.contains(PROXIED_TAG)),
+            line: start_line,
+        });
+        last_name = Some(name);
+    }
+
+    if depth != 0 {
+        failure::bail!("line {}: unbalanced parentheses", start_line)
+    }
+
+    Ok(entries)
+}
+
+fn quote(value: &str) -> String {
+    // Character strings are limited to 255 bytes, longer values are split in several strings
+    let mut chunks: Vec<String> = vec![];
+    let mut chunk = String::new();
+    for c in value.chars() {
+        if chunk.len() + c.len_utf8() > MAX_STRING_LEN {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        chunk.push(c);
+    }
+    if !chunk.is_empty() || chunks.is_empty() {
+        chunks.push(chunk);
+    }
+
+    chunks.iter()
+        .map(|chunk| format!("\"{}\"", chunk.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn fqdn(name: &str) -> String {
+    format!("{}.", name.trim_end_matches('.'))
+}
+
+/// Returns the record type and its RDATA in zone file presentation format
+pub fn rdata(content: &DnsContent) -> (&'static str, String) {
+    let data = match content {
+        DnsContent::A { content } => content.to_string(),
+        DnsContent::AAAA { content } => content.to_string(),
+        DnsContent::CNAME { content } | DnsContent::NS { content } | DnsContent::PTR { content } => fqdn(content),
+        DnsContent::MX { content, priority } => format!("{} {}", priority, fqdn(content)),
+        DnsContent::TXT { content } | DnsContent::SPF { content } => quote(content),
+        DnsContent::OPENPGPKEY { content } => content.clone(),
+        DnsContent::SRV { data } => format!("{} {} {} {}", data.priority, data.weight, data.port, fqdn(&data.target)),
+        DnsContent::CAA { data } => format!("{} {} {}", data.flags, data.tag, quote(&data.value)),
+        DnsContent::TLSA { data } | DnsContent::SMIMEA { data } => {
+            format!("{} {} {} {}", data.usage, data.selector, data.matching_type, data.certificate)
+        }
+        DnsContent::LOC { data } => format!("{} {} {} {} {} {} {} {} {}m {}m {}m {}m",
+                                            data.lat_degrees, data.lat_minutes, data.lat_seconds, data.lat_direction,
+                                            data.long_degrees, data.long_minutes, data.long_seconds, data.long_direction,
+                                            data.altitude, data.size, data.precision_horz, data.precision_vert),
+        DnsContent::HTTPS { data } | DnsContent::SVCB { data } => {
+            format!("{} {} {}", data.priority, fqdn(&data.target), data.value).trim_end().to_string()
+        }
+        DnsContent::DS { data } => format!("{} {} {} {}", data.key_tag, data.algorithm, data.digest_type, data.digest),
+        DnsContent::DNSKEY { data } => format!("{} {} {} {}", data.flags, data.protocol, data.algorithm, data.public_key),
+        DnsContent::CERT { data } => format!("{} {} {} {}", data.cert_type, data.key_tag, data.algorithm, data.certificate),
+        DnsContent::URI { data, priority } => format!("{} {} {}", priority, data.weight, quote(&data.target)),
+        DnsContent::NAPTR { data } => format!("{} {} {} {} {} {}", data.order, data.preference, quote(&data.flags),
+                                              quote(&data.service), quote(&data.regex), fqdn(&data.replacement)),
+        DnsContent::SSHFP { data } => format!("{} {} {}", data.algorithm, data.fingerprint_type, data.fingerprint),
+    };
+
+    (content.record_type(), data)
+}
+
+/// Splits RDATA in presentation format into fields, unquoting character strings
+pub fn split_rdata(value: &str) -> Result<Vec<String>, failure::Error> {
+    let mut tokens = vec![];
+    let mut depth = 0;
+
+    tokenize(value, 1, &mut tokens, &mut depth)?;
+    if depth != 0 {
+        failure::bail!("unbalanced parentheses")
+    }
+    Ok(tokens)
+}
+
+struct Fields<'a> {
+    record_type: &'a str,
+    tokens: &'a [String],
+}
+
+impl<'a> Fields<'a> {
+    fn text(&self, index: usize, field: &str) -> Result<&'a str, String> {
+        self.tokens.get(index)
+            .map(|t| t.as_str())
+            .ok_or_else(|| format!("{} record is missing its {}", self.record_type, field))
+    }
+
+    fn number<T: FromStr>(&self, index: usize, field: &str) -> Result<T, String> {
+        self.text(index, field)?
+            .parse()
+            .map_err(|_| format!("Invalid {} {}: {}", self.record_type, field, self.tokens[index]))
+    }
+
+    // Base64 and hex blobs may be split in several fields
+    fn rest(&self, index: usize, field: &str) -> Result<String, String> {
+        self.text(index, field)?;
+        Ok(self.tokens[index..].concat())
+    }
+
+    fn hex(&self, index: usize, field: &str) -> Result<String, String> {
+        let value = self.rest(index, field)?;
+        if !value.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid {} {}: expected hexadecimal data", self.record_type, field));
+        }
+        Ok(value)
+    }
+
+    fn range<T: FromStr + PartialOrd + std::fmt::Display>(&self, index: usize, field: &str, min: T, max: T) -> Result<T, String> {
+        let value: T = self.number(index, field)?;
+        if value < min || value > max {
+            return Err(format!("Invalid {} {}: must be between {} and {}", self.record_type, field, min, max));
+        }
+        Ok(value)
+    }
+
+    fn exact(&self, count: usize) -> Result<(), String> {
+        if self.tokens.len() > count {
+            return Err(format!("Unexpected data in {} record: {}", self.record_type, self.tokens[count..].join(" ")));
+        }
+        Ok(())
+    }
+}
+
+fn hostname(name: &str) -> String {
+    if name == "." { name.to_string() } else { name.trim_end_matches('.').to_string() }
+}
+
+// Parses `d [m [s]] N|S d [m [s]] E|W alt[m] [size[m] [hp[m] [vp[m]]]]` (RFC 1876)
+fn parse_loc(fields: &Fields) -> Result<LocData, String> {
+    let tokens = fields.tokens;
+    let invalid = |field: &str| format!("Invalid LOC {}", field);
+    let meters = |value: &str, field: &str| value.trim_end_matches('m').parse::<f64>().map_err(|_| invalid(field));
+
+    let lat_end = tokens.iter().position(|t| t == "N" || t == "S").ok_or_else(|| invalid("latitude"))?;
+    let long_end = tokens.iter().position(|t| t == "E" || t == "W").ok_or_else(|| invalid("longitude"))?;
+    if lat_end == 0 || lat_end > 3 || long_end <= lat_end + 1 || long_end - lat_end > 4 {
+        return Err(invalid("coordinates"));
+    }
+
+    let coordinate = |parts: &[String], max_degrees: u8, field: &str| -> Result<(u8, u8, f64), String> {
+        let degrees: u8 = parts[0].parse().map_err(|_| invalid(field))?;
+        let minutes: u8 = parts.get(1).map(|m| m.parse()).unwrap_or(Ok(0)).map_err(|_| invalid(field))?;
+        let seconds: f64 = parts.get(2).map(|s| s.parse()).unwrap_or(Ok(0.0)).map_err(|_| invalid(field))?;
+        if degrees > max_degrees || minutes >= 60 || !(0.0..60.0).contains(&seconds) {
+            return Err(invalid(field));
+        }
+        Ok((degrees, minutes, seconds))
+    };
+    let (lat_degrees, lat_minutes, lat_seconds) = coordinate(&tokens[..lat_end], 90, "latitude")?;
+    let (long_degrees, long_minutes, long_seconds) = coordinate(&tokens[lat_end + 1..long_end], 180, "longitude")?;
+
+    let sizes = &tokens[long_end + 1..];
+    if sizes.is_empty() || sizes.len() > 4 {
+        return Err(invalid("altitude and precision"));
+    }
+    let size = |index: usize, default: f64, field: &str| sizes.get(index).map(|v| meters(v, field)).unwrap_or(Ok(default));
+
+    Ok(LocData {
+        lat_degrees,
+        lat_minutes,
+        lat_seconds,
+        lat_direction: tokens[lat_end].clone(),
+        long_degrees,
+        long_minutes,
+        long_seconds,
+        long_direction: tokens[long_end].clone(),
+        altitude: size(0, 0.0, "altitude")?,
+        size: size(1, 1.0, "size")?,
+        precision_horz: size(2, 10000.0, "horizontal precision")?,
+        precision_vert: size(3, 10.0, "vertical precision")?,
+    })
+}
+
+/// Builds and validates record content from RDATA fields in presentation format. For record types
+/// that start with a priority, `priority` is used when the fields leave it out.
+pub fn parse_rdata(record_type: &str, tokens: &[String], priority: Option<u16>) -> Result<DnsContent, String> {
+    let with_priority;
+    let tokens = match (record_type, priority) {
+        ("MX", Some(p)) | ("SRV", Some(p)) | ("URI", Some(p)) | ("HTTPS", Some(p)) | ("SVCB", Some(p))
+        if tokens.first().map(|t| t.parse::<u16>().is_err()).unwrap_or(true) => {
+            with_priority = [vec![p.to_string()], tokens.to_vec()].concat();
+            &with_priority
+        }
+        _ => tokens,
+    };
+    let f = Fields { record_type, tokens };
+
+    let content = match record_type {
+        "A" => {
+            f.exact(1)?;
+            DnsContent::A { content: Ipv4Addr::from_str(f.text(0, "address")?).map_err(|_| "Invalid IPv4 address")? }
+        }
+        "AAAA" => {
+            f.exact(1)?;
+            DnsContent::AAAA { content: Ipv6Addr::from_str(f.text(0, "address")?).map_err(|_| "Invalid IPv6 address")? }
+        }
+        "CNAME" | "NS" | "PTR" => {
+            f.exact(1)?;
+            let content = hostname(f.text(0, "target")?);
+            match record_type {
+                "CNAME" => DnsContent::CNAME { content },
+                "NS" => DnsContent::NS { content },
+                _ => DnsContent::PTR { content },
+            }
+        }
+        "MX" => {
+            f.exact(2)?;
+            DnsContent::MX { priority: f.number(0, "priority")?, content: hostname(f.text(1, "mail server")?) }
+        }
+        "TXT" => DnsContent::TXT { content: tokens.concat() },
+        "SPF" => DnsContent::SPF { content: tokens.concat() },
+        "OPENPGPKEY" => DnsContent::OPENPGPKEY { content: f.rest(0, "public key")? },
+        "SRV" => {
+            f.exact(4)?;
+            DnsContent::SRV {
+                data: SrvData {
+                    priority: f.number(0, "priority")?,
+                    weight: f.number(1, "weight")?,
+                    port: f.number(2, "port")?,
+                    target: hostname(f.text(3, "target")?),
+                    service: None,
+                    proto: None,
+                }
+            }
+        }
+        "CAA" => {
+            let tag = f.text(1, "tag")?;
+            if !["issue", "issuewild", "iodef"].contains(&tag) {
+                return Err(format!("Invalid CAA tag: {}. Expected issue, issuewild or iodef", tag));
+            }
+            let flags: u8 = f.number(0, "flags")?;
+            if flags != 0 && flags != 128 {
+                return Err("Invalid CAA flags: must be 0 or 128".to_string());
+            }
+            f.text(2, "value")?;
+            DnsContent::CAA { data: CaaData { flags, tag: tag.to_string(), value: tokens[2..].join(" ") } }
+        }
+        "TLSA" | "SMIMEA" => {
+            let data = TlsaData {
+                usage: f.range(0, "usage", 0, 3)?,
+                selector: f.range(1, "selector", 0, 1)?,
+                matching_type: f.range(2, "matching type", 0, 2)?,
+                certificate: f.hex(3, "certificate")?,
+            };
+            if record_type == "TLSA" { DnsContent::TLSA { data } } else { DnsContent::SMIMEA { data } }
+        }
+        "LOC" => DnsContent::LOC { data: parse_loc(&f)? },
+        "HTTPS" | "SVCB" => {
+            let data = SvcbData {
+                priority: f.number(0, "priority")?,
+                target: hostname(f.text(1, "target")?),
+                value: tokens.get(2..).map(|v| v.join(" ")).unwrap_or_default(),
+            };
+            if record_type == "HTTPS" { DnsContent::HTTPS { data } } else { DnsContent::SVCB { data } }
+        }
+        "DS" => DnsContent::DS {
+            data: DsData {
+                key_tag: f.number(0, "key tag")?,
+                algorithm: f.number(1, "algorithm")?,
+                digest_type: f.range(2, "digest type", 1, 4)?,
+                digest: f.hex(3, "digest")?,
+            }
+        },
+        "DNSKEY" => DnsContent::DNSKEY {
+            data: DnskeyData {
+                flags: f.number(0, "flags")?,
+                protocol: f.range(1, "protocol", 3, 3)?,
+                algorithm: f.number(2, "algorithm")?,
+                public_key: f.rest(3, "public key")?,
+            }
+        },
+        "CERT" => DnsContent::CERT {
+            data: CertData {
+                cert_type: f.number(0, "type")?,
+                key_tag: f.number(1, "key tag")?,
+                algorithm: f.number(2, "algorithm")?,
+                certificate: f.rest(3, "certificate")?,
+            }
+        },
+        "URI" => {
+            f.exact(3)?;
+            DnsContent::URI {
+                priority: f.number(0, "priority")?,
+                data: UriData { weight: f.number(1, "weight")?, target: f.text(2, "target")?.to_string() },
+            }
+        }
+        "NAPTR" => {
+            f.exact(6)?;
+            let flags = f.text(2, "flags")?;
+            if !flags.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(format!("Invalid NAPTR flags: {}", flags));
+            }
+            DnsContent::NAPTR {
+                data: NaptrData {
+                    order: f.number(0, "order")?,
+                    preference: f.number(1, "preference")?,
+                    flags: flags.to_string(),
+                    service: f.text(3, "service")?.to_string(),
+                    regex: f.text(4, "regex")?.to_string(),
+                    replacement: hostname(f.text(5, "replacement")?),
+                }
+            }
+        }
+        "SSHFP" => {
+            let fingerprint_type = f.range(1, "fingerprint type", 1, 2)?;
+            let fingerprint = f.hex(2, "fingerprint")?;
+            let expected_len = if fingerprint_type == 1 { 40 } else { 64 };
+            if fingerprint.len() != expected_len {
+                return Err(format!("Invalid SSHFP fingerprint: expected {} hexadecimal characters", expected_len));
+            }
+            DnsContent::SSHFP { data: SshfpData { algorithm: f.range(0, "algorithm", 1, 6)?, fingerprint_type, fingerprint } }
+        }
+        _ => return Err(format!("Record type not supported: {}", record_type)),
+    };
+
+    Ok(content)
+}
+
+/// Builds record content from a `--content` value. Values of single-string types are used
+/// as they are, others are read in presentation format, e.g. `0 issue "letsencrypt.org"` for CAA.
+pub fn parse_content(record_type: &str, value: &str, priority: Option<u16>) -> Result<DnsContent, String> {
+    match record_type {
+        "TXT" | "SPF" if !value.starts_with('"') => parse_rdata(record_type, &[value.to_string()], priority),
+        _ => {
+            let tokens = split_rdata(value).map_err(|e| format!("Invalid content: {}", e))?;
+            parse_rdata(record_type, &tokens, priority)
+        }
+    }
+}
+
+/// Renders records as a BIND zone file. Automatic TTLs are kept as `1` and proxied records are
+/// tagged with a `cf_tags` comment, the same way Cloudflare's dashboard export does.
+pub fn render(zone_name: &str, records: &[DnsRecord]) -> String {
+    let mut lines: Vec<(&str, String)> = records.iter().map(|record| {
+        let (record_type, data) = rdata(&record.content);
+        let mut line = format!("{}\t{}\tIN\t{}\t{}", fqdn(&record.name), record.ttl, record_type, data);
+        if record.proxied {
+            line.push_str(&format!(" ; cf_tags={}", PROXIED_TAG));
+        }
+        (record_type, line)
+    }).collect();
+    lines.sort();
+
+    let mut output = format!(";;\n;; Domain:     {}\n;; Exported:   {}\n;;\n\n$ORIGIN {}\n",
+                             zone_name, chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"), fqdn(zone_name));
+
+    let mut current_type = "";
+    for (record_type, line) in lines {
+        if record_type != current_type {
+            output.push_str(&format!("\n;; {} Records\n", record_type));
+            current_type = record_type;
+        }
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::endpoints::dns::Meta;
+
+    fn record(name: &str, ttl: u32, proxied: bool, content: DnsContent) -> DnsRecord {
+        DnsRecord {
+            id: String::new(),
+            name: name.to_string(),
+            content,
+            ttl,
+            proxied,
+            proxiable: true,
+            locked: false,
+            zone_id: String::new(),
+            zone_name: "example.com".to_string(),
+            created_on: chrono::Utc::now(),
+            modified_on: chrono::Utc::now(),
+            meta: Meta { auto_added: false },
+        }
+    }
+
+    #[test]
+    fn each_record_type_round_trips() {
+        let sshfp = format!("4 2 {}", "ab".repeat(32));
+        let cases = [
+            ("A", "192.0.2.1"),
+            ("AAAA", "2001:db8::1"),
+            ("CNAME", "target.example.com."),
+            ("NS", "ns1.example.com."),
+            ("PTR", "host.example.com."),
+            ("MX", "10 mail.example.com."),
+            ("TXT", r#""v=spf1 -all""#),
+            ("SPF", r#""v=spf1 -all""#),
+            ("OPENPGPKEY", "mQENBFVHm5sBCAD"),
+            ("SRV", "10 5 5060 sip.example.com."),
+            ("CAA", r#"0 issue "letsencrypt.org""#),
+            ("TLSA", "3 1 1 abcdef0123"),
+            ("SMIMEA", "3 1 1 abcdef0123"),
+            ("LOC", "52 22 23.5 N 4 53 32 E -2m 1m 10000m 10m"),
+            ("HTTPS", r#"1 . alpn="h3,h2""#),
+            ("SVCB", "1 svc.example.com. port=8443"),
+            ("DS", "2371 13 2 1f987cc6583e92df0890718c42"),
+            ("DNSKEY", "257 3 13 mdsswUyr3DPW132mOi8V9xESWE8jTo0d"),
+            ("CERT", "1 12345 8 MIIBIjANBgkqhkiG"),
+            ("URI", r#"10 1 "https://example.com/""#),
+            ("NAPTR", r#"100 10 "U" "E2U+sip" "!^.*$!sip:info@example.com!" ."#),
+            ("SSHFP", &sshfp),
+        ];
+
+        for (record_type, data) in cases.iter() {
+            let content = parse_content(record_type, data, None)
+                .unwrap_or_else(|e| panic!("{} {}: {}", record_type, data, e));
+            assert_eq!(rdata(&content), (*record_type, data.to_string()));
+        }
+    }
+
+    #[test]
+    fn directives_and_relative_names() {
+        let input = "$ORIGIN example.com.\n\
+                     $TTL 1h\n\
+                     @ IN SOA ns1 hostmaster (\n\
+                     \t1 7200 3600 ; serial, refresh, retry\n\
+                     \t1209600 3600 )\n\
+                     www 300 IN A 192.0.2.1 ; cf_tags=cf-proxied:true\n\
+                     \tIN AAAA 2001:db8::1\n\
+                     mail IN MX 10 mx\n\
+                     $ORIGIN sub\n\
+                     api IN CNAME www.example.com.\n";
+
+        let entries = parse(input, "ignored.com").unwrap();
+        assert_eq!(entries.len(), 5);
+
+        assert_eq!(entries[0].name, "example.com");
+        assert_eq!(entries[0].record_type, "SOA");
+        assert_eq!(entries[0].ttl, Some(3600));
+        assert_eq!(entries[0].data, vec!["ns1", "hostmaster", "1", "7200", "3600", "1209600", "3600"]);
+        assert_eq!(entries[0].line, 3);
+
+        assert_eq!(entries[1].name, "www.example.com");
+        assert_eq!(entries[1].ttl, Some(300));
+        assert!(entries[1].proxied);
+
+        assert_eq!(entries[2].name, "www.example.com");
+        assert_eq!(entries[2].record_type, "AAAA");
+        assert_eq!(entries[2].ttl, Some(3600));
+        assert!(!entries[2].proxied);
+
+        assert_eq!(entries[3].data, vec!["10", "mx"]);
+        assert_eq!(entries[3].origin, "example.com");
+
+        assert_eq!(entries[4].name, "api.sub.example.com");
+        assert_eq!(entries[4].origin, "sub.example.com");
+    }
+
+    #[test]
+    fn default_origin_until_set() {
+        let entries = parse("@ 3600 IN TXT \"apex\"\nwww 3600 IN A 192.0.2.1\n", "example.com.").unwrap();
+        assert_eq!(entries[0].name, "example.com");
+        assert_eq!(entries[1].name, "www.example.com");
+    }
+
+    #[test]
+    fn quoted_strings_and_escapes() {
+        let entries = parse(r#"txt.example.com. IN TXT "a \"quoted\" \\ value; not a comment" "caf\195\169" ; comment"#, "").unwrap();
+        assert_eq!(entries[0].data, vec![r#"a "quoted" \ value; not a comment"#, "café"]);
+
+        assert!(parse(r#"txt.example.com. IN TXT "\255""#, "").is_err());
+        assert!(parse(r#"txt.example.com. IN TXT "unterminated"#, "").is_err());
+        assert!(parse("www.example.com. IN A ( 192.0.2.1", "").is_err());
+    }
+
+    #[test]
+    fn render_then_parse() {
+        let long_text = "x".repeat(300);
+        let records = vec![
+            record("example.com", 1, false, DnsContent::TXT { content: "say \"hi\" \\ café".to_string() }),
+            record("long.example.com", 3600, false, DnsContent::TXT { content: long_text.clone() }),
+            record("www.example.com", 1, true, DnsContent::A { content: "192.0.2.1".parse().unwrap() }),
+            record("example.com", 300, false, DnsContent::MX { content: "mail.example.com".to_string(), priority: 10 }),
+        ];
+
+        let zone = render("example.com", &records);
+        let entries = parse(&zone, "").unwrap();
+        assert_eq!(entries.len(), records.len());
+
+        for record in &records {
+            let (record_type, _) = rdata(&record.content);
+            let entry = entries.iter()
+                .find(|e| e.name == record.name && e.record_type == record_type)
+                .unwrap_or_else(|| panic!("{} {} not in\n{}", record.name, record_type, zone));
+            assert_eq!(entry.ttl, Some(record.ttl));
+            assert_eq!(entry.proxied, record.proxied);
+            if record.name == "long.example.com" {
+                assert_eq!(entry.data.len(), 2);
+            }
+            assert_eq!(parse_rdata(&entry.record_type, &entry.data, None).unwrap(), record.content);
+        }
+    }
+
+    #[test]
+    fn svc_params_keep_their_quotes() {
+        let tokens = split_rdata(r#"1 . alpn="h3,h2" ipv4hint=192.0.2.1"#).unwrap();
+        assert_eq!(tokens, vec!["1", ".", r#"alpn="h3,h2""#, "ipv4hint=192.0.2.1"]);
+
+        let content = parse_content("HTTPS", r#"1 . alpn="h3,h2""#, None).unwrap();
+        assert_eq!(rdata(&content).1, r#"1 . alpn="h3,h2""#);
+    }
+}
@@ -0,0 +1,1198 @@
+//! Runs cflare against a local mock of the Cloudflare API
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::{cflare, stderr, stdout, temp_home, MockServer, Route};
+
+const ZONE_ID: &str = "023e105f4ecef8ad9ca31a8372d0c353";
+const TOKEN: &str = "test-token";
+
+fn dns_records_path() -> String {
+    format!("/client/v4/zones/{}/dns_records", ZONE_ID)
+}
+
+#[test]
+fn zones_list() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "--api-url", &server.api_url(), "zones", "list"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains(ZONE_ID));
+    assert!(stdout.contains("example.com"));
+    assert_eq!(server.count("GET", "/client/v4/zones"), 1);
+}
+
+#[test]
+fn zones_list_json() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "--api-url", &server.api_url(), "-o", "json", "zones", "list"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let zones: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(zones[0]["id"], ZONE_ID);
+    assert_eq!(zones[0]["name"], "example.com");
+}
+
+#[test]
+fn dns_list_resolves_zone_name() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+        Route::new("GET", &dns_records_path(), 200, "dns_records.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "--api-url", &server.api_url(), "dns", "list", "--zone", "example.com"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("www.example.com"));
+    assert!(stdout.contains("198.51.100.4"));
+    assert!(stdout.contains("v=spf1 -all"));
+
+    let lookup = &server.requests()[0];
+    assert_eq!(lookup.path, "/client/v4/zones");
+    assert!(lookup.query.as_deref().unwrap_or_default().contains("name=example.com"));
+}
+
+#[test]
+fn dns_create_sends_record() {
+    let server = MockServer::start(vec![
+        Route::new("POST", &dns_records_path(), 200, "dns_record.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "dns", "create", "www.example.com", "--zone-id", ZONE_ID,
+        "--type", "A", "--content", "198.51.100.4", "--ttl", "1", "--proxied",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("372e67954025e0ba6aaa6d586b9e0b59"));
+
+    let body: serde_json::Value = serde_json::from_str(&server.requests()[0].body).unwrap();
+    assert_eq!(body["name"], "www.example.com");
+    assert_eq!(body["type"], "A");
+    assert_eq!(body["content"], "198.51.100.4");
+    assert_eq!(body["proxied"], true);
+}
+
+#[test]
+fn dns_create_dry_run() {
+    let server = MockServer::start(vec![]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(), "--dry-run",
+        "dns", "create", "www.example.com", "--zone-id", ZONE_ID,
+        "--type", "A", "--content", "198.51.100.4", "--ttl", "1",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.starts_with(&format!("POST {}{}\n", server.api_url().replace("/client/v4", ""), dns_records_path())));
+    assert!(stdout.contains("\"content\": \"198.51.100.4\""));
+    assert!(!stdout.contains("created"));
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn dns_delete_dry_run_still_reads() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(), "--dry-run",
+        "dns", "delete", "--zone", "example.com", "372e67954025e0ba6aaa6d586b9e0b59", "9a7806061c88ada191ed06f989cc3dac",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert_eq!(stdout.matches("DELETE ").count(), 2);
+    assert!(!stdout.contains("deleted"));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn flags_without_command_print_usage() {
+    let home = temp_home();
+
+    let output = cflare(&home, &["--yes", "--dry-run"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("USAGE"), "{}", stderr(&output));
+}
+
+#[test]
+fn dns_delete_refused_without_terminal() {
+    let server = MockServer::start(vec![]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "dns", "delete", "--zone-id", ZONE_ID, "372e67954025e0ba6aaa6d586b9e0b59",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--yes"));
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn dns_delete_with_yes() {
+    let record_path = format!("{}/372e67954025e0ba6aaa6d586b9e0b59", dns_records_path());
+    let server = MockServer::start(vec![
+        Route::new("DELETE", &record_path, 200, "dns_record_deleted.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "dns", "delete", "--zone-id", ZONE_ID, "372e67954025e0ba6aaa6d586b9e0b59", "--yes",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("deleted"));
+    assert_eq!(server.count("DELETE", &record_path), 1);
+}
+
+#[test]
+fn unknown_zone_is_not_found() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "empty_list.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "--api-url", &server.api_url(), "dns", "list", "--zone", "missing.com"]);
+
+    assert_eq!(output.status.code(), Some(6));
+    assert!(stderr(&output).contains("missing.com"));
+}
+
+#[test]
+fn api_error_exit_code() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 403, "auth_error.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "--api-url", &server.api_url(), "zones", "list"]);
+
+    assert_eq!(output.status.code(), Some(4));
+    assert!(stderr(&output).contains("Authentication error"));
+}
+
+#[test]
+fn rate_limited_request_is_retried() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 429, "rate_limited.json")
+            .header("Retry-After", "0")
+            .times(1),
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "--api-url", &server.api_url(), "zones", "list"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("example.com"));
+    assert!(stderr(&output).contains("Rate limited"));
+    assert_eq!(server.count("GET", "/client/v4/zones"), 2);
+}
+
+#[test]
+fn rate_limited_request_gives_up() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 429, "rate_limited.json")
+            .header("Retry-After", "0"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "--api-url", &server.api_url(), "--retries", "2", "zones", "list"]);
+
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(server.count("GET", "/client/v4/zones"), 3);
+}
+
+#[test]
+fn sends_token() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "--api-url", &server.api_url(), "zones", "list"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let request = &server.requests()[0];
+    assert_eq!(request.header("authorization"), Some(format!("Bearer {}", TOKEN).as_str()));
+}
+
+#[test]
+fn invalid_api_url() {
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "--api-url", "ftp://localhost/client/v4", "zones", "list"]);
+
+    assert_eq!(output.status.code(), Some(3));
+}
+
+// Writes a config whose current context points at the mock server
+fn write_config(home: &std::path::Path, api_url: &str) {
+    write_config_with_token(home, api_url, TOKEN);
+}
+
+fn write_config_with_token(home: &std::path::Path, api_url: &str, token: &str) {
+    let dir = home.join(".cflare");
+    fs::create_dir_all(&dir).unwrap();
+    let config = format!(
+        "current_context = \"mock\"\n\n\
+         [[contexts]]\n\
+         name = \"mock\"\n\
+         api_url = \"{}\"\n\n\
+         [contexts.credential]\n\
+         api_token = \"{}\"\n",
+        api_url, token
+    );
+    fs::write(dir.join("default.toml"), config).unwrap();
+}
+
+#[test]
+fn api_url_from_context() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+    ]);
+    let home = temp_home();
+    write_config(&home, &server.api_url());
+
+    let output = cflare(&home, &["zones", "list"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("example.com"));
+    assert_eq!(server.count("GET", "/client/v4/zones"), 1);
+}
+
+#[test]
+fn zone_lookup_is_cached() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+        Route::new("GET", &dns_records_path(), 200, "dns_records.json"),
+    ]);
+    let home = temp_home();
+    write_config(&home, &server.api_url());
+
+    for _ in 0..2 {
+        let output = cflare(&home, &["dns", "list", "--zone", "example.com"]);
+        assert!(output.status.success(), "{}", stderr(&output));
+    }
+
+    assert_eq!(server.count("GET", "/client/v4/zones"), 1);
+    assert_eq!(server.count("GET", &dns_records_path()), 2);
+
+    let output = cflare(&home, &["--no-cache", "dns", "list", "--zone", "example.com"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(server.count("GET", "/client/v4/zones"), 2);
+}
+
+#[test]
+fn dns_delete_matching_filters() {
+    let record_path = format!("{}/9a7806061c88ada191ed06f989cc3dac", dns_records_path());
+    let server = MockServer::start(vec![
+        Route::new("GET", &dns_records_path(), 200, "dns_records.json"),
+        Route::new("DELETE", &record_path, 200, "dns_record_deleted.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "dns", "delete", "--zone-id", ZONE_ID, "--type", "TXT", "--content-match", "v=spf1*", "--yes",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(server.count("DELETE", &record_path), 1);
+    assert_eq!(server.requests().iter().filter(|r| r.method == "DELETE").count(), 1);
+
+    let list = &server.requests()[0];
+    assert!(list.query.as_deref().unwrap_or_default().contains("type=TXT"));
+}
+
+#[test]
+fn dns_upsert_updates_existing_record() {
+    let record_path = format!("{}/372e67954025e0ba6aaa6d586b9e0b59", dns_records_path());
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+        Route::new("GET", &dns_records_path(), 200, "dns_records_www.json"),
+        Route::new("PUT", &record_path, 200, "dns_record.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "dns", "upsert", "www", "--zone", "example.com", "--type", "A", "-c", "198.51.100.5",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("updated"));
+
+    let list = &server.requests()[1];
+    let query = list.query.as_deref().unwrap_or_default();
+    assert!(query.contains("name=www.example.com"));
+    assert!(query.contains("type=A"));
+
+    let update = server.requests().into_iter().find(|r| r.method == "PUT").unwrap();
+    let body: serde_json::Value = serde_json::from_str(&update.body).unwrap();
+    assert_eq!(body["content"], "198.51.100.5");
+    assert_eq!(body["proxied"], true);
+    assert_eq!(body["ttl"], 1);
+}
+
+#[test]
+fn dns_upsert_creates_missing_record() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+        Route::new("GET", &dns_records_path(), 200, "empty_list.json"),
+        Route::new("POST", &dns_records_path(), 200, "dns_record.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "dns", "upsert", "www.example.com", "--zone", "example.com", "--type", "A", "-c", "198.51.100.4",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("created"));
+    assert_eq!(server.count("POST", &dns_records_path()), 1);
+}
+
+#[test]
+fn dns_list_filters_and_sorts() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &dns_records_path(), 200, "dns_records.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "dns", "list", "--zone-id", ZONE_ID, "--type", "A", "--proxied", "true", "--match", "any",
+        "--sort", "ttl", "--desc",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let query = server.requests()[0].query.clone().unwrap_or_default();
+    for param in &["type=A", "proxied=true", "match=any", "order=ttl", "direction=desc"] {
+        assert!(query.contains(param), "{} not in {}", param, query);
+    }
+}
+
+#[test]
+fn dns_list_regex() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &dns_records_path(), 200, "dns_records.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "dns", "list", "--zone-id", ZONE_ID, "--regex", "^v=spf1", "--sort", "modified",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("v=spf1 -all"));
+    assert!(!stdout.contains("198.51.100.4"));
+    assert!(!server.requests()[0].query.clone().unwrap_or_default().contains("order="));
+}
+
+#[test]
+fn dns_list_invalid_regex() {
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "dns", "list", "--zone-id", ZONE_ID, "--regex", "("]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--regex"));
+}
+
+#[test]
+fn dns_list_regex_limit_spans_pages() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &dns_records_path(), 200, "dns_records_page1.json").times(1),
+        Route::new("GET", &dns_records_path(), 200, "dns_records_page2.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "dns", "list", "--zone-id", ZONE_ID, "--regex", "spf1", "--per-page", "1", "--limit", "1",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("v=spf1 -all"));
+    assert!(!stdout.contains("198.51.100.4"));
+    assert_eq!(server.count("GET", &dns_records_path()), 2);
+}
+
+#[test]
+fn dns_list_all_stops_at_total_pages() {
+    // The second page is full, only result_info says it is the last one
+    let server = MockServer::start(vec![
+        Route::new("GET", &dns_records_path(), 200, "dns_records_page1.json").times(1),
+        Route::new("GET", &dns_records_path(), 200, "dns_records_page2.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "dns", "list", "--zone-id", ZONE_ID, "--all", "--per-page", "1",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("v=spf1 -all"));
+    assert!(stdout.contains("198.51.100.4"));
+    assert_eq!(server.count("GET", &dns_records_path()), 2);
+    let queries: Vec<String> = server.requests().into_iter().filter_map(|r| r.query).collect();
+    assert!(queries[0].contains("page=1") && queries[1].contains("page=2"), "{:?}", queries);
+}
+
+#[test]
+fn dns_list_rejects_empty_pages() {
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "dns", "list", "--zone-id", ZONE_ID, "--per-page", "0"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("positive integer"), "{}", stderr(&output));
+}
+
+#[test]
+fn workers_upload_dry_run_hides_secrets() {
+    let server = MockServer::start(vec![]);
+    let home = temp_home();
+    let script = home.join("worker.js");
+    fs::write(&script, "addEventListener('fetch', () => {})").unwrap();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(), "--dry-run",
+        "workers", "upload", "--account", "01a7362d577a6c3019a474fd6f485823", "hello",
+        "-f", script.to_str().unwrap(), "--secret", "API_KEY=hunter2", "--var", "MODE=test",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let printed = format!("{}{}", stdout(&output), stderr(&output));
+    assert!(printed.contains("API_KEY"));
+    assert!(printed.contains("MODE"));
+    assert!(!printed.contains("hunter2"));
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn dns_plan_wildcard_ownership_record() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+        Route::new("GET", &dns_records_path(), 200, "empty_list.json"),
+    ]);
+    let home = temp_home();
+    let state = home.join("records.yaml");
+    fs::write(&state, "records:\n  - name: \"*\"\n    type: A\n    content: 198.51.100.4\n").unwrap();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "dns", "plan", "--zone", "example.com", "-f", state.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("*.example.com"));
+    assert!(stdout.contains("_cflare._wildcard.example.com"));
+    assert!(!stdout.contains("_cflare.*"));
+}
+
+#[test]
+fn dns_plan_destroys_owned_wildcard() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+        Route::new("GET", &dns_records_path(), 200, "dns_records_wildcard.json"),
+    ]);
+    let home = temp_home();
+    let state = home.join("records.yaml");
+    fs::write(&state, "records: []\n").unwrap();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(), "-o", "json",
+        "dns", "plan", "--zone", "example.com", "-f", state.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let changes: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let destroyed: Vec<&str> = changes.as_array().unwrap().iter()
+        .filter(|c| c["action"] == "destroy")
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert!(destroyed.contains(&"*.example.com"), "{:?}", changes);
+    assert!(destroyed.contains(&"_cflare._wildcard.example.com"), "{:?}", changes);
+}
+
+#[test]
+fn dns_plan_leaves_unowned_records_alone() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json").times(2),
+        Route::new("GET", &dns_records_path(), 200, "dns_records_www.json").times(2),
+    ]);
+    let home = temp_home();
+    let state = home.join("records.yaml");
+    fs::write(&state, "records:\n  - name: www\n    type: A\n    content: 198.51.100.5\n").unwrap();
+    let api_url = server.api_url();
+    let plan = |extra: &[&str]| {
+        let mut args = vec![
+            "--token", TOKEN, "--api-url", &api_url, "-o", "json",
+            "dns", "plan", "--zone", "example.com", "-f", state.to_str().unwrap(),
+        ];
+        args.extend_from_slice(extra);
+        let output = cflare(&home, &args);
+        assert!(output.status.success(), "{}", stderr(&output));
+        serde_json::from_str::<serde_json::Value>(&stdout(&output)).unwrap()
+    };
+    let actions = |changes: &serde_json::Value, name: &str| -> Vec<String> {
+        changes.as_array().unwrap().iter()
+            .filter(|c| c["name"] == name)
+            .map(|c| c["action"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let changes = plan(&[]);
+    assert_eq!(actions(&changes, "www.example.com"), vec!["add"], "{:?}", changes);
+
+    let changes = plan(&["--prune"]);
+    assert_eq!(actions(&changes, "www.example.com"), vec!["change"], "{:?}", changes);
+}
+
+#[test]
+fn dns_import_by_zone_id_into_empty_zone() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &format!("/client/v4/zones/{}", ZONE_ID), 200, "zone.json"),
+        Route::new("GET", &dns_records_path(), 200, "empty_list.json"),
+        Route::new("POST", &dns_records_path(), 200, "dns_record.json"),
+    ]);
+    let home = temp_home();
+    let zone_file = home.join("example.com.zone");
+    fs::write(&zone_file, "@ 3600 IN TXT \"v=spf1 -all\"\nwww 3600 IN A 198.51.100.4\n").unwrap();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "dns", "import", "--zone-id", ZONE_ID, "-f", zone_file.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let names: Vec<String> = server.requests().iter()
+        .filter(|r| r.method == "POST")
+        .map(|r| serde_json::from_str::<serde_json::Value>(&r.body).unwrap()["name"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names, vec!["example.com", "www.example.com"]);
+}
+
+#[test]
+fn dns_export_empty_zone_origin() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &format!("/client/v4/zones/{}", ZONE_ID), 200, "zone.json"),
+        Route::new("GET", &dns_records_path(), 200, "empty_list.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "--api-url", &server.api_url(), "dns", "export", "--zone-id", ZONE_ID]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("$ORIGIN example.com."));
+}
+
+#[test]
+fn zone_cache_follows_context_changes() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+        Route::new("GET", &dns_records_path(), 200, "dns_records.json"),
+    ]);
+    let home = temp_home();
+    write_config(&home, &server.api_url());
+
+    let output = cflare(&home, &["dns", "list", "--zone", "example.com"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(server.count("GET", "/client/v4/zones"), 1);
+
+    // Same context name, other account
+    write_config_with_token(&home, &server.api_url(), "other-token");
+    let output = cflare(&home, &["dns", "list", "--zone", "example.com"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(server.count("GET", "/client/v4/zones"), 2);
+
+    // Same context, other API
+    let other = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+        Route::new("GET", &dns_records_path(), 200, "dns_records.json"),
+    ]);
+    let output = cflare(&home, &["--api-url", &other.api_url(), "dns", "list", "--zone", "example.com"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(other.count("GET", "/client/v4/zones"), 1);
+}
+
+const RULE_ID: &str = "372e67954025e0ba6aaa6d586b9e0b60";
+const FILTER_ID: &str = "ff7a4d7e2b6a4c6f9f2d4cbf6f3a1c21";
+
+fn firewall_rules_path() -> String {
+    format!("/client/v4/zones/{}/firewall/rules", ZONE_ID)
+}
+
+fn update_rule_expression(server: &MockServer, home: &std::path::Path) -> std::process::Output {
+    cflare(home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "firewall", "rules", "update", RULE_ID, "--zone-id", ZONE_ID, "-x", "ip.src eq 198.51.100.5",
+    ])
+}
+
+#[test]
+fn firewall_rule_update_leaves_shared_filter_alone() {
+    let rule_path = format!("{}/{}", firewall_rules_path(), RULE_ID);
+    let filters_path = format!("/client/v4/zones/{}/filters", ZONE_ID);
+    let server = MockServer::start(vec![
+        Route::new("GET", &rule_path, 200, "firewall_rule.json"),
+        Route::new("GET", &firewall_rules_path(), 200, "firewall_rules_shared_filter.json"),
+        Route::new("POST", &filters_path, 200, "filters_created.json"),
+        Route::new("PUT", &rule_path, 200, "firewall_rule_updated.json"),
+    ]);
+    let home = temp_home();
+
+    let output = update_rule_expression(&server, &home);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(server.count("PUT", &format!("{}/{}", filters_path, FILTER_ID)), 0);
+    let created: serde_json::Value = serde_json::from_str(&server.requests().iter().find(|r| r.method == "POST").unwrap().body).unwrap();
+    assert_eq!(created[0]["expression"], "ip.src eq 198.51.100.5");
+    let update = server.requests().into_iter().find(|r| r.method == "PUT").unwrap();
+    let body: serde_json::Value = serde_json::from_str(&update.body).unwrap();
+    assert_eq!(body["filter"]["id"], "0a1b2c3d4e5f60718293a4b5c6d7e8f9");
+}
+
+#[test]
+fn firewall_rule_update_changes_own_filter_after_rule() {
+    let rule_path = format!("{}/{}", firewall_rules_path(), RULE_ID);
+    let filter_path = format!("/client/v4/zones/{}/filters/{}", ZONE_ID, FILTER_ID);
+    let server = MockServer::start(vec![
+        Route::new("GET", &rule_path, 200, "firewall_rule.json"),
+        Route::new("GET", &firewall_rules_path(), 200, "firewall_rules_own_filter.json"),
+        Route::new("PUT", &rule_path, 200, "firewall_rule.json"),
+        Route::new("PUT", &filter_path, 200, "filter.json"),
+    ]);
+    let home = temp_home();
+
+    let output = update_rule_expression(&server, &home);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let puts: Vec<String> = server.requests().into_iter().filter(|r| r.method == "PUT").map(|r| r.path).collect();
+    assert_eq!(puts, vec![rule_path, filter_path]);
+}
+
+#[test]
+fn firewall_rule_update_failure_keeps_filter() {
+    let rule_path = format!("{}/{}", firewall_rules_path(), RULE_ID);
+    let server = MockServer::start(vec![
+        Route::new("GET", &rule_path, 200, "firewall_rule.json"),
+        Route::new("GET", &firewall_rules_path(), 200, "firewall_rules_own_filter.json"),
+        Route::new("PUT", &rule_path, 400, "auth_error.json"),
+    ]);
+    let home = temp_home();
+
+    let output = update_rule_expression(&server, &home);
+
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(server.count("PUT", &format!("/client/v4/zones/{}/filters/{}", ZONE_ID, FILTER_ID)), 0);
+}
+
+#[test]
+fn firewall_rules_limit_follows_priority() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &firewall_rules_path(), 200, "firewall_rules_page1.json").times(1),
+        Route::new("GET", &firewall_rules_path(), 200, "firewall_rules_page2.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "firewall", "rules", "list", "--zone-id", ZONE_ID, "--limit", "1", "--per-page", "1",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("6f2b3e0c1d4a5b6c7d8e9f0a1b2c3d4f"));
+    assert!(!stdout.contains(RULE_ID));
+}
+
+#[test]
+fn config_get_contexts_json() {
+    let home = temp_home();
+    write_config(&home, "http://127.0.0.1:1/client/v4");
+
+    let output = cflare(&home, &["-o", "json", "config", "get-contexts"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    let contexts: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(contexts[0]["name"], "mock");
+    assert_eq!(contexts[0]["current"], true);
+    assert_eq!(contexts[0]["auth"], "api_token");
+    assert!(!stdout.contains(TOKEN));
+}
+
+const PAGE_RULE_ID: &str = "9a7806061c88ada191ed06f989cc3dac";
+
+fn page_rules_path() -> String {
+    format!("/client/v4/zones/{}/pagerules", ZONE_ID)
+}
+
+fn page_rule_path(id: &str) -> String {
+    format!("{}/{}", page_rules_path(), id)
+}
+
+#[test]
+fn pagerules_list() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &page_rules_path(), 200, "pagerules.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "pagerules", "list", "--zone-id", ZONE_ID, "--status", "active",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains(PAGE_RULE_ID));
+    assert!(stdout.contains("*example.com/images/*"));
+    assert!(stdout.contains("Always use https; Cache level: cache_everything"));
+    assert!(stdout.contains("Forwarding url (301): https://example.com/new/$1"));
+    let query = server.requests()[0].query.clone().unwrap();
+    assert!(query.contains("status=active"), "{}", query);
+}
+
+#[test]
+fn pagerules_create_from_flags() {
+    let server = MockServer::start(vec![
+        Route::new("POST", &page_rules_path(), 200, "pagerule.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "pagerules", "create", "--zone-id", ZONE_ID, "--url", "*example.com/images/*",
+        "--action", "always_use_https", "--action", "cache_level=cache_everything",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Page rule \"*example.com/images/*\" created"), "{}", stdout(&output));
+    let body: serde_json::Value = serde_json::from_str(&server.requests()[0].body).unwrap();
+    assert_eq!(body["targets"][0]["constraint"]["value"], "*example.com/images/*");
+    assert_eq!(body["actions"][0]["id"], "always_use_https");
+    assert_eq!(body["actions"][1], serde_json::json!({ "id": "cache_level", "value": "cache_everything" }));
+}
+
+#[test]
+fn pagerules_update_changes_only_given_actions() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &page_rule_path(PAGE_RULE_ID), 200, "pagerule.json"),
+        Route::new("PUT", &page_rule_path(PAGE_RULE_ID), 200, "pagerule.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "pagerules", "update", PAGE_RULE_ID, "--zone-id", ZONE_ID,
+        "--action", "ssl=flexible", "--remove-action", "cache_level",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let put = server.requests().into_iter().find(|r| r.method == "PUT").unwrap();
+    let body: serde_json::Value = serde_json::from_str(&put.body).unwrap();
+    assert_eq!(body["actions"], serde_json::json!([{ "id": "always_use_https" }, { "id": "ssl", "value": "flexible" }]));
+    assert_eq!(body["targets"][0]["constraint"]["value"], "*example.com/images/*");
+    assert_eq!(body["priority"], 3);
+}
+
+#[test]
+fn pagerules_reorder_moves_every_rule_out_of_place() {
+    let moved = "7f8e9d0c1b2a3948576a5b4c3d2e1f00";
+    let server = MockServer::start(vec![
+        Route::new("GET", &page_rules_path(), 200, "pagerules.json").times(1),
+        Route::new("GET", &page_rules_path(), 200, "pagerules_reordered.json"),
+        Route::new("PATCH", &page_rule_path(moved), 200, "pagerule.json"),
+        Route::new("PATCH", &page_rule_path(PAGE_RULE_ID), 200, "pagerule.json"),
+        Route::new("PATCH", &page_rule_path("1b2c3d4e5f60718293a4b5c6d7e8f901"), 200, "pagerule.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "pagerules", "reorder", "--zone-id", ZONE_ID, moved,
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let patches: Vec<(String, String)> = server.requests().into_iter()
+        .filter(|r| r.method == "PATCH")
+        .map(|r| (r.path.rsplit('/').next().unwrap().to_string(), r.body))
+        .collect();
+    assert_eq!(patches, vec![
+        (moved.to_string(), r#"{"priority":3}"#.to_string()),
+        (PAGE_RULE_ID.to_string(), r#"{"priority":2}"#.to_string()),
+        ("1b2c3d4e5f60718293a4b5c6d7e8f901".to_string(), r#"{"priority":1}"#.to_string()),
+    ]);
+    assert_eq!(server.count("GET", &page_rules_path()), 2);
+}
+
+#[test]
+fn pagerules_reorder_checks_resulting_order() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &page_rules_path(), 200, "pagerules.json"),
+        Route::new("PATCH", &page_rule_path("7f8e9d0c1b2a3948576a5b4c3d2e1f00"), 200, "pagerule.json"),
+        Route::new("PATCH", &page_rule_path(PAGE_RULE_ID), 200, "pagerule.json"),
+        Route::new("PATCH", &page_rule_path("1b2c3d4e5f60718293a4b5c6d7e8f901"), 200, "pagerule.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "pagerules", "reorder", "--zone-id", ZONE_ID, "7f8e9d0c1b2a3948576a5b4c3d2e1f00",
+    ]);
+
+    assert_eq!(output.status.code(), Some(4));
+    assert!(stderr(&output).contains("requested order"), "{}", stderr(&output));
+    assert_eq!(server.count("GET", &page_rules_path()), 4);
+}
+
+fn purge_cache_path() -> String {
+    format!("/client/v4/zones/{}/purge_cache", ZONE_ID)
+}
+
+// A file of 35 URLs, purged in batches of 30 and 5
+fn write_urls(home: &Path) -> (PathBuf, Vec<String>) {
+    let urls: Vec<String> = (1..=35).map(|i| format!("https://example.com/page/{}", i)).collect();
+    let file = home.join("urls.txt");
+    fs::write(&file, urls.join("\n")).unwrap();
+    (file, urls)
+}
+
+#[test]
+fn cache_purge_in_batches() {
+    let server = MockServer::start(vec![
+        Route::new("POST", &purge_cache_path(), 200, "purge_cache.json"),
+    ]);
+    let home = temp_home();
+    let (file, urls) = write_urls(&home);
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "cache", "purge", "--zone-id", ZONE_ID, "-f", file.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let batches: Vec<serde_json::Value> = server.requests().iter()
+        .map(|r| serde_json::from_str::<serde_json::Value>(&r.body).unwrap()["files"].clone())
+        .collect();
+    assert_eq!(batches, vec![serde_json::json!(urls[..30]), serde_json::json!(urls[30..])]);
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Batch 1/2: purged 30 URLs"), "{}", stdout);
+    assert!(stdout.contains("Batch 2/2: purged 5 URLs"), "{}", stdout);
+}
+
+#[test]
+fn cache_purge_failed_batch_is_partial() {
+    let server = MockServer::start(vec![
+        Route::new("POST", &purge_cache_path(), 200, "purge_cache.json").times(1),
+        Route::new("POST", &purge_cache_path(), 400, "auth_error.json"),
+    ]);
+    let home = temp_home();
+    let (file, _) = write_urls(&home);
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "cache", "purge", "--zone-id", ZONE_ID, "-f", file.to_str().unwrap(),
+    ]);
+
+    assert_eq!(output.status.code(), Some(7));
+    assert_eq!(server.count("POST", &purge_cache_path()), 2);
+    assert!(stdout(&output).contains("Batch 1/2: purged 30 URLs"));
+    assert!(stderr(&output).contains("Batch 2/2 (https://example.com/page/31 to https://example.com/page/35)"), "{}", stderr(&output));
+}
+
+#[test]
+fn zones_delete_confirms_and_forgets_the_zone() {
+    let zone_path = format!("/client/v4/zones/{}", ZONE_ID);
+    let server = MockServer::start(vec![
+        Route::new("GET", "/client/v4/zones", 200, "zones.json"),
+        Route::new("GET", &dns_records_path(), 200, "dns_records.json"),
+        Route::new("GET", &zone_path, 200, "zone.json"),
+        Route::new("DELETE", &zone_path, 200, "zone_identifier.json"),
+    ]);
+    let home = temp_home();
+    write_config(&home, &server.api_url());
+    let output = cflare(&home, &["dns", "list", "--zone", "example.com"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    // Nobody can answer without a terminal
+    let output = cflare(&home, &["zones", "delete", "example.com"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(server.count("DELETE", &zone_path), 0);
+
+    let output = cflare(&home, &["--yes", "zones", "delete", "example.com"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains(&format!("Zone \"{}\" deleted", ZONE_ID)));
+    assert_eq!(server.count("DELETE", &zone_path), 1);
+    assert_eq!(server.count("GET", "/client/v4/zones"), 1);
+
+    // The name is looked up again
+    let output = cflare(&home, &["dns", "list", "--zone", "example.com"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(server.count("GET", "/client/v4/zones"), 2);
+}
+
+#[test]
+fn zones_create_reports_name_servers() {
+    let server = MockServer::start(vec![
+        Route::new("POST", "/client/v4/zones", 200, "zone.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "zones", "create", "example.com", "--account", "01a7362d577a6c3019a474fd6f485823",
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains(&format!("Zone \"example.com\" created with ID {}", ZONE_ID)), "{}", stdout);
+    assert!(stdout.contains("tony.ns.cloudflare.com, woz.ns.cloudflare.com"), "{}", stdout);
+    let body: serde_json::Value = serde_json::from_str(&server.requests()[0].body).unwrap();
+    assert_eq!(body["name"], "example.com");
+    assert_eq!(body["account"]["id"], "01a7362d577a6c3019a474fd6f485823");
+    assert_eq!(body["type"], "full");
+}
+
+#[test]
+fn zones_activation_check() {
+    let server = MockServer::start(vec![
+        Route::new("PUT", &format!("/client/v4/zones/{}/activation_check", ZONE_ID), 200, "zone_identifier.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &["--token", TOKEN, "--api-url", &server.api_url(), "zones", "activation-check", ZONE_ID]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Activation check requested"));
+}
+
+const ACCOUNT_ID: &str = "01a7362d577a6c3019a474fd6f485823";
+const NAMESPACE_ID: &str = "0f2ac74b498b48028cb68387c421e279";
+
+fn kv_value_path(key: &str) -> String {
+    format!("/client/v4/accounts/{}/storage/kv/namespaces/{}/values/{}", ACCOUNT_ID, NAMESPACE_ID, key)
+}
+
+#[test]
+fn kv_put_then_get() {
+    let server = MockServer::start(vec![
+        Route::new("PUT", &kv_value_path("greeting"), 200, "empty_result.json"),
+        Route::new("GET", &kv_value_path("greeting"), 200, "kv_value.txt"),
+    ]);
+    let home = temp_home();
+    let api_url = server.api_url();
+    let kv = |args: &[&str]| {
+        let mut all = vec!["--token", TOKEN, "--api-url", &api_url, "kv", "keys"];
+        all.extend_from_slice(args);
+        cflare(&home, &all)
+    };
+
+    let output = kv(&["put", "-a", ACCOUNT_ID, NAMESPACE_ID, "greeting", "hello world", "--ttl", "120"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let put = &server.requests()[0];
+    assert_eq!(put.body, "hello world");
+    assert_eq!(put.query.as_deref(), Some("expiration_ttl=120"));
+
+    let output = kv(&["get", "-a", ACCOUNT_ID, NAMESPACE_ID, "greeting"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "hello world");
+}
+
+#[test]
+fn kv_put_rejects_short_ttl() {
+    let server = MockServer::start(vec![]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "kv", "keys", "put", "-a", ACCOUNT_ID, NAMESPACE_ID, "greeting", "hello", "--ttl", "5",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("at least 60"), "{}", stderr(&output));
+    assert!(server.requests().is_empty());
+}
+
+fn account_path(suffix: &str) -> String {
+    format!("/client/v4/accounts/{}{}", ACCOUNT_ID, suffix)
+}
+
+#[test]
+fn accounts_describe() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &account_path(""), 200, "account.json"),
+        Route::new("GET", "/client/v4/zones", 200, "account_zones.json"),
+        Route::new("GET", &account_path("/members"), 200, "account_members.json"),
+        Route::new("GET", &account_path("/subscriptions"), 200, "account_subscriptions.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "accounts", "describe", ACCOUNT_ID,
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    // Only one zone is returned, the count comes from result_info
+    assert!(stdout.lines().any(|l| l.starts_with("ZONES") && l.ends_with(" 42")), "{}", stdout);
+    assert!(stdout.contains("John Appleseed"), "{}", stdout);
+    assert!(stdout.contains("Account Administrator"), "{}", stdout);
+    assert!(stdout.contains("Business Plan"), "{}", stdout);
+    assert!(stdout.contains("20.00 USD"), "{}", stdout);
+    let zones = server.requests().into_iter().find(|r| r.path == "/client/v4/zones").unwrap();
+    assert!(zones.query.unwrap().contains(&format!("account.id={}", ACCOUNT_ID)));
+}
+
+#[test]
+fn accounts_describe_without_member_permissions() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &account_path(""), 200, "account.json"),
+        Route::new("GET", "/client/v4/zones", 200, "account_zones.json"),
+        Route::new("GET", &account_path("/members"), 403, "auth_error.json"),
+        Route::new("GET", &account_path("/subscriptions"), 403, "auth_error.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "accounts", "describe", ACCOUNT_ID,
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Demo Account"), "{}", stdout);
+    assert!(stdout.lines().any(|l| l.starts_with("ZONES") && l.ends_with(" 42")), "{}", stdout);
+    assert!(!stdout.contains("John Appleseed"), "{}", stdout);
+    let stderr = stderr(&output);
+    assert!(stderr.contains("Could not fetch members"), "{}", stderr);
+    assert!(stderr.contains("Could not fetch subscriptions"), "{}", stderr);
+}
+
+const LOAD_BALANCER_ID: &str = "699d98642c564d2e855e9661899b7252";
+const POOL_ID: &str = "17b5962d775c646f3f9725cbc7a53df4";
+
+fn load_balancers_path() -> String {
+    format!("/client/v4/zones/{}/load_balancers", ZONE_ID)
+}
+
+fn pools_path() -> String {
+    format!("/client/v4/accounts/{}/load_balancers/pools", ACCOUNT_ID)
+}
+
+#[test]
+fn lb_list() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &load_balancers_path(), 200, "load_balancers.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "lb", "list", "--zone-id", ZONE_ID,
+    ]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains(LOAD_BALANCER_ID));
+    assert!(stdout.contains("www.example.com"));
+    assert!(stdout.contains("api.example.com"));
+    assert!(stdout.contains("dynamic_latency"));
+    assert!(stdout.contains(POOL_ID));
+}
+
+#[test]
+fn lb_delete_with_yes() {
+    let lb_path = format!("{}/{}", load_balancers_path(), LOAD_BALANCER_ID);
+    let server = MockServer::start(vec![
+        Route::new("GET", &load_balancers_path(), 200, "load_balancers.json"),
+        Route::new("DELETE", &lb_path, 200, "zone_identifier.json"),
+    ]);
+    let home = temp_home();
+    write_config(&home, &server.api_url());
+
+    // Nobody can answer without a terminal
+    let output = cflare(&home, &["lb", "delete", "--zone-id", ZONE_ID, "www.example.com"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(server.count("DELETE", &lb_path), 0);
+
+    let output = cflare(&home, &["--yes", "lb", "delete", "--zone-id", ZONE_ID, "www.example.com"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Load balancer \"www.example.com\" deleted"), "{}", stdout(&output));
+    assert_eq!(server.count("DELETE", &lb_path), 1);
+}
+
+#[test]
+fn lb_delete_unknown_name() {
+    let server = MockServer::start(vec![
+        Route::new("GET", &load_balancers_path(), 200, "load_balancers.json"),
+    ]);
+    let home = temp_home();
+
+    let output = cflare(&home, &[
+        "--token", TOKEN, "--api-url", &server.api_url(),
+        "--yes", "lb", "delete", "--zone-id", ZONE_ID, "old.example.com",
+    ]);
+
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Load balancer \"old.example.com\" not found"), "{}", stderr(&output));
+    assert_eq!(server.requests().iter().filter(|r| r.method == "DELETE").count(), 0);
+}
+
+#[test]
+fn lb_pools_list_and_delete() {
+    let pool_path = format!("{}/{}", pools_path(), POOL_ID);
+    let server = MockServer::start(vec![
+        Route::new("GET", &pools_path(), 200, "lb_pools.json"),
+        Route::new("DELETE", &pool_path, 200, "zone_identifier.json"),
+    ]);
+    let home = temp_home();
+    write_config(&home, &server.api_url());
+
+    let output = cflare(&home, &["lb", "pools", "list", "--account", ACCOUNT_ID]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("primary-dc-1"));
+    assert!(stdout.contains("1/2 enabled"));
+
+    let output = cflare(&home, &["--yes", "lb", "pools", "delete", "--account", ACCOUNT_ID, "primary-dc-1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(server.count("DELETE", &pool_path), 1);
+}
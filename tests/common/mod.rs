@@ -0,0 +1,210 @@
+//! A local stand-in for the Cloudflare API, and helpers to run the cflare binary against it.
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Response of the mock server to the requests matching a method and path
+pub struct Route {
+    method: String,
+    path: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+    /// How many requests it still answers, `None` for any number
+    times: Option<usize>,
+}
+
+impl Route {
+    /// Answers `status` with the content of `tests/fixtures/<fixture>`
+    pub fn new(method: &str, path: &str, status: u16, fixture: &str) -> Self {
+        Route {
+            method: method.to_string(),
+            path: path.to_string(),
+            status,
+            headers: vec![],
+            body: read_fixture(fixture),
+            times: None,
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Only answers the first `times` requests, the next ones fall to the following routes
+    pub fn times(mut self, times: usize) -> Self {
+        self.times = Some(times);
+        self
+    }
+}
+
+/// A request received by the mock server
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    /// Names are lowercase
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// HTTP server answering with recorded responses. Routes are tried in the order they were given;
+/// requests matching none are answered with a 404.
+pub struct MockServer {
+    port: u16,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl MockServer {
+    pub fn start(routes: Vec<Route>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(vec![]));
+        let routes = Arc::new(Mutex::new(routes));
+
+        let received = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle(stream, &routes, &received);
+            }
+        });
+
+        MockServer { port, requests }
+    }
+
+    /// Value of `--api-url`
+    pub fn api_url(&self) -> String {
+        format!("http://127.0.0.1:{}/client/v4", self.port)
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Number of requests received for a path
+    pub fn count(&self, method: &str, path: &str) -> usize {
+        self.requests().iter().filter(|r| r.method == method && r.path == path).count()
+    }
+}
+
+fn handle(stream: TcpStream, routes: &Mutex<Vec<Route>>, received: &Mutex<Vec<Request>>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = vec![];
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).is_err() || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+    let mut body = vec![0; content_length];
+    let _ = reader.read_exact(&mut body);
+
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target, None),
+    };
+    received.lock().unwrap().push(Request {
+        method: method.clone(),
+        path: path.clone(),
+        query,
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+    });
+
+    let mut routes = routes.lock().unwrap();
+    let route = routes.iter_mut()
+        .find(|r| r.method == method && r.path == path && r.times != Some(0));
+    let (status, headers, body) = match route {
+        Some(route) => {
+            if let Some(times) = route.times.as_mut() {
+                *times -= 1;
+            }
+            (route.status, route.headers.clone(), route.body.clone())
+        }
+        None => (404, vec![], read_fixture("not_found.json")),
+    };
+    drop(routes);
+
+    let mut response = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        body.len()
+    );
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    response.push_str(&body);
+
+    let mut stream = stream;
+    let _ = stream.write_all(response.as_bytes());
+}
+
+pub fn read_fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+/// An empty home directory, so tests never read the config or cache of the user
+pub fn temp_home() -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = env::temp_dir().join(format!(
+        "cflare-test-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs cflare with `home` as the home directory and no Cloudflare settings from the environment
+pub fn cflare(home: &Path, args: &[&str]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_cflare"));
+    for (name, _) in env::vars() {
+        if name.starts_with("CFLARE_") {
+            command.env_remove(name);
+        }
+    }
+    command.env("HOME", home).args(args).output().expect("run cflare")
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}